@@ -1,20 +1,42 @@
-use crate::{git::Git, github::repo::Repo, rich_edit, MarkdownToken, Node, PackageJSON, SemVer};
+use crate::config::{CommitOrder, CompareBase, Config, InsertPosition, ReferencePlacement};
+use crate::conventional_commit::{classify, ConventionalCommit};
+use crate::error::{ChangelogError, Result};
+use crate::git::dedupe_pull_requests;
+use crate::github::github_info::{entry_reference, entry_title, GitHubInfo};
+use crate::list_format::conjunction;
+use crate::markdown::normalize::{normalize, Tool};
+use crate::markdown::stream::NodeStream;
+use crate::markdown::tokens::{escape_mentions, links, reference_links, strip_emoji, strip_links};
+use crate::output::{output_status, output_warning, table};
+use crate::package::Version;
+use crate::shell::Shell;
+use crate::{
+    git::Git, github::commit::Commit, github::repo::Repo, rich_edit, MarkdownToken, Node,
+    PackageJSON,
+};
 use chrono::prelude::*;
-use color_eyre::eyre::{eyre, Result};
 use colored::*;
+use regex::Regex;
 use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    fmt::Display,
     fs,
+    io::BufReader,
     path::{Path, PathBuf},
     str::FromStr,
 };
 
 const UNRELEASED_HEADING: &str = "Unreleased";
+const NOTHING_YET: &str = "Nothing yet!";
+const OLDER_VERSIONS: &str = "Older versions:";
 
 #[derive(Debug, Clone)]
 pub struct Changelog {
     pwd: PathBuf,
     file_path: PathBuf,
     root: Node,
+    config: Config,
 }
 
 impl Changelog {
@@ -25,11 +47,145 @@ impl Changelog {
             true => fs::read_to_string(&file_path)?.parse()?,
             false => Node::empty(),
         };
+        tracing::debug!(path = %file_path.display(), "read changelog");
+
+        Ok(Changelog {
+            pwd,
+            file_path,
+            root,
+            config: Config::default(),
+        })
+    }
+
+    /// Open a changelog that was generated by another tool, and rewrite it into the structure of
+    /// this tool. The rewritten changelog isn't persisted yet. Versions without a link get one
+    /// based on the configured git remote.
+    pub fn import(pwd: &Path, filename: &str, tool: Tool, config: &Config) -> Result<Self> {
+        let pwd = fs::canonicalize(pwd)?;
+        let file_path = pwd.join(filename);
+
+        if !file_path.is_file() {
+            return Err(ChangelogError::NotFound(format!(
+                "Couldn't find changelog file: {}",
+                file_path.display()
+            )));
+        }
+
+        let url = Repo::from_remote(&pwd, &config.remote)
+            .ok()
+            .map(|repo| repo.url());
+        let root = normalize(
+            &fs::read_to_string(&file_path)?,
+            tool,
+            config,
+            url.as_deref(),
+        )
+        .parse()?;
+        tracing::debug!(path = %file_path.display(), ?tool, "imported changelog");
+
+        Ok(Changelog {
+            pwd,
+            file_path,
+            root,
+            config: config.clone(),
+        })
+    }
+
+    /// Open a changelog by the path of its file, e.g.: a changelog outside of the project
+    pub fn from_file(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Err(ChangelogError::NotFound(format!(
+                "Couldn't find changelog file: {}",
+                path.display()
+            )));
+        }
+
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Changelog::new(
+            path.parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or_else(|| Path::new(".")),
+            &filename,
+        )
+    }
+
+    pub fn with_config(mut self, config: &Config) -> Self {
+        self.config = config.clone();
+        self
+    }
+
+    /// Parse the changelog lazily, and stop reading the file as soon as `done` returns true for
+    /// the nodes that are parsed so far. This is useful for read-only commands on big changelog
+    /// files, the resulting changelog only contains a part of the file so it should never be
+    /// persisted.
+    fn new_lazy<F>(pwd: &Path, filename: &str, mut done: F) -> Result<Self>
+    where
+        F: FnMut(&Node) -> bool,
+    {
+        let pwd = fs::canonicalize(pwd)?;
+        let file_path = pwd.join(filename);
+        let mut root = Node::empty();
+
+        if std::fs::metadata(&file_path).is_ok() {
+            let reader = BufReader::new(fs::File::open(&file_path)?);
+
+            let mut nodes = NodeStream::new(reader).peekable();
+            while let Some(node) = nodes.next() {
+                let node = node?;
+                let is_done = done(&node);
+                root.add_child(node);
+
+                if is_done {
+                    // The definitions of the reference-style links of the last section follow it
+                    while let Some(Ok(Node {
+                        data: Some(MarkdownToken::Reference(_, _)),
+                        ..
+                    })) = nodes.peek()
+                    {
+                        root.add_child(nodes.next().unwrap()?);
+                    }
+                    break;
+                }
+            }
+            tracing::debug!(path = %file_path.display(), "read changelog lazily");
+        }
 
         Ok(Changelog {
             pwd,
             file_path,
             root,
+            config: Config::default(),
+        })
+    }
+
+    /// Read the changelog until the notes of the given version are found
+    pub fn for_notes(pwd: &Path, filename: &str, version: Option<&String>) -> Result<Self> {
+        // The unreleased section might only hold placeholders, so the latest release is read
+        // as well, which also covers the unreleased section before it
+        let latest = "latest".to_string();
+        let version = version.or(Some(&latest));
+
+        Self::new_lazy(pwd, filename, |node| {
+            node.find_node(|node| is_section(node, version, None, &Config::default()))
+                .is_some()
+        })
+    }
+
+    /// Read the changelog until the requested amount of releases are found
+    pub fn for_list(pwd: &Path, filename: &str, amount: Amount) -> Result<Self> {
+        let mut releases = 0;
+
+        Self::new_lazy(pwd, filename, |node| {
+            releases += node.filter_nodes(is_version_reference).len();
+
+            match amount {
+                Amount::All => false,
+                Amount::Value(x) => releases >= x,
+            }
         })
     }
 
@@ -44,38 +200,79 @@ impl Changelog {
     }
 
     pub fn unreleased_heading(&self, scope: Option<&PackageJSON>) -> String {
-        match scope {
-            Some(scope) => format!("[{} - {}]", UNRELEASED_HEADING, scope.name()),
-            None => format!("[{}]", UNRELEASED_HEADING),
-        }
+        unreleased_heading(scope)
     }
 
-    pub fn init(&mut self) -> Result<String> {
-        let meta = fs::metadata(&self.file_path);
+    /// Create a new changelog. When `ensure` is set, the unreleased section is scaffolded, and an
+    /// existing changelog gets a scaffolded unreleased section when it doesn't have one. When
+    /// `force` is set, an existing changelog is overwritten, after backing it up.
+    pub fn init(&mut self, options: &InitOptions) -> Result<String> {
+        let ensure = options.ensure;
+        let exists = fs::metadata(&self.file_path).is_ok();
+
+        if exists && !options.force {
+            if ensure {
+                return self.ensure_unreleased();
+            }
 
-        if meta.is_ok() {
             return Ok(format!(
                 "Changelog already exists at: {}",
                 &self.relative_path()?.white().dimmed()
             ));
         }
 
-        if !Git::new(Some(&self.pwd))?.is_git_repo() {
-            return Ok(format!(
-                "Not a git repository: {}",
-                self.pwd.to_str().unwrap().white().dimmed()
-            ));
+        let date = Local::now().format("%Y-%m-%d");
+
+        // Seed the references at the bottom with the url of the remote, without a remote (or when
+        // the remotes can't be listed) there is nothing to link to yet
+        let remote = Repo::from_remote(&self.pwd, &self.config.remote).ok();
+        let template = include_str!("./fixtures/changelog.md").to_string();
+        let template = match remote {
+            Some(remote) => template.replace("<url>", &remote.url()),
+            None => template
+                .lines()
+                .filter(|line| !line.contains("<url>"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+
+        self.root = template.replace("<date>", &date.to_string()).parse()?;
+
+        if ensure {
+            let contents = unreleased_scaffold(&self.config, true);
+            let unreleased_heading = self.unreleased_heading(None);
+
+            if let Some(unreleased) = self.root.find_node_mut(|node| match &node.data {
+                Some(MarkdownToken::H2(name)) => {
+                    strip_heading_link(name).eq_ignore_ascii_case(&unreleased_heading)
+                }
+                _ => false,
+            }) {
+                unreleased.children = contents;
+            }
         }
 
-        let date = Local::now().format("%Y-%m-%d");
-        let repo = Repo::from_git_repo(&self.pwd)?;
+        if exists {
+            let backup = match options.no_backup {
+                true => String::new(),
+                false => {
+                    let backup_path = self.backup_path();
+                    fs::copy(&self.file_path, &backup_path)?;
+                    format!(
+                        ", the old one was backed up to: {}",
+                        format!("{}.bak", self.relative_path()?).white().dimmed()
+                    )
+                }
+            };
 
-        self.root = include_str!("./fixtures/changelog.md")
-            .to_string()
-            .replace("<date>", &date.to_string())
-            .replace("<owner>", &repo.org)
-            .replace("<repo>", &repo.repo)
-            .parse()?;
+            self.persist()?;
+
+            return Ok(format!(
+                "Overwrote changelog file at: {}{}",
+                &self.relative_path()?.white().dimmed(),
+                backup
+            ));
+        }
 
         self.persist().map(|_| {
             format!(
@@ -85,17 +282,80 @@ impl Changelog {
         })
     }
 
+    /// Whether the changelog file exists
+    pub fn exists(&self) -> bool {
+        self.file_path.is_file()
+    }
+
+    /// The path of the backup of an overwritten changelog, e.g.: `CHANGELOG.md.bak`
+    fn backup_path(&self) -> PathBuf {
+        let mut path = self.file_path.clone().into_os_string();
+        path.push(".bak");
+        PathBuf::from(path)
+    }
+
+    /// Whether the changelog has an unreleased section, e.g.: not after it was fully released
+    fn has_unreleased(&self) -> bool {
+        let unreleased_heading = self.unreleased_heading(None);
+
+        self.root
+            .find_node(|node| match &node.data {
+                Some(MarkdownToken::H2(name)) => {
+                    strip_heading_link(name).eq_ignore_ascii_case(&unreleased_heading)
+                }
+                _ => false,
+            })
+            .is_some()
+    }
+
+    /// Add a scaffolded unreleased section to an existing changelog, when it doesn't have one
+    fn ensure_unreleased(&mut self) -> Result<String> {
+        let unreleased_heading = self.unreleased_heading(None);
+
+        if self.has_unreleased() {
+            return Ok(format!(
+                "Changelog already has an unreleased section at: {}",
+                &self.relative_path()?.white().dimmed()
+            ));
+        }
+
+        let mut unreleased = Node::from_token(MarkdownToken::H2(unreleased_heading));
+        unreleased.children = unreleased_scaffold(&self.config, true);
+
+        insert_section(
+            self.root.children.get_mut(0).ok_or_else(|| {
+                ChangelogError::ParseError(
+                    "Couldn't find main heading, is your CHANGELOG.md formatted correctly?"
+                        .to_string(),
+                )
+            })?,
+            unreleased,
+        );
+
+        self.persist()?;
+
+        Ok(format!(
+            "Added an unreleased section to: {}",
+            &self.relative_path()?.white().dimmed()
+        ))
+    }
+
+    /// Remove the emoji from all entries, e.g.: of an imported changelog
+    pub fn strip_emoji(&mut self) {
+        self.root.strip_emoji();
+    }
+
     pub fn persist(&self) -> Result<()> {
-        fs::write(&self.file_path, self.root.to_string() + "\n").map_err(|e| eyre!(e))
+        tracing::debug!(path = %self.file_path.display(), "write changelog");
+        fs::write(&self.file_path, self.root.to_string() + "\n").map_err(ChangelogError::from)
     }
 
     fn find_latest_version(&self) -> Option<&str> {
-        if let Some(node) = self.root.find_node(|node| {
-            if let Some(MarkdownToken::Reference(name, _)) = &node.data {
-                !name.to_lowercase().starts_with("unreleased")
-            } else {
-                false
+        if let Some(node) = self.root.find_node(|node| match &node.data {
+            Some(MarkdownToken::Reference(name, _)) => {
+                is_version_reference(node) && !name.to_lowercase().starts_with("unreleased")
             }
+            _ => false,
         }) {
             if let Some(MarkdownToken::Reference(name, _)) = &node.data {
                 return Some(name);
@@ -109,74 +369,42 @@ impl Changelog {
     fn add_list_item_to_section_scope(
         &mut self,
         section_name: &str,
+        under: Option<&str>,
         item: String,
         scope: Option<&PackageJSON>,
     ) {
+        let item = match self.config.normalize_entries {
+            true => normalize_entry(&item),
+            false => item,
+        };
+        let item = match self.config.gitmoji.prepend {
+            true => self.config.gitmoji.decorate(section_name, &item),
+            false => item,
+        };
+        let item = self.config.decorate_entry(&item);
+
         let unreleased_heading = self.unreleased_heading(scope);
         let unreleased = self.root.find_node_mut(|node| match &node.data {
-            Some(MarkdownToken::H2(name)) => name.eq_ignore_ascii_case(&unreleased_heading),
+            Some(MarkdownToken::H2(name)) => {
+                strip_heading_link(name).eq_ignore_ascii_case(&unreleased_heading)
+            }
             _ => false,
         });
 
         if let Some(unreleased) = unreleased {
-            // Search for the "Nothing yet!" note, and delete it if it exists.
-            let nothing_yet_ul = unreleased
-                .children
-                .iter_mut()
-                .position(|node| matches!(&node.data, Some(MarkdownToken::UnorderedList)));
-
-            if let Some(nothing_yet_ul) = nothing_yet_ul {
-                unreleased.children.remove(nothing_yet_ul);
-            }
-
-            let section = unreleased.find_node_mut(|node| match &node.data {
-                Some(MarkdownToken::H3(name)) => name.eq_ignore_ascii_case(section_name),
-                _ => false,
-            });
-
-            if let Some(section) = section {
-                let ul = section
-                    .find_node_mut(|node| matches!(&node.data, Some(MarkdownToken::UnorderedList)));
-
-                if let Some(ul) = ul {
-                    let li = Node::from_token(MarkdownToken::ListItem(item, 0));
-
-                    ul.add_child(li);
-                } else {
-                    let mut ul = Node::from_token(MarkdownToken::UnorderedList);
-                    let li = Node::from_token(MarkdownToken::ListItem(item, 0));
-
-                    ul.add_child(li);
-
-                    section.add_child(ul);
-                }
-            } else {
-                let mut h3 = Node::from_token(MarkdownToken::H3(section_name.to_string()));
-                let mut ul = Node::from_token(MarkdownToken::UnorderedList);
-                let li = Node::from_token(MarkdownToken::ListItem(item, 0));
-
-                ul.add_child(li);
-                h3.add_child(ul);
-
-                unreleased.add_child(h3);
-            }
+            add_list_item_to_version(unreleased, section_name, under, item, &self.config);
         } else {
             let unreleased_heading = self.unreleased_heading(scope);
             let mut section = Node::from_token(MarkdownToken::H2(unreleased_heading));
-            let mut h3 = Node::from_token(MarkdownToken::H3(section_name.to_string()));
-            let mut ul = Node::from_token(MarkdownToken::UnorderedList);
-            let li = Node::from_token(MarkdownToken::ListItem(item, 0));
-
-            ul.add_child(li);
-            h3.add_child(ul);
-            section.add_child(h3);
+            add_list_item_to_version(&mut section, section_name, under, item, &self.config);
 
             // Insert "Unreleased" section
-            self.root
-                .children
-                .get_mut(0)
-                .expect("Couldn't find main heading, is your CHANGELOG.md formatted correctly?")
-                .add_child_at(2, section);
+            insert_section(
+                self.root.children.get_mut(0).expect(
+                    "Couldn't find main heading, is your CHANGELOG.md formatted correctly?",
+                ),
+                section,
+            );
         }
     }
 
@@ -219,9 +447,23 @@ impl Changelog {
         item: &str,
         edit: bool,
         scope: Option<&PackageJSON>,
+    ) {
+        self.add_list_item_to_section_under(section_name, None, item, edit, scope);
+    }
+
+    /// Add an entry to a section, below the optional sub-heading (e.g.: "#### API") of that
+    /// section. The sub-heading is created when it doesn't exist yet.
+    pub fn add_list_item_to_section_under(
+        &mut self,
+        section_name: &str,
+        under: Option<&str>,
+        item: &str,
+        edit: bool,
+        scope: Option<&PackageJSON>,
     ) {
         self.add_list_item_to_section_scope(
             section_name,
+            under,
             match edit {
                 true => match self.edit(section_name, item, scope) {
                     Some(data) => data,
@@ -233,45 +475,121 @@ impl Changelog {
         );
     }
 
+    /// Add an entry to a section of a released version instead of the unreleased section, e.g.: to
+    /// backfill a change that was forgotten before the version was published
+    pub fn add_list_item_to_released_version(
+        &mut self,
+        version: &str,
+        section_name: &str,
+        item: &str,
+    ) -> Result<()> {
+        let item = match self.config.normalize_entries {
+            true => normalize_entry(item),
+            false => item.to_string(),
+        };
+        let item = match self.config.gitmoji.prepend {
+            true => self.config.gitmoji.decorate(section_name, &item),
+            false => item,
+        };
+        let item = self.config.decorate_entry(&item);
+
+        let name = version.to_string();
+        let node = self
+            .root
+            .find_node_mut(|node| is_section(node, Some(&name), None, &self.config))
+            .ok_or_else(|| ChangelogError::NotFound(format!("Couldn't find version: {}", name)))?;
+
+        add_list_item_to_version(node, section_name, None, item, &self.config);
+
+        Ok(())
+    }
+
+    /// Add an entry for every commit in the given range to the unreleased section, based on the
+    /// conventional commit type of each commit, in the configured commit order. Returns the amount
+    /// of entries that were added.
+    pub fn add_commits(
+        &mut self,
+        from: Option<&str>,
+        to: &str,
+        scope: Option<&PackageJSON>,
+        placement: ReferencePlacement,
+    ) -> Result<usize> {
+        let git = Git::new(Some(&self.pwd))?;
+        let from = match from {
+            Some(from) => Some(from.to_string()),
+            None => git.latest_tag(to, &self.config.tag("", scope)).ok(),
+        };
+
+        // Only limit the log to the package directory in monorepos, because filtering on a path
+        // hides merge commits that we want to use for deduplication.
+        let path = scope.and_then(|_| self.pwd.to_str());
+        let entries = git.log_range(from.as_deref(), to, path)?;
+        let mut items = vec![];
+
+        for entry in dedupe_pull_requests(entries) {
+            // Strip the emoji before classifying, e.g.: "✨ feat: ..." is a conventional commit
+            let title = match self.config.strip_emoji {
+                true => strip_emoji(entry.title()),
+                false => entry.title().to_string(),
+            };
+            let classified = match self.config.gitmoji.detect {
+                true => self
+                    .config
+                    .gitmoji
+                    .classify(entry.title())
+                    .map(|(section, rest)| {
+                        // Also support emoji in front of a conventional commit, e.g.: "✨ feat: ..."
+                        let description = ConventionalCommit::parse(&rest)
+                            .map(|commit| commit.description)
+                            .unwrap_or(rest);
+                        (section, description)
+                    }),
+                false => None,
+            }
+            .or_else(|| {
+                classify(&title).map(|(section, description)| (section.to_string(), description))
+            });
+
+            if let Some((section, description)) = classified {
+                let description = match self.config.strip_emoji {
+                    true => strip_emoji(&description),
+                    false => description,
+                };
+                let commit =
+                    Commit::from_local_commit(&self.pwd, &entry.hash)?.with_title(&description);
+                let item = GitHubInfo::Commit(commit).render(placement, self.config.link_format);
+
+                items.push((
+                    entry.date,
+                    section,
+                    match self.config.escape_mentions {
+                        true => escape_mentions(&item),
+                        false => item,
+                    },
+                ));
+            }
+        }
+
+        if self.config.commit_order == CommitOrder::Date {
+            items.sort_by_key(|(date, _, _)| *date);
+        }
+
+        let added = items.len();
+        for (_, section, item) in items {
+            self.add_list_item_to_section(&section, &item, false, scope);
+        }
+
+        Ok(added)
+    }
+
     pub fn get_contents_of_section_scope(
         &self,
         name: Option<&String>,
         scope: Option<&PackageJSON>,
     ) -> Option<Node> {
-        let node = self.root.find_node(|node| {
-            if let Some(MarkdownToken::H2(section_name)) = &node.data {
-                match name {
-                    Some(name) => {
-                        if name.eq_ignore_ascii_case("latest") {
-                            !section_name.eq_ignore_ascii_case(&self.unreleased_heading(scope))
-                        } else {
-                            match scope {
-                                Some(scope) if !scope.is_root() => {
-                                    section_name.to_lowercase().starts_with(&format!(
-                                        "[{}@v{}]",
-                                        scope.name(),
-                                        name.to_lowercase()
-                                    ))
-                                }
-                                _ => section_name
-                                    .to_lowercase()
-                                    .starts_with(&format!("[{}]", name.to_lowercase())),
-                            }
-                        }
-                    }
-                    None => {
-                        if section_name.eq_ignore_ascii_case(&self.unreleased_heading(scope)) {
-                            node.find_node(|node| matches!(&node.data, Some(MarkdownToken::H3(_))))
-                                .is_some()
-                        } else {
-                            true
-                        }
-                    }
-                }
-            } else {
-                false
-            }
-        });
+        let node = self
+            .root
+            .find_node(|node| is_section(node, name, scope, &self.config));
 
         if let Some(node) = node {
             let mut copy = node.clone();
@@ -287,120 +605,499 @@ impl Changelog {
         self.get_contents_of_section_scope(name.as_ref(), None)
     }
 
-    fn notes_scope(&self, version: Option<&String>, scope: Option<&PackageJSON>) -> Result<String> {
-        Ok(
-            if let Some(node) = self.get_contents_of_section_scope(version, scope) {
-                node.to_string()
-            } else {
-                match version {
-                    Some(version) => format!(
-                        "Couldn't find notes for version: {} {}",
-                        version.blue().bold(),
-                        scope
-                            .map(|scope| format!("({})", scope.name().white().dimmed()))
-                            .unwrap_or_default()
-                    ),
-                    None => format!(
-                        "Couldn't find notes for version: {} {}",
-                        "<unknown>".blue().bold(),
-                        scope
-                            .map(|scope| format!("({})", scope.name().white().dimmed()))
-                            .unwrap_or_default()
-                    ),
-                }
-            },
-        )
+    pub fn notes_scope(
+        &self,
+        version: Option<&String>,
+        scope: Option<&PackageJSON>,
+        options: &NotesOptions,
+    ) -> Result<String> {
+        let mut node = self
+            .get_contents_of_section_scope(version, scope)
+            .ok_or_else(|| {
+                ChangelogError::NotFound(format!(
+                    "Couldn't find notes for version: {} {}",
+                    version.map_or("<unknown>", |version| version).blue().bold(),
+                    scope
+                        .map(|scope| format!("({})", scope.name().white().dimmed()))
+                        .unwrap_or_default()
+                ))
+            })?;
+
+        match options.group_by {
+            GroupBy::Type => group_by_type(&mut node),
+            GroupBy::Section if options.include_empty_sections => {
+                add_empty_sections(&mut node, &self.config)
+            }
+            GroupBy::Section => {}
+        }
+        if options.strip_links {
+            node.strip_links();
+        }
+
+        Ok(node.to_string())
     }
 
-    pub fn notes(&self, version: Option<&String>) -> Result<String> {
-        self.notes_scope(version, None)
+    pub fn notes(&self, version: Option<&String>, options: &NotesOptions) -> Result<String> {
+        self.notes_scope(version, None, options)
     }
 
-    pub fn list(&self, amount: Amount) -> Result<String> {
-        let releases = self
-            .root
-            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::Reference(_, _))))
-            .iter()
-            .filter_map(|node| node.data.as_ref())
-            .take(match amount {
-                Amount::All => std::usize::MAX,
-                Amount::Value(x) => x,
+    /// The combined notes of the released versions with a date in the given range, e.g.: for
+    /// monthly reports. `after` is inclusive and `before` is exclusive.
+    pub fn notes_between(
+        &self,
+        after: Option<NaiveDate>,
+        before: Option<NaiveDate>,
+        scope: Option<&PackageJSON>,
+        options: &NotesOptions,
+    ) -> String {
+        let prefix = match scope {
+            Some(scope) if !scope.is_root() => format!("{}@", scope.name()),
+            _ => String::new(),
+        };
+
+        let notes: Vec<String> = self
+            .versions()
+            .into_iter()
+            .filter(|node| version_name(node, &self.config).starts_with(&prefix))
+            .filter(|node| {
+                match section_date(node, &self.config)
+                    .and_then(|date| date.parse::<NaiveDate>().ok())
+                {
+                    Some(date) => {
+                        after.is_none_or(|after| date >= after)
+                            && before.is_none_or(|before| date < before)
+                    }
+                    None => false,
+                }
             })
-            .map(|token| match token {
-                MarkdownToken::Reference(name, link) => format!("- {:15} {}", name, link),
-                _ => panic!("Expected a reference"),
+            .map(|node| {
+                let mut node = node.clone();
+                match options.group_by {
+                    GroupBy::Type => group_by_type(&mut node),
+                    GroupBy::Section if options.include_empty_sections => {
+                        add_empty_sections(&mut node, &self.config)
+                    }
+                    GroupBy::Section => {}
+                }
+                if options.strip_links {
+                    node.strip_links();
+                }
+
+                node.to_string().trim_end().to_string()
             })
-            .collect::<Vec<_>>()
-            .join("\n");
+            .collect();
 
-        if releases.is_empty() {
-            Ok("There are no releases yet.".to_string())
-        } else {
-            Ok(releases)
+        match notes.is_empty() {
+            true => format!(
+                "Couldn't find versions released between: {} and {}",
+                after
+                    .map_or("<any>".to_string(), |date| date.to_string())
+                    .blue()
+                    .bold(),
+                before
+                    .map_or("<any>".to_string(), |date| date.to_string())
+                    .blue()
+                    .bold()
+            ),
+            false => notes.join("\n\n"),
         }
     }
 
-    pub fn release(&mut self, version: &SemVer, scope: Option<&PackageJSON>) -> Result<()> {
-        let date = Local::now().format("%Y-%m-%d");
-
-        let unreleased_heading = self.unreleased_heading(None);
+    /// Whether the version has an entry that contains the text of `list --contains`, ignoring the
+    /// case. Every version matches when there is no text.
+    fn version_contains(&self, version: &str, options: &ListOptions) -> bool {
+        let text = match &options.contains {
+            Some(text) => text.to_lowercase(),
+            None => return true,
+        };
 
-        if let Some(unreleased) = self.root.find_node_mut(|node| {
-            if let Some(MarkdownToken::H2(name)) = &node.data {
-                name.eq_ignore_ascii_case(&unreleased_heading)
-            } else {
-                false
-            }
-        }) {
-            // Convert to the new version
-            unreleased.rename_heading(&format!("[{}] - {}", version, date));
+        self.entries(Some(&version.to_string()), None)
+            .unwrap_or_default()
+            .iter()
+            .any(|(_, entry)| entry.to_lowercase().contains(&text))
+    }
 
-            // Insert new [Unreleased] section at the top
-            let mut new_unreleased =
-                Node::from_token(MarkdownToken::H2(unreleased_heading.clone()));
-            let mut ul = Node::from_token(MarkdownToken::UnorderedList);
-            let li = Node::from_token(MarkdownToken::ListItem("Nothing yet!".to_string(), 0));
+    /// The entries of a version with the name of their section, e.g.: for `notes --porcelain`.
+    /// Entries outside of a section have an empty section name.
+    pub fn entries(
+        &self,
+        version: Option<&String>,
+        scope: Option<&PackageJSON>,
+    ) -> Option<Vec<(String, String)>> {
+        let node = self.get_contents_of_section_scope(version, scope)?;
 
-            ul.add_child(li);
-            new_unreleased.add_child(ul);
+        Some(self.version_entries(&node))
+    }
 
-            self.root
-                .children
-                .get_mut(0)
-                .expect("Couldn't find main heading, is your CHANGELOG.md formatted correctly?")
-                .add_child_at(2, new_unreleased);
+    /// The entries of a version node with the name of their section
+    fn version_entries(&self, version: &Node) -> Vec<(String, String)> {
+        version
+            .children
+            .iter()
+            .flat_map(|child| {
+                let section = match &child.data {
+                    Some(MarkdownToken::H3(name)) => name.as_str(),
+                    _ => "",
+                };
 
-            // Update references at the bottom
-            let c = self.clone();
-            match c.find_latest_version() {
-                Some(old_version) => {
-                    if let Some(unreleased_reference) =
-                        self.root.find_node_mut(|node| match &node.data {
-                            Some(MarkdownToken::Reference(name, _)) => name.eq_ignore_ascii_case(
-                                &unreleased_heading[1..unreleased_heading.len() - 1],
-                            ),
-                            _ => false,
-                        })
-                    {
-                        if let Some(MarkdownToken::Reference(name, link)) =
-                            &unreleased_reference.data
+                child
+                    .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::ListItem(_, _))))
+                    .into_iter()
+                    .filter_map(move |node| match &node.data {
+                        Some(MarkdownToken::ListItem(text, _))
+                            if text != NOTHING_YET
+                                && !is_placeholder(&self.config, Some(section), text) =>
                         {
-                            let (updated_link, new_link) = (
-                                link.clone().replace(old_version, &version.to_string()),
-                                link.clone().replace(
-                                    "HEAD",
-                                    &match scope {
-                                        Some(scope) if !scope.is_root() => {
-                                            format!("{}@v{}", scope.name(), version)
-                                        }
-                                        _ => format!("v{}", version),
-                                    },
-                                ),
-                            );
+                            Some((section.to_string(), text.clone()))
+                        }
+                        _ => None,
+                    })
+            })
+            .collect()
+    }
 
-                            // Update unreleased_reference
-                            unreleased_reference.data =
-                                Some(MarkdownToken::Reference(name.to_string(), updated_link));
+    /// The entries that match the pattern with the name of their section, grouped by version
+    /// (including the unreleased section), e.g.: for `search`. The `context` amount of entries
+    /// before and after every match (within the same version) are included as well.
+    pub fn search(&self, pattern: &Regex, context: usize) -> Vec<(String, Vec<(String, String)>)> {
+        self.versions()
+            .into_iter()
+            .filter_map(|version| {
+                let entries = self.version_entries(version);
+                let matches: Vec<usize> = entries
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, entry))| pattern.is_match(entry))
+                    .map(|(idx, _)| idx)
+                    .collect();
+
+                let entries: Vec<_> = entries
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(idx, _)| {
+                        matches
+                            .iter()
+                            .any(|matched| matched.abs_diff(*idx) <= context)
+                    })
+                    .map(|(_, entry)| entry)
+                    .collect();
+
+                (!entries.is_empty())
+                    .then(|| (version_name(version, &self.config).to_string(), entries))
+            })
+            .collect()
+    }
+
+    /// Count the entries of a version, in total and per section
+    pub fn count(
+        &self,
+        version: Option<&String>,
+        scope: Option<&PackageJSON>,
+    ) -> Option<EntryCount> {
+        let node = self.get_contents_of_section_scope(version, scope)?;
+        let is_entry = |node: &Node| match &node.data {
+            Some(MarkdownToken::ListItem(text, 0)) => !is_placeholder(&self.config, None, text),
+            _ => false,
+        };
+
+        let sections: Vec<(String, usize)> = node
+            .children
+            .iter()
+            .filter_map(|child| match &child.data {
+                Some(MarkdownToken::H3(name)) => {
+                    Some((name.to_string(), child.filter_nodes(is_entry).len()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        Some(EntryCount {
+            total: node.filter_nodes(is_entry).len(),
+            sections,
+        })
+    }
+
+    /// The bump that the unreleased entries call for: "major" for removals and breaking changes,
+    /// "minor" for additions, changes and deprecations, and "patch" otherwise
+    pub fn suggested_bump(&self, scope: Option<&PackageJSON>) -> &'static str {
+        let entries = self.unreleased_entries(scope);
+        let any = |sections: &[&str]| {
+            entries.iter().any(|(section, _)| {
+                sections
+                    .iter()
+                    .any(|name| section.eq_ignore_ascii_case(name))
+            })
+        };
+
+        if any(&["Removed"]) || entries.iter().any(|(_, entry)| entry.contains("BREAKING")) {
+            "major"
+        } else if any(&["Added", "Changed", "Deprecated"]) {
+            "minor"
+        } else {
+            "patch"
+        }
+    }
+
+    /// A nudge to release a new version, when the unreleased section has more entries than the
+    /// configured maximum
+    pub fn release_reminder(&self, scope: Option<&PackageJSON>) -> Option<String> {
+        let max_entries = self.config.max_entries?;
+
+        // The unreleased section of the scope, or the shared one of the changelog
+        let total = [
+            self.unreleased_heading(scope),
+            self.unreleased_heading(None),
+        ]
+        .iter()
+        .find_map(|heading| self.count(Some(&heading[1..heading.len() - 1].to_string()), None))?
+        .total;
+
+        (total > max_entries).then(|| {
+            format!(
+                "The unreleased section has {} entries (more than {}), consider releasing a new version",
+                total, max_entries
+            )
+        })
+    }
+
+    /// The released versions with their date, selected like `list`, e.g.: for `list --porcelain`.
+    /// The unreleased section is only included when asked for.
+    pub fn list_versions(
+        &self,
+        amount: Amount,
+        options: &ListOptions,
+    ) -> Vec<(String, Option<String>)> {
+        let released: Vec<&Node> = self
+            .versions()
+            .into_iter()
+            .filter(|node| {
+                !version_name(node, &self.config)
+                    .to_lowercase()
+                    .starts_with(&UNRELEASED_HEADING.to_lowercase())
+                    && self.version_contains(version_name(node, &self.config), options)
+            })
+            .collect();
+        let amount = match amount {
+            Amount::All => released.len(),
+            Amount::Value(x) => x.min(released.len()),
+        };
+        let selected = match options.oldest {
+            true => &released[released.len() - amount..],
+            false => &released[..amount],
+        };
+
+        let mut versions: Vec<_> = selected
+            .iter()
+            .map(|node| {
+                (
+                    version_name(node, &self.config).to_string(),
+                    section_date(node, &self.config).map(|date| date.to_string()),
+                )
+            })
+            .collect();
+
+        if options.include_unreleased && self.version_contains(UNRELEASED_HEADING, options) {
+            versions.insert(0, (UNRELEASED_HEADING.to_lowercase(), None));
+        }
+
+        if options.reverse {
+            versions.reverse();
+        }
+
+        versions
+    }
+
+    /// List the references of the versions. When including the unreleased section, it is listed
+    /// at the top with its amount of entries instead of its reference.
+    pub fn list(&self, amount: Amount, options: &ListOptions) -> Result<String> {
+        let include_unreleased = options.include_unreleased;
+        let references: Vec<&MarkdownToken> = self
+            .root
+            .filter_nodes(is_version_reference)
+            .iter()
+            .filter_map(|node| node.data.as_ref())
+            .filter(|token| match token {
+                MarkdownToken::Reference(name, _) => {
+                    (!include_unreleased || !name.eq_ignore_ascii_case(UNRELEASED_HEADING))
+                        && self.version_contains(name, options)
+                }
+                _ => true,
+            })
+            .collect();
+        let amount = match amount {
+            Amount::All => references.len(),
+            Amount::Value(x) => x.min(references.len()),
+        };
+        let selected = match options.oldest {
+            true => &references[references.len() - amount..],
+            false => &references[..amount],
+        };
+
+        let mut releases = selected
+            .iter()
+            .map(|token| match token {
+                MarkdownToken::Reference(name, link) => (
+                    name.to_string(),
+                    self.find_version(name)
+                        .and_then(|node| section_date(node, &self.config))
+                        .map(|date| date.to_string()),
+                    link.to_string(),
+                ),
+                _ => panic!("Expected a reference"),
+            })
+            .collect::<Vec<_>>();
+
+        if include_unreleased && self.version_contains(UNRELEASED_HEADING, options) {
+            let total = self
+                .count(Some(&UNRELEASED_HEADING.to_string()), None)
+                .map(|count| count.total)
+                .unwrap_or_default();
+
+            releases.insert(
+                0,
+                (
+                    UNRELEASED_HEADING.to_lowercase(),
+                    None,
+                    format!(
+                        "— ({} {})",
+                        total,
+                        if total == 1 { "entry" } else { "entries" }
+                    ),
+                ),
+            );
+        }
+
+        if options.reverse {
+            releases.reverse();
+        }
+
+        if releases.is_empty() {
+            return Ok(match &options.contains {
+                Some(text) => format!("There are no releases that mention \"{}\".", text),
+                None => "There are no releases yet.".to_string(),
+            });
+        }
+
+        // Only show the date column when there are dates
+        let dates = releases.iter().any(|(_, date, _)| date.is_some());
+        let rows: Vec<_> = releases
+            .into_iter()
+            .map(|(name, date, link)| match dates {
+                true => vec![format!("- {}", name), date.unwrap_or_default(), link],
+                false => vec![format!("- {}", name), link],
+            })
+            .collect();
+
+        Ok(table(&rows, options.max_width))
+    }
+
+    pub fn release(
+        &mut self,
+        version: &Version,
+        scope: Option<&PackageJSON>,
+        options: &ReleaseOptions,
+    ) -> Result<()> {
+        let unreleased_heading = self.unreleased_heading(None);
+
+        if !self.has_unreleased() {
+            if !options.create_empty {
+                return Err(ChangelogError::NotFound(
+                    "Nothing to release, the changelog has no unreleased section".to_string(),
+                ));
+            }
+
+            // Release an empty version, as if the unreleased section was still there
+            insert_section(
+                self.root.children.get_mut(0).ok_or_else(|| {
+                    ChangelogError::ParseError(
+                        "Couldn't find main heading, is your CHANGELOG.md formatted correctly?"
+                            .to_string(),
+                    )
+                })?,
+                Node::from_token(MarkdownToken::H2(unreleased_heading.clone())),
+            );
+        }
+
+        self.run_hooks(&self.config.pre_release, version, options)?;
+
+        let date = Local::now().format("%Y-%m-%d").to_string();
+        let heading = self.config.version_heading(
+            &version.to_string(),
+            (!options.no_date).then_some(date.as_str()),
+        );
+
+        if let Some(unreleased) = self.root.find_node_mut(|node| {
+            if let Some(MarkdownToken::H2(name)) = &node.data {
+                strip_heading_link(name).eq_ignore_ascii_case(&unreleased_heading)
+            } else {
+                false
+            }
+        }) {
+            // Convert to the new version
+            strip_placeholders(unreleased, &self.config);
+            if options.dedupe_references {
+                dedupe_references(unreleased);
+            }
+            unreleased.rename_heading(&heading);
+
+            // Insert new [Unreleased] section at the top
+            let mut new_unreleased =
+                Node::from_token(MarkdownToken::H2(unreleased_heading.clone()));
+            new_unreleased.children = unreleased_scaffold(&self.config, options.scaffold);
+
+            insert_section(
+                self.root.children.get_mut(0).expect(
+                    "Couldn't find main heading, is your CHANGELOG.md formatted correctly?",
+                ),
+                new_unreleased,
+            );
+
+            // The tag of the version below the released version, to compare with
+            let tag = self.config.tag(&version.to_string(), scope);
+            let base = match self.config.compare_base {
+                CompareBase::Changelog => self
+                    .versions()
+                    .into_iter()
+                    .filter_map(|node| section_version(node, &self.config))
+                    .filter(|name| !name.eq_ignore_ascii_case(UNRELEASED_HEADING))
+                    .skip_while(|name| *name != version.to_string())
+                    .nth(1)
+                    .map(|previous| self.config.tag(previous, scope)),
+                CompareBase::Tags => None,
+            };
+
+            // Update references at the bottom
+            let c = self.clone();
+            match c.find_latest_version() {
+                Some(old_version) => {
+                    if let Some(unreleased_reference) =
+                        self.root.find_node_mut(|node| match &node.data {
+                            Some(MarkdownToken::Reference(name, _)) => name.eq_ignore_ascii_case(
+                                &unreleased_heading[1..unreleased_heading.len() - 1],
+                            ),
+                            _ => false,
+                        })
+                    {
+                        if let Some(MarkdownToken::Reference(name, link)) =
+                            &unreleased_reference.data
+                        {
+                            let compare_links = base.as_ref().and_then(|base| {
+                                Some((
+                                    compare_link(link, &tag, "HEAD")?,
+                                    compare_link(link, base, &tag)?,
+                                ))
+                            });
+                            let (updated_link, new_link) = match compare_links {
+                                Some(links) => links,
+                                None => (
+                                    link.clone().replace(old_version, &version.to_string()),
+                                    link.clone().replace("HEAD", &tag),
+                                ),
+                            };
+
+                            // Update unreleased_reference
+                            unreleased_reference.data =
+                                Some(MarkdownToken::Reference(name.to_string(), updated_link));
 
                             // Insert new version reference
                             let new_version_reference = Node::from_token(MarkdownToken::Reference(
@@ -410,7 +1107,8 @@ impl Changelog {
 
                             match self.root.children.iter().position(|node| match &node.data {
                                 Some(MarkdownToken::Reference(name, _)) => {
-                                    !name.to_lowercase().starts_with("unreleased")
+                                    is_version_reference(node)
+                                        && !name.to_lowercase().starts_with("unreleased")
                                 }
                                 _ => false,
                             }) {
@@ -420,173 +1118,3735 @@ impl Changelog {
                         }
                     }
                 }
+                // A changelog without any references (e.g.: created without a git remote) has
+                // nothing to update
+                None if self.root.find_node(is_version_reference).is_none() => {}
                 None => {
-                    return Err(eyre!(
+                    return Err(ChangelogError::NotFound(
                         "Couldn't find latest version, is your CHANGELOG.md formatted correctly?"
+                            .to_string(),
                     ));
                 }
             }
         }
 
-        self.persist()
+        // Read every version file before writing anything, so that a missing file doesn't leave a
+        // half finished release behind
+        let version_files = self.version_file_updates(version)?;
+
+        self.persist()?;
+        for (path, contents) in version_files {
+            fs::write(path, contents)?;
+        }
+        self.run_hooks(&self.config.post_release, version, options)
     }
-}
 
-#[derive(Debug, Clone, Copy)]
-pub enum Amount {
-    All,
-    Value(usize),
-}
+    /// The new contents of the configured version files with the version updated, e.g.: a version
+    /// badge in the README
+    fn version_file_updates(&self, version: &Version) -> Result<Vec<(PathBuf, String)>> {
+        let mut updates = vec![];
 
-impl FromStr for Amount {
-    type Err = String;
+        for file in &self.config.version_files {
+            let path = self.pwd.join(&file.path);
+            let contents = fs::read_to_string(&path).map_err(|e| {
+                ChangelogError::NotFound(format!(
+                    "Couldn't read the version file {}: {}",
+                    file.path, e
+                ))
+            })?;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "all" => Amount::All,
-            _ => Amount::Value(s.parse::<usize>().map_err(|_| "Invalid amount")?),
-        })
+            match file.replace(&contents, &version.to_string()) {
+                Some(contents) => updates.push((path, contents)),
+                None => output_warning(format!(
+                    "Couldn't find the version in {} with the pattern: {}",
+                    file.path, file.pattern
+                )),
+            }
+        }
+
+        Ok(updates)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// The paths of the files that a release updates the version in, besides the changelog
+    pub fn version_file_paths(&self) -> Vec<PathBuf> {
+        self.config
+            .version_files
+            .iter()
+            .map(|file| self.pwd.join(&file.path))
+            .collect()
+    }
 
-    #[test]
-    fn it_should_find_the_latest_version() {
-        let c = Changelog {
-            root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
-            pwd: PathBuf::default(),
-            file_path: PathBuf::default(),
-        };
+    /// Run the release hooks in the directory of the changelog, the version that is released is
+    /// available as `$CHANGELOG_VERSION`. Their output is streamed to stderr.
+    fn run_hooks(
+        &self,
+        commands: &[String],
+        version: &Version,
+        options: &ReleaseOptions,
+    ) -> Result<()> {
+        let shell = Shell::new(&self.pwd).env("CHANGELOG_VERSION", &version.to_string());
 
-        let latest_version = c.find_latest_version();
-        assert_eq!(latest_version, Some("0.1.0"));
+        for command in commands {
+            if options.verbose {
+                output_status(format!("Running {}", command.white().dimmed()));
+            }
+
+            shell.stream(command)?;
+        }
+
+        Ok(())
     }
 
-    #[test]
-    fn it_should_get_the_contents_of_a_section() {
-        let c = Changelog {
-            root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
-            pwd: PathBuf::default(),
-            file_path: PathBuf::default(),
-        };
+    /// The version of the latest release, e.g.: "1.2.3"
+    pub fn latest_release(&self) -> Option<&str> {
+        let unreleased_heading = self.unreleased_heading(None);
 
-        let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
-        assert!(unreleased_section.is_some());
+        self.root
+            .find_node(|node| match &node.data {
+                Some(MarkdownToken::H2(name)) => {
+                    !strip_heading_link(name).eq_ignore_ascii_case(&unreleased_heading)
+                }
+                _ => false,
+            })
+            .and_then(|node| section_version(node, &self.config))
+    }
 
-        let unreleased_section = unreleased_section.unwrap();
-        assert_eq!(
-            unreleased_section,
-            Node::from_str("- Nothing yet!").unwrap()
-        );
+    /// Whether the changelog has a heading for the given version
+    pub fn has_version(&self, version: &str) -> bool {
+        self.find_version(version).is_some()
+    }
 
-        let first_release = c.get_contents_of_section(&Some("0.1.0".to_string()));
-        assert!(first_release.is_some());
+    /// The date of a version, e.g.: "2022-01-01" for "## [1.2.3] - 2022-01-01"
+    pub fn version_date(&self, version: &str) -> Option<&str> {
+        self.find_version(version)
+            .and_then(|node| section_date(node, &self.config))
+    }
 
-        let first_release = first_release.unwrap();
-        assert_eq!(
-            first_release,
-            Node::from_str("### Added\n- Everything!").unwrap()
-        );
+    /// The name of a version in the changelog for a version or its tag, e.g.: "1.2.3" for "v1.2.3"
+    pub fn resolve_version(&self, version: &str) -> Option<String> {
+        [
+            version,
+            version
+                .strip_prefix(&self.config.tag_prefix)
+                .unwrap_or(version),
+            version.trim_start_matches('v'),
+        ]
+        .into_iter()
+        .find(|version| self.has_version(version))
+        .map(|version| version.to_string())
     }
 
-    #[test]
-    fn it_should_generate_a_list_of_releases() {
-        let c = Changelog {
-            root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
-            pwd: PathBuf::default(),
-            file_path: PathBuf::default(),
+    /// Whether a version (or its tag) isn't released yet: it's not in the changelog, and it's
+    /// newer than the latest release, e.g.: the version that is about to be released in CI
+    pub fn is_upcoming_version(&self, version: &str) -> bool {
+        let number = |name: &str| {
+            name.rsplit_once('@')
+                .map_or(name, |(_, number)| number)
+                .trim_start_matches('v')
+                .parse::<Version>()
+                .ok()
         };
 
-        assert_eq!(
-            c.list(Amount::All).unwrap(),
-            ["- unreleased      https://github.com/RobinMalfait/changelog/compare/v0.1.0...HEAD",
-                "- 0.1.0           https://github.com/RobinMalfait/changelog/releases/tag/v0.1.0"]
-            .join("\n")
-        );
+        match (self.resolve_version(version), number(version)) {
+            (None, Some(version)) => match self.latest_release().and_then(number) {
+                Some(latest) => version > latest,
+                None => true,
+            },
+            _ => false,
+        }
     }
 
-    #[test]
-    fn it_should_be_possible_to_add_something_to_a_section() {
-        let mut c = Changelog {
-            root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
-            pwd: PathBuf::default(),
-            file_path: PathBuf::default(),
-        };
+    /// Whether a released version was already published, either because the git tag of the
+    /// version exists or because the version was pushed to the upstream branch.
+    pub fn is_published(&self, version: &str, tag: &str) -> Result<bool> {
+        let git = Git::new(Some(&self.pwd))?;
 
-        let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
-        assert!(unreleased_section.is_some());
-        let unreleased_section = unreleased_section.unwrap();
+        if git.tag_exists(tag) {
+            return Ok(true);
+        }
 
-        assert_eq!(
-            unreleased_section,
-            Node {
-                data: None,
-                children: vec![Node {
-                    data: Some(MarkdownToken::UnorderedList,),
-                    children: vec![Node {
-                        data: Some(MarkdownToken::ListItem("Nothing yet!".to_string(), 0)),
-                        children: vec![],
-                    }],
-                }],
-            }
-        );
+        match git.upstream() {
+            Some(upstream) => Ok(self.at_revision(&upstream)?.find_version(version).is_some()),
+            None => Ok(false),
+        }
+    }
 
-        c.add_list_item_to_section("Added", "Something new", false, None);
+    /// A summary of the commits since the latest tag, e.g.: "12 commits since v1.2.0". In
+    /// monorepos, only the commits that touched the package are counted.
+    pub fn commits_since_tag(&self, scope: Option<&PackageJSON>) -> Result<String> {
+        let git = Git::new(Some(&self.pwd))?;
+        let tag = git.latest_tag("HEAD", &self.config.tag("", scope)).ok();
+        let path = scope.and_then(|_| self.pwd.to_str());
+        let count = git.count_commits(tag.as_deref(), "HEAD", path)?;
+        let commits = if count == 1 { "commit" } else { "commits" };
 
-        let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
-        assert!(unreleased_section.is_some());
-        let unreleased_section = unreleased_section.unwrap();
+        Ok(match tag {
+            Some(tag) => format!("{} {} since {}", count, commits, tag),
+            None => format!("{} {}, no tags yet", count, commits),
+        })
+    }
 
-        assert_eq!(
-            unreleased_section,
-            Node {
-                data: None,
-                children: vec![Node {
-                    data: Some(MarkdownToken::H3("Added".to_string())),
-                    children: vec![Node {
-                        data: Some(MarkdownToken::UnorderedList),
-                        children: vec![Node {
-                            data: Some(MarkdownToken::ListItem("Something new".to_string(), 0)),
-                            children: vec![],
-                        }],
-                    }],
-                }],
-            }
+    /// Move the entries of the unreleased section into the latest released version and update
+    /// its date, e.g.: to add one more thing to a release that wasn't published yet. Returns the
+    /// version that was amended.
+    pub fn amend(&mut self, options: &ReleaseOptions) -> Result<String> {
+        let entries = self.unreleased_entries(None);
+        let unreleased_heading = self.unreleased_heading(None);
+
+        let latest = self
+            .root
+            .find_node_mut(|node| match &node.data {
+                Some(MarkdownToken::H2(name)) => {
+                    !strip_heading_link(name).eq_ignore_ascii_case(&unreleased_heading)
+                }
+                _ => false,
+            })
+            .ok_or_else(|| {
+                ChangelogError::NotFound("Couldn't find a released version to amend".to_string())
+            })?;
+        let version = section_version(latest, &self.config)
+            .ok_or_else(|| {
+                ChangelogError::ParseError(
+                    "Couldn't find the version of the latest release".to_string(),
+                )
+            })?
+            .to_string();
+
+        for (section_name, text) in entries {
+            add_list_item_to_version(latest, &section_name, None, text, &self.config);
+        }
+
+        if options.dedupe_references {
+            dedupe_references(latest);
+        }
+
+        let date = Local::now().format("%Y-%m-%d").to_string();
+        latest.rename_heading(
+            &self
+                .config
+                .version_heading(&version, (!options.no_date).then_some(date.as_str())),
         );
 
-        c.add_list_item_to_section("Added", "Something newer", false, None);
+        // Start over with an empty unreleased section
+        if let Some(unreleased) = self.root.find_node_mut(|node| match &node.data {
+            Some(MarkdownToken::H2(name)) => {
+                strip_heading_link(name).eq_ignore_ascii_case(&unreleased_heading)
+            }
+            _ => false,
+        }) {
+            unreleased.children = unreleased_scaffold(&self.config, options.scaffold);
+        }
 
-        let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
-        assert!(unreleased_section.is_some());
-        let unreleased_section = unreleased_section.unwrap();
+        self.persist()?;
+
+        Ok(version)
+    }
+
+    /// Move the entries of the latest released version back into the unreleased section and
+    /// remove its heading and reference, the inverse of `release`.
+    pub fn unrelease(&mut self, version: &str, scope: Option<&PackageJSON>) -> Result<()> {
+        let latest = self.latest_release().map(|latest| latest.to_string());
+        if latest.as_deref() != Some(version) {
+            return Err(ChangelogError::NotFound(match latest {
+                Some(latest) => format!(
+                    "Only the latest release ({}) can be unreleased, not: {}",
+                    latest, version
+                ),
+                None => "Couldn't find a released version to unrelease".to_string(),
+            }));
+        }
+
+        let idx = self.root.children[0]
+            .children
+            .iter()
+            .position(|node| is_section(node, Some(&version.to_string()), None, &self.config))
+            .ok_or_else(|| {
+                ChangelogError::NotFound(format!("Couldn't find version: {}", version))
+            })?;
+        let released = self.root.children[0].children.remove(idx);
+
+        // The unreleased section of the scope, or the shared one, created when it's missing
+        let is_unreleased = |node: &Node, heading: &str| match &node.data {
+            Some(MarkdownToken::H2(name)) => strip_heading_link(name).eq_ignore_ascii_case(heading),
+            _ => false,
+        };
+        let unreleased_heading = [
+            self.unreleased_heading(scope),
+            self.unreleased_heading(None),
+        ]
+        .into_iter()
+        .find(|heading| {
+            self.root
+                .find_node(|node| is_unreleased(node, heading))
+                .is_some()
+        })
+        .unwrap_or_else(|| {
+            let heading = self.unreleased_heading(scope);
+            insert_section(
+                &mut self.root.children[0],
+                Node::from_token(MarkdownToken::H2(heading.clone())),
+            );
+            heading
+        });
+
+        if let Some(unreleased) = self
+            .root
+            .find_node_mut(|node| is_unreleased(node, &unreleased_heading))
+        {
+            strip_placeholders(unreleased, &self.config);
+            unreleased.children.retain(|node| !is_nothing_yet(node));
+
+            // Move the entries back as a whole, keeping loose entries, nesting and sub-headings
+            merge_section(unreleased, released, &self.config);
+
+            if unreleased.children.is_empty() {
+                unreleased.children = unreleased_scaffold(&self.config, false);
+            }
+        }
+
+        // Point the unreleased reference back at the tag the removed version was compared with
+        let reference = self.root.children.iter().position(
+            |node| matches!(&node.data, Some(MarkdownToken::Reference(name, _)) if name == version),
+        );
+
+        if let Some(reference) = reference {
+            let removed = self.root.children.remove(reference);
+            let tag = self.config.tag(version, scope);
+
+            if let (Some(MarkdownToken::Reference(_, link)), Some(unreleased_reference)) = (
+                &removed.data,
+                self.root.find_node_mut(|node| match &node.data {
+                    Some(MarkdownToken::Reference(name, _)) => name
+                        .eq_ignore_ascii_case(&unreleased_heading[1..unreleased_heading.len() - 1]),
+                    _ => false,
+                }),
+            ) {
+                if link.ends_with(&tag) && link.contains("...") {
+                    if let Some(MarkdownToken::Reference(name, _)) = &unreleased_reference.data {
+                        unreleased_reference.data = Some(MarkdownToken::Reference(
+                            name.to_string(),
+                            format!("{}HEAD", &link[..link.len() - tag.len()]),
+                        ));
+                    }
+                }
+            }
+        }
+
+        self.persist()
+    }
+
+    /// Merge another changelog into this one. Released versions that only exist in the other
+    /// changelog are added, and the entries of both unreleased sections are combined. Returns a
+    /// description of every change that was made.
+    ///
+    /// When the common `base` of both changelogs is known (e.g.: in a git merge driver), entries
+    /// that were removed on one side stay removed, and a released version that only changed on
+    /// one side is taken from that side.
+    pub fn merge(&mut self, other: &Changelog, base: Option<&Changelog>) -> Result<Vec<String>> {
+        let unreleased_heading = self.unreleased_heading(None);
+        let is_released = |node: &Node| match &node.data {
+            Some(MarkdownToken::H2(name)) => {
+                !strip_heading_link(name).eq_ignore_ascii_case(&unreleased_heading)
+            }
+            _ => false,
+        };
+        let base_version = |version: &str| base.and_then(|base| base.find_version(version));
+
+        let mut changes = vec![];
+        let mut conflicts = vec![];
+
+        // Released versions should be the same in both changelogs, we don't want to guess which
+        // one is correct, unless only one of them changed compared to the base.
+        for section in other.root.filter_nodes(is_released) {
+            let version = match section_version(section, &self.config) {
+                Some(version) => version,
+                None => continue,
+            };
+            let existing = match self.find_version(version) {
+                Some(existing) if existing.children != section.children => existing,
+                _ => continue,
+            };
+
+            match base_version(version) {
+                Some(base) if base.children == section.children => {}
+                Some(base) if base.children == existing.children => {
+                    if let Some(existing) = self.root.find_node_mut(|node| match &node.data {
+                        Some(MarkdownToken::H2(_)) => section_version(node, &self.config)
+                            .is_some_and(|other| other.eq_ignore_ascii_case(version)),
+                        _ => false,
+                    }) {
+                        *existing = section.clone();
+                        changes.push(format!("Updated version {}", version));
+                    }
+                }
+                _ => conflicts.push(version.to_string()),
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return Err(ChangelogError::ParseError(format!(
+                "Version {} differs in both changelogs, please resolve this manually",
+                conjunction(&conflicts).blue().bold()
+            )));
+        }
+
+        let base_entries = base
+            .map(|base| base.unreleased_entries(None))
+            .unwrap_or_default();
+        let other_entries = other.unreleased_entries(None);
+
+        // Combine the unreleased sections
+        for (section_name, text) in &other_entries {
+            // Entries of the base that we don't have anymore were removed on purpose
+            if base_entries.contains(&(section_name.clone(), text.clone())) {
+                continue;
+            }
+
+            if !self.has_unreleased_entry(section_name, text) {
+                changes.push(format!("Added \"{}\" to {}", text, section_name));
+                self.add_list_item_to_section_scope(section_name, None, text.clone(), None);
+            }
+        }
+
+        // Remove the entries that were removed (or released) in the other changelog
+        for (section_name, text) in &base_entries {
+            if !other_entries.contains(&(section_name.clone(), text.clone()))
+                && self.remove_unreleased_entry(section_name, text)
+            {
+                changes.push(format!("Removed \"{}\" from {}", text, section_name));
+            }
+        }
+
+        // Add the released versions that we don't know about yet
+        for section in other.root.filter_nodes(is_released) {
+            if let Some(version) = section_version(section, &self.config) {
+                if self.find_version(version).is_some() {
+                    continue;
+                }
+
+                let main = self
+                    .root
+                    .find_node_mut(|node| matches!(&node.data, Some(MarkdownToken::H1(_))));
+
+                if let Some(main) = main {
+                    let idx = insert_position(&main.children, version, &self.config, is_released);
+                    main.add_child_at(idx, section.clone());
+                    changes.push(format!("Added version {}", version));
+                }
+            }
+        }
+
+        // Add the missing references
+        for reference in other
+            .root
+            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::Reference(_, _))))
+        {
+            if let Some(MarkdownToken::Reference(name, link)) = &reference.data {
+                let base_link = base.and_then(|base| {
+                    base.root.find_node(|node| match &node.data {
+                        Some(MarkdownToken::Reference(other, _)) => {
+                            other.eq_ignore_ascii_case(name)
+                        }
+                        _ => false,
+                    })
+                });
+                let exists = self.root.find_node_mut(|node| match &node.data {
+                    Some(MarkdownToken::Reference(other, _)) => other.eq_ignore_ascii_case(name),
+                    _ => false,
+                });
+
+                if let Some(existing) = exists {
+                    // Take the link of the other changelog when only the other one changed it,
+                    // e.g.: the compare link of the unreleased section after a release.
+                    if let (Some(base_link), Some(MarkdownToken::Reference(_, existing_link))) =
+                        (base_link, &existing.data)
+                    {
+                        if base_link.data == existing.data && existing_link != link {
+                            existing.data = reference.data.clone();
+                        }
+                    }
+                } else {
+                    let idx = insert_position(&self.root.children, name, &self.config, |node| {
+                        matches!(&node.data, Some(MarkdownToken::Reference(_, _)))
+                    });
+                    self.root.add_child_at(idx, reference.clone());
+                }
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// The changelog as it was at the given git revision. A changelog that didn't exist yet at
+    /// that revision is empty.
+    pub fn at_revision(&self, rev: &str) -> Result<Self> {
+        let git = Git::new(Some(&self.pwd))?;
+        let filename = self
+            .file_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+
+        let root = match git.show_file(rev, filename) {
+            Ok(contents) => contents.parse()?,
+            Err(e @ ChangelogError::NotFound(_)) => return Err(e),
+            Err(_) => Node::empty(),
+        };
+
+        Ok(Changelog {
+            root,
+            ..self.clone()
+        })
+    }
+
+    /// All entries of the unreleased section, as (section name, entry) pairs
+    pub fn unreleased_entries(&self, scope: Option<&PackageJSON>) -> Vec<(String, String)> {
+        let unreleased_heading = self.unreleased_heading(scope);
+        let unreleased = self.root.find_node(|node| match &node.data {
+            Some(MarkdownToken::H2(name)) => {
+                strip_heading_link(name).eq_ignore_ascii_case(&unreleased_heading)
+            }
+            _ => false,
+        });
+
+        let mut entries = vec![];
+
+        if let Some(unreleased) = unreleased {
+            for section in
+                unreleased.filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::H3(_))))
+            {
+                if let Some(MarkdownToken::H3(section_name)) = &section.data {
+                    for item in section.filter_nodes(|node| {
+                        matches!(&node.data, Some(MarkdownToken::ListItem(_, _)))
+                    }) {
+                        match &item.data {
+                            Some(MarkdownToken::ListItem(text, _))
+                                if !is_placeholder(&self.config, Some(section_name), text) =>
+                            {
+                                entries.push((section_name.to_string(), text.to_string()));
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        entries
+    }
+
+    /// All entries of every version, as (version, section name, entry) triples
+    fn all_entries(&self) -> Vec<(String, String, String)> {
+        let mut entries = vec![];
+
+        for version in self.versions() {
+            for section in
+                version.filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::H3(_))))
+            {
+                if let Some(MarkdownToken::H3(section_name)) = &section.data {
+                    for item in section.filter_nodes(|node| {
+                        matches!(&node.data, Some(MarkdownToken::ListItem(_, _)))
+                    }) {
+                        if let Some(MarkdownToken::ListItem(text, _)) = &item.data {
+                            entries.push((
+                                version_name(version, &self.config).to_string(),
+                                section_name.to_string(),
+                                text.to_string(),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        entries
+    }
+
+    /// The whole changelog as structured JSON: every version (including the unreleased section)
+    /// with its date, reference link and the entries of its sections, including their links.
+    pub fn export(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Json => self.to_json().to_string(),
+        }
+    }
+
+    /// The notes of a version as structured JSON, like a version of `export`, e.g.: for
+    /// `notes --format json`
+    pub fn notes_json(
+        &self,
+        version: Option<&String>,
+        options: &NotesOptions,
+    ) -> Option<serde_json::Value> {
+        let mut node = self
+            .root
+            .find_node(|node| is_section(node, version, None, &self.config))?
+            .clone();
+
+        match options.group_by {
+            GroupBy::Type => group_by_type(&mut node),
+            GroupBy::Section if options.include_empty_sections => {
+                add_empty_sections(&mut node, &self.config)
+            }
+            GroupBy::Section => {}
+        }
+        if options.strip_links {
+            node.strip_links();
+        }
+
+        Some(self.version_json(&node, &self.references()))
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let title = self
+            .root
+            .find_node(|node| matches!(&node.data, Some(MarkdownToken::H1(_))));
+        let references = self.references();
+
+        let versions: Vec<_> = self
+            .versions()
+            .into_iter()
+            .map(|version| self.version_json(version, &references))
+            .collect();
+
+        serde_json::json!({
+            "title": title.and_then(|node| match &node.data {
+                Some(MarkdownToken::H1(title)) => Some(title),
+                _ => None,
+            }),
+            "versions": versions,
+        })
+    }
+
+    /// The link reference definitions at the bottom of the changelog, by their name
+    fn references(&self) -> Vec<(&str, &str)> {
+        self.root
+            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::Reference(_, _))))
+            .into_iter()
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::Reference(name, link)) => Some((name.as_str(), link.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// A version with its date, reference link and the entries of its sections, for `to_json`
+    fn version_json(&self, version: &Node, references: &[(&str, &str)]) -> serde_json::Value {
+        let entries_json = |node: &Node, section_name: &str| -> Vec<serde_json::Value> {
+            node.filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::ListItem(_, _))))
+                .into_iter()
+                .filter_map(|node| match &node.data {
+                    Some(MarkdownToken::ListItem(text, _))
+                        if !is_placeholder(&self.config, Some(section_name), text) =>
+                    {
+                        // Reference-style links are resolved with their definition
+                        let resolved =
+                            reference_links(text)
+                                .into_iter()
+                                .filter_map(|(text, label)| {
+                                    references
+                                        .iter()
+                                        .find(|(name, _)| name.eq_ignore_ascii_case(label))
+                                        .map(|(_, url)| (text, *url))
+                                });
+
+                        Some(serde_json::json!({
+                            "text": text,
+                            "links": links(text)
+                                .into_iter()
+                                .chain(resolved)
+                                .map(|(text, url)| serde_json::json!({ "text": text, "url": url }))
+                                .collect::<Vec<_>>(),
+                        }))
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let name = version_name(version, &self.config);
+        let date = section_date(version, &self.config).map(|date| date.to_string());
+        let link = references
+            .iter()
+            .find(|(reference, _)| reference.eq_ignore_ascii_case(name))
+            .map(|(_, link)| link.to_string())
+            .or_else(|| section_link(version).map(|link| link.to_string()));
+
+        let entries: Vec<_> = version
+            .children
+            .iter()
+            .filter(|node| matches!(&node.data, Some(MarkdownToken::UnorderedList)))
+            .flat_map(|node| entries_json(node, ""))
+            .collect();
+        let sections: Vec<_> = version
+            .children
+            .iter()
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::H3(section_name)) => Some(serde_json::json!({
+                    "name": section_name,
+                    "entries": entries_json(node, section_name),
+                })),
+                _ => None,
+            })
+            .collect();
+
+        serde_json::json!({
+            "name": name,
+            "date": date,
+            "link": link,
+            "entries": entries,
+            "sections": sections,
+        })
+    }
+
+    /// The version that precedes the given version in version order. Without a version, or for
+    /// "latest", this is the release before the latest release. For "unreleased", this is the
+    /// latest release.
+    pub fn previous_version(
+        &self,
+        version: Option<&String>,
+        scope: Option<&PackageJSON>,
+    ) -> Result<String> {
+        let prefix = match scope {
+            Some(scope) if !scope.is_root() => format!("{}@v", scope.name()),
+            _ => String::new(),
+        };
+        let versions: Vec<(&str, Version)> = self
+            .versions()
+            .into_iter()
+            .filter_map(|node| section_version(node, &self.config))
+            .filter_map(|name| name.strip_prefix(&prefix))
+            .filter_map(|name| Some((name, name.parse().ok()?)))
+            .collect();
+
+        let (name, target) = match version.map(|version| version.as_str()) {
+            Some(version) if version.eq_ignore_ascii_case(UNRELEASED_HEADING) => {
+                return versions
+                    .first()
+                    .map(|(name, _)| name.to_string())
+                    .ok_or_else(|| {
+                        ChangelogError::NotFound("There are no releases yet".to_string())
+                    });
+            }
+            None | Some("latest") => {
+                let (name, version) = versions.first().ok_or_else(|| {
+                    ChangelogError::NotFound("There are no releases yet".to_string())
+                })?;
+                (name.to_string(), version.clone())
+            }
+            Some(version) => (version.to_string(), version.parse()?),
+        };
+
+        versions
+            .iter()
+            .filter(|(_, other)| other < &target)
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .map(|(name, _)| name.to_string())
+            .ok_or_else(|| ChangelogError::NotFound(format!("There is no version before {}", name)))
+    }
+
+    /// Move the released versions beyond the `keep_latest` most recent ones into a file per
+    /// version in the given directory, and link to those files at the top of the changelog.
+    /// Returns the paths of the new files.
+    pub fn split(&mut self, keep_latest: usize, directory: &str) -> Result<Vec<PathBuf>> {
+        let heading = self.root.children.get_mut(0).ok_or_else(|| {
+            ChangelogError::ParseError(
+                "Couldn't find main heading, is your CHANGELOG.md formatted correctly?".to_string(),
+            )
+        })?;
+
+        let released: Vec<usize> = heading
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| match &node.data {
+                Some(MarkdownToken::H2(_)) => !version_name(node, &self.config)
+                    .to_lowercase()
+                    .starts_with(&UNRELEASED_HEADING.to_lowercase()),
+                _ => false,
+            })
+            .map(|(idx, _)| idx)
+            .skip(keep_latest)
+            .collect();
+
+        if released.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let directory = directory.trim_end_matches('/');
+        fs::create_dir_all(self.pwd.join(directory))?;
+
+        // Remove the newest last, so that the indexes of the others stay valid
+        let mut extracted: Vec<Node> = released
+            .into_iter()
+            .rev()
+            .map(|idx| heading.children.remove(idx))
+            .collect();
+        extracted.reverse();
+
+        let mut links = vec![];
+        let mut paths = vec![];
+        for version in &extracted {
+            let name = version_name(version, &self.config);
+            let relative_path = format!("{}/{}.md", directory, name);
+            let path = self.pwd.join(&relative_path);
+
+            fs::write(&path, version.to_string().trim_end().to_string() + "\n")?;
+            links.push(format!("[{}]({})", name, relative_path));
+            paths.push(path);
+        }
+
+        // The versions of an earlier split are older than the ones of this split
+        match heading
+            .children
+            .iter_mut()
+            .find(|node| is_older_versions(node))
+        {
+            Some(Node {
+                data: Some(MarkdownToken::Paragraph(text)),
+                ..
+            }) => {
+                *text = format!(
+                    "{} {},{}",
+                    OLDER_VERSIONS,
+                    links.join(", "),
+                    &text[OLDER_VERSIONS.len()..]
+                );
+            }
+            _ => {
+                let position = heading
+                    .children
+                    .iter()
+                    .position(|node| matches!(&node.data, Some(MarkdownToken::H2(_))))
+                    .unwrap_or(heading.children.len());
+
+                heading.add_child_at(
+                    position,
+                    Node::from_token(MarkdownToken::Paragraph(format!(
+                        "{} {}",
+                        OLDER_VERSIONS,
+                        links.join(", ")
+                    ))),
+                );
+            }
+        }
+
+        self.persist()?;
+
+        Ok(paths)
+    }
+
+    /// Move the versions that were split into separate files back into the changelog, and remove
+    /// those files. Returns the amount of versions that were moved back.
+    pub fn merge_split(&mut self) -> Result<usize> {
+        let heading = self.root.children.get_mut(0).ok_or_else(|| {
+            ChangelogError::ParseError(
+                "Couldn't find main heading, is your CHANGELOG.md formatted correctly?".to_string(),
+            )
+        })?;
+
+        let text = match heading.children.iter().position(is_older_versions) {
+            Some(position) => match heading.children.remove(position).data {
+                Some(MarkdownToken::Paragraph(text)) => text,
+                _ => unreachable!("The older versions are a paragraph"),
+            },
+            None => return Ok(0),
+        };
+
+        let mut paths = vec![];
+        for (_, relative_path) in links(&text) {
+            let path = self.pwd.join(relative_path);
+            let contents: Node = fs::read_to_string(&path)
+                .map_err(|_| {
+                    ChangelogError::NotFound(format!(
+                        "Couldn't read the split version at: {}",
+                        path.display()
+                    ))
+                })?
+                .parse()?;
+
+            heading.children.extend(contents.children);
+            paths.push(path);
+        }
+
+        self.persist()?;
+
+        for path in &paths {
+            fs::remove_file(path)?;
+
+            // Clean up the directory once it's empty
+            if let Some(directory) = path.parent() {
+                let _ = fs::remove_dir(directory);
+            }
+        }
+
+        Ok(paths.len())
+    }
+
+    /// The problems in the structure of the changelog, errors break the other commands and
+    /// warnings are deviations from the conventions
+    pub fn validate(&self) -> Vec<Problem> {
+        let mut problems = vec![];
+
+        if self
+            .root
+            .find_node(|node| matches!(&node.data, Some(MarkdownToken::H1(_))))
+            .is_none()
+        {
+            problems.push(Problem::Error(
+                "Missing the main heading, e.g.: \"# Changelog\"".to_string(),
+            ));
+        }
+
+        let references: Vec<&str> = self
+            .root
+            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::Reference(_, _))))
+            .into_iter()
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::Reference(name, _)) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        let mut seen: Vec<&str> = vec![];
+        for version in self.versions() {
+            let name = version_name(version, &self.config);
+            let is_unreleased = name
+                .to_lowercase()
+                .starts_with(&UNRELEASED_HEADING.to_lowercase());
+
+            if !is_unreleased {
+                let number = name.rsplit_once('@').map_or(name, |(_, number)| number);
+                if number.trim_start_matches('v').parse::<Version>().is_err() {
+                    problems.push(Problem::Error(format!("Invalid version: {}", name)));
+                }
+
+                if seen.contains(&name) {
+                    problems.push(Problem::Error(format!("Duplicate version: {}", name)));
+                }
+
+                if section_link(version).is_none()
+                    && !references
+                        .iter()
+                        .any(|reference| reference.eq_ignore_ascii_case(name))
+                {
+                    problems.push(Problem::Warning(format!(
+                        "Version {} has no link at the bottom of the changelog",
+                        name
+                    )));
+                }
+            }
+            seen.push(name);
+
+            for child in &version.children {
+                match &child.data {
+                    Some(MarkdownToken::H3(section))
+                        if !self
+                            .config
+                            .section_order
+                            .iter()
+                            .any(|known| known.eq_ignore_ascii_case(section)) =>
+                    {
+                        problems.push(Problem::Warning(format!(
+                            "Unknown section \"{}\" in {}",
+                            section, name
+                        )))
+                    }
+                    _ => {}
+                }
+            }
+
+            let mixed_markers = version
+                .filter_nodes(|node| match &node.data {
+                    Some(MarkdownToken::Paragraph(text)) => {
+                        text.starts_with("* ") || text.starts_with("+ ")
+                    }
+                    _ => false,
+                })
+                .len();
+            if mixed_markers > 0 {
+                problems.push(Problem::Warning(format!(
+                    "Use \"- \" instead of \"*\" or \"+\" for the entries in {}",
+                    name
+                )));
+            }
+
+            for node in version.filter_nodes(|node| {
+                matches!(
+                    &node.data,
+                    Some(MarkdownToken::ListItem(_, _)) | Some(MarkdownToken::Paragraph(_))
+                )
+            }) {
+                if let Some(MarkdownToken::ListItem(text, _) | MarkdownToken::Paragraph(text)) =
+                    &node.data
+                {
+                    for (_, label) in reference_links(text) {
+                        if !references
+                            .iter()
+                            .any(|reference| reference.eq_ignore_ascii_case(label))
+                        {
+                            problems.push(Problem::Warning(format!(
+                                "Undefined link reference \"{}\" in {}",
+                                label, name
+                            )));
+                        }
+                    }
+                }
+            }
+        }
+
+        problems
+    }
+
+    fn versions(&self) -> Vec<&Node> {
+        self.root
+            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::H2(_))))
+    }
+
+    /// The versions and entries that were added or removed in the `newer` version of this
+    /// changelog, e.g.: the same file at a later git revision.
+    pub fn diff(&self, newer: &Changelog) -> Vec<Change> {
+        let versions = |changelog: &Changelog| -> Vec<String> {
+            changelog
+                .versions()
+                .into_iter()
+                .map(|node| version_name(node, &self.config).to_string())
+                .collect()
+        };
+        let (old_versions, new_versions) = (versions(self), versions(newer));
+        let (old_entries, new_entries) = (self.all_entries(), newer.all_entries());
+
+        let describe = |(version, section, text): &(String, String, String)| {
+            format!("{} in {}: {}", section, version, text)
+        };
+
+        new_versions
+            .iter()
+            .filter(|version| !old_versions.contains(version))
+            .map(|version| Change::Added(format!("Version {}", version)))
+            .chain(
+                old_versions
+                    .iter()
+                    .filter(|version| !new_versions.contains(version))
+                    .map(|version| Change::Removed(format!("Version {}", version))),
+            )
+            .chain(
+                new_entries
+                    .iter()
+                    .filter(|entry| !old_entries.contains(entry))
+                    .map(|entry| Change::Added(describe(entry))),
+            )
+            .chain(
+                old_entries
+                    .iter()
+                    .filter(|entry| !new_entries.contains(entry))
+                    .map(|entry| Change::Removed(describe(entry))),
+            )
+            .collect()
+    }
+
+    /// A summary of the changes since the earlier contents of the changelog file, e.g.: from before
+    /// a command wrote it. Without earlier contents, everything is new.
+    pub fn changes_since(&self, contents: Option<&str>) -> Result<Vec<String>> {
+        let root = match contents {
+            Some(contents) => contents.parse()?,
+            None => Node::empty(),
+        };
+
+        Ok(Changelog {
+            root,
+            ..self.clone()
+        }
+        .summarize_changes(self))
+    }
+
+    /// A summary of the versions and entries that changed in the `newer` version of this
+    /// changelog, per section, e.g.: "+1 entry under Fixed in Unreleased"
+    pub fn summarize_changes(&self, newer: &Changelog) -> Vec<String> {
+        let (old_entries, new_entries) = (self.all_entries(), newer.all_entries());
+        let added = new_entries
+            .iter()
+            .filter(|entry| !old_entries.contains(entry))
+            .map(|entry| (entry, true));
+        let removed = old_entries
+            .iter()
+            .filter(|entry| !new_entries.contains(entry))
+            .map(|entry| (entry, false));
+
+        // The amount of added and removed entries per section of a version
+        let mut sections: Vec<((&str, &str), usize, usize)> = vec![];
+        for ((version, section, _), added) in added.chain(removed) {
+            let key = (version.as_str(), section.as_str());
+            let idx = match sections.iter().position(|(other, _, _)| *other == key) {
+                Some(idx) => idx,
+                None => {
+                    sections.push((key, 0, 0));
+                    sections.len() - 1
+                }
+            };
+
+            match added {
+                true => sections[idx].1 += 1,
+                false => sections[idx].2 += 1,
+            }
+        }
+
+        // The amount of entries that moved within a section of a version, e.g.: by `sort`
+        let mut reordered: Vec<((&str, &str), usize)> = vec![];
+        for (version, section, _) in &new_entries {
+            let key = (version.as_str(), section.as_str());
+            if reordered.iter().any(|(other, _)| *other == key) {
+                continue;
+            }
+
+            let kept = |entries: &[(String, String, String)],
+                        others: &[(String, String, String)]| {
+                entries
+                    .iter()
+                    .filter(|entry| (entry.0.as_str(), entry.1.as_str()) == key)
+                    .filter(|entry| others.contains(entry))
+                    .map(|(_, _, text)| text.clone())
+                    .collect::<Vec<_>>()
+            };
+            let (before, after) = (
+                kept(&old_entries, &new_entries),
+                kept(&new_entries, &old_entries),
+            );
+            let moved = before.iter().zip(&after).filter(|(a, b)| a != b).count();
+            reordered.push((key, moved));
+        }
+
+        let entries = |amount: usize| match amount {
+            1 => "1 entry".to_string(),
+            amount => format!("{} entries", amount),
+        };
+
+        self.diff(newer)
+            .into_iter()
+            .filter_map(|change| match change {
+                Change::Added(text) if text.starts_with("Version ") => Some(format!("+{}", text)),
+                Change::Removed(text) if text.starts_with("Version ") => Some(format!("-{}", text)),
+                _ => None,
+            })
+            .chain(
+                sections
+                    .iter()
+                    .flat_map(|((version, section), added, removed)| {
+                        [(*added, '+'), (*removed, '-')]
+                            .into_iter()
+                            .filter(|(amount, _)| *amount > 0)
+                            .map(move |(amount, sign)| {
+                                format!(
+                                    "{}{} under {} in {}",
+                                    sign,
+                                    entries(amount),
+                                    section,
+                                    version
+                                )
+                            })
+                    }),
+            )
+            .chain(reordered.iter().filter(|(_, moved)| *moved > 0).map(
+                |((version, section), moved)| {
+                    format!(
+                        "~{} reordered under {} in {}",
+                        entries(*moved),
+                        section,
+                        version
+                    )
+                },
+            ))
+            .collect()
+    }
+
+    fn find_version(&self, version: &str) -> Option<&Node> {
+        self.root.find_node(|node| match &node.data {
+            Some(MarkdownToken::H2(_)) => section_version(node, &self.config)
+                .is_some_and(|other| other.eq_ignore_ascii_case(version)),
+            _ => false,
+        })
+    }
+
+    /// Remove an entry from the unreleased section, returns whether the entry existed
+    fn remove_unreleased_entry(&mut self, section_name: &str, text: &str) -> bool {
+        let unreleased_heading = self.unreleased_heading(None);
+        let unreleased = match self.root.find_node_mut(|node| match &node.data {
+            Some(MarkdownToken::H2(name)) => {
+                strip_heading_link(name).eq_ignore_ascii_case(&unreleased_heading)
+            }
+            _ => false,
+        }) {
+            Some(unreleased) => unreleased,
+            None => return false,
+        };
+
+        let mut removed = false;
+
+        for section in unreleased
+            .children
+            .iter_mut()
+            .filter(|node| match &node.data {
+                Some(MarkdownToken::H3(name)) => name.eq_ignore_ascii_case(section_name),
+                _ => false,
+            })
+        {
+            for ul in section.children.iter_mut() {
+                let before = ul.children.len();
+                ul.children.retain(|item| match &item.data {
+                    Some(MarkdownToken::ListItem(item, _)) => item != text,
+                    _ => true,
+                });
+                removed |= ul.children.len() != before;
+            }
+
+            section.children.retain(|node| {
+                !matches!(&node.data, Some(MarkdownToken::UnorderedList))
+                    || !node.children.is_empty()
+            });
+        }
+
+        // Drop the sections that became empty
+        unreleased.children.retain(|node| {
+            !matches!(&node.data, Some(MarkdownToken::H3(_))) || !node.children.is_empty()
+        });
+
+        if unreleased.children.is_empty() {
+            let mut ul = Node::from_token(MarkdownToken::UnorderedList);
+            ul.add_child(Node::from_token(MarkdownToken::ListItem(
+                NOTHING_YET.to_string(),
+                0,
+            )));
+            unreleased.add_child(ul);
+        }
+
+        removed
+    }
+
+    fn has_unreleased_entry(&self, section_name: &str, text: &str) -> bool {
+        let unreleased_heading = self.unreleased_heading(None);
+
+        self.root
+            .find_node(|node| match &node.data {
+                Some(MarkdownToken::H2(name)) => {
+                    strip_heading_link(name).eq_ignore_ascii_case(&unreleased_heading)
+                }
+                _ => false,
+            })
+            .and_then(|unreleased| {
+                unreleased.find_node(|node| match &node.data {
+                    Some(MarkdownToken::H3(name)) => name.eq_ignore_ascii_case(section_name),
+                    _ => false,
+                })
+            })
+            .and_then(|section| {
+                section.find_node(|node| match &node.data {
+                    // Entries with the same reference are duplicates, even if the reference is
+                    // placed differently.
+                    Some(MarkdownToken::ListItem(other, _)) => {
+                        other == text
+                            || matches!(
+                                (entry_reference(other), entry_reference(text)),
+                                (Some(a), Some(b)) if a == b
+                            )
+                    }
+                    _ => false,
+                })
+            })
+            .is_some()
+    }
+}
+
+/// Add an entry to a section of a version, the section is created at the configured position when
+/// it doesn't exist yet.
+fn add_list_item_to_version(
+    version: &mut Node,
+    section_name: &str,
+    under: Option<&str>,
+    item: String,
+    config: &Config,
+) {
+    // Search for the "Nothing yet!" note, and delete it if it exists.
+    let nothing_yet_ul = version.children.iter().position(is_nothing_yet);
+
+    if let Some(nothing_yet_ul) = nothing_yet_ul {
+        version.children.remove(nothing_yet_ul);
+    }
+
+    let section = version.find_node_mut(|node| match &node.data {
+        Some(MarkdownToken::H3(name)) => name.eq_ignore_ascii_case(section_name),
+        _ => false,
+    });
+
+    if let Some(section) = section {
+        let section = match under {
+            Some(under) => sub_section(section, under),
+            None => section,
+        };
+
+        // The entries of the section itself go before its sub-headings
+        let ul = section
+            .children
+            .iter()
+            .position(|node| matches!(&node.data, Some(MarkdownToken::UnorderedList)));
+
+        if let Some(ul) = ul.map(|idx| &mut section.children[idx]) {
+            let li = Node::from_token(MarkdownToken::ListItem(item, 0));
+
+            // Replace the placeholder of the section with the real entry
+            ul.children.retain(|li| match &li.data {
+                Some(MarkdownToken::ListItem(text, _)) => {
+                    !is_placeholder(config, Some(section_name), text)
+                }
+                _ => true,
+            });
+            insert_entry(ul, li, config.insert);
+        } else {
+            let mut ul = Node::from_token(MarkdownToken::UnorderedList);
+            let li = Node::from_token(MarkdownToken::ListItem(item, 0));
+
+            ul.add_child(li);
+
+            section.add_child_at(0, ul);
+        }
+    } else {
+        let mut h3 = Node::from_token(MarkdownToken::H3(config.section_case.apply(section_name)));
+        let mut ul = Node::from_token(MarkdownToken::UnorderedList);
+        let li = Node::from_token(MarkdownToken::ListItem(item, 0));
+
+        ul.add_child(li);
+        match under {
+            Some(under) => {
+                let mut h4 = Node::from_token(MarkdownToken::H4(under.to_string()));
+                h4.add_child(ul);
+                h3.add_child(h4);
+            }
+            None => h3.add_child(ul),
+        }
+
+        // Keep the sections in the configured order
+        let rank = config.section_rank(section_name);
+        match version.children.iter().position(|node| match &node.data {
+            Some(MarkdownToken::H3(name)) => config.section_rank(name) > rank,
+            _ => false,
+        }) {
+            Some(idx) => version.add_child_at(idx, h3),
+            None => version.add_child(h3),
+        }
+    }
+}
+
+/// Add the configured sections that a version doesn't have, with a "_None_" entry, e.g.: for
+/// release pages that always show the same sections
+fn add_empty_sections(version: &mut Node, config: &Config) {
+    // The "Nothing yet!" note is replaced by the empty sections
+    version.children.retain(|node| !is_nothing_yet(node));
+
+    for section_name in &config.section_order {
+        let exists = version.children.iter().any(|node| match &node.data {
+            Some(MarkdownToken::H3(name)) => name.eq_ignore_ascii_case(section_name),
+            _ => false,
+        });
+        if exists {
+            continue;
+        }
+
+        let mut h3 = Node::from_token(MarkdownToken::H3(config.section_case.apply(section_name)));
+        let mut ul = Node::from_token(MarkdownToken::UnorderedList);
+        ul.add_child(Node::from_token(MarkdownToken::ListItem(
+            "_None_".to_string(),
+            0,
+        )));
+        h3.add_child(ul);
+
+        let rank = config.section_rank(section_name);
+        match version.children.iter().position(|node| match &node.data {
+            Some(MarkdownToken::H3(name)) => config.section_rank(name) > rank,
+            _ => false,
+        }) {
+            Some(idx) => version.add_child_at(idx, h3),
+            None => version.add_child(h3),
+        }
+    }
+}
+
+/// The label of a conventional commit type, e.g.: "Features" for "feat"
+fn type_label(kind: &str) -> Option<&'static str> {
+    match kind {
+        "feat" => Some("Features"),
+        "fix" => Some("Bug Fixes"),
+        "perf" => Some("Performance"),
+        "refactor" => Some("Refactoring"),
+        "revert" => Some("Reverts"),
+        "docs" => Some("Documentation"),
+        "test" => Some("Tests"),
+        "build" | "ci" => Some("Build"),
+        "chore" => Some("Chores"),
+        _ => None,
+    }
+}
+
+/// Regroup the entries of a version by their conventional commit type (e.g.: "feat: ..." goes to
+/// "### Features"), instead of by section. Entries without a type are grouped by their section,
+/// "Added" entries are features and "Fixed" entries are bug fixes.
+fn group_by_type(version: &mut Node) {
+    let mut groups: Vec<(String, Node)> = vec![];
+    let mut other = vec![];
+
+    for child in std::mem::take(&mut version.children) {
+        let (section, lists) = match &child.data {
+            Some(MarkdownToken::H3(name)) => (
+                name.clone(),
+                child.filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::UnorderedList))),
+            ),
+            Some(MarkdownToken::UnorderedList) => ("Other".to_string(), vec![&child]),
+            _ => {
+                other.push(child);
+                continue;
+            }
+        };
+
+        let mut group = 0;
+        for item in lists.into_iter().flat_map(|ul| &ul.children) {
+            let item = match &item.data {
+                Some(MarkdownToken::ListItem(text, 0)) if text == NOTHING_YET => continue,
+                Some(MarkdownToken::ListItem(text, 0)) => {
+                    let typed = ConventionalCommit::parse(text).and_then(|commit| {
+                        Some((type_label(&commit.kind)?.to_string(), commit.description))
+                    });
+                    let (label, text) = typed.unwrap_or_else(|| {
+                        let label = match section.to_lowercase().as_str() {
+                            "added" => "Features",
+                            "fixed" => "Bug Fixes",
+                            _ => section.as_str(),
+                        };
+
+                        (label.to_string(), text.clone())
+                    });
+
+                    group = match groups.iter().position(|(other, _)| *other == label) {
+                        Some(idx) => idx,
+                        None => {
+                            let ul = Node::from_token(MarkdownToken::UnorderedList);
+                            groups.push((label, ul));
+                            groups.len() - 1
+                        }
+                    };
+
+                    Node::from_token(MarkdownToken::ListItem(text, 0))
+                }
+                // Nested entries stay with their parent
+                _ => item.clone(),
+            };
+
+            if let Some((_, ul)) = groups.get_mut(group) {
+                ul.add_child(item);
+            }
+        }
+    }
+
+    version.children = other;
+    for (label, ul) in groups {
+        let mut heading = Node::from_token(MarkdownToken::H3(label));
+        heading.add_child(ul);
+        version.add_child(heading);
+    }
+}
+
+/// The sub-heading (e.g.: "#### API") of a section, which is created when it doesn't exist yet
+fn sub_section<'a>(section: &'a mut Node, name: &str) -> &'a mut Node {
+    let idx = section.children.iter().position(|node| {
+        matches!(&node.data, Some(MarkdownToken::H4(other)) if other.eq_ignore_ascii_case(name))
+    });
+
+    let idx = match idx {
+        Some(idx) => idx,
+        None => {
+            section.add_child(Node::from_token(MarkdownToken::H4(name.to_string())));
+            section.children.len() - 1
+        }
+    };
+
+    &mut section.children[idx]
+}
+
+/// Whether an entry is the placeholder of an empty section, instead of a real entry
+fn is_placeholder(config: &Config, section_name: Option<&str>, text: &str) -> bool {
+    text == NOTHING_YET
+        || match section_name {
+            Some(section_name) => config.placeholder(section_name) == Some(text),
+            None => config
+                .placeholders
+                .values()
+                .any(|placeholder| placeholder == text),
+        }
+}
+
+/// The contents of a new unreleased section. When scaffolding, every section of the configured
+/// order gets its placeholder entry, otherwise there is a single "Nothing yet!" entry.
+fn unreleased_scaffold(config: &Config, scaffold: bool) -> Vec<Node> {
+    let placeholder_list = |text: &str| {
+        let mut ul = Node::from_token(MarkdownToken::UnorderedList);
+        ul.add_child(Node::from_token(MarkdownToken::ListItem(
+            text.to_string(),
+            0,
+        )));
+        ul
+    };
+
+    if !scaffold {
+        return vec![placeholder_list(NOTHING_YET)];
+    }
+
+    config
+        .section_order
+        .iter()
+        .map(|section| {
+            let mut h3 = Node::from_token(MarkdownToken::H3(config.section_case.apply(section)));
+            h3.add_child(placeholder_list(
+                config.placeholder(section).unwrap_or(NOTHING_YET),
+            ));
+            h3
+        })
+        .collect()
+}
+
+/// Insert an entry in a list at the configured position. Nested entries stay with their parent.
+fn insert_entry(ul: &mut Node, li: Node, position: InsertPosition) {
+    let idx = match (position, &li.data) {
+        (InsertPosition::Top, _) => Some(0),
+        (InsertPosition::Bottom, _) => None,
+        (InsertPosition::Sorted, Some(MarkdownToken::ListItem(entry, _))) => {
+            let key = sort_key(entry);
+            ul.children.iter().position(|item| match &item.data {
+                Some(MarkdownToken::ListItem(other, 0)) => sort_key(other) > key,
+                _ => false,
+            })
+        }
+        (InsertPosition::Sorted, _) => None,
+    };
+
+    match idx {
+        Some(idx) => ul.add_child_at(idx, li),
+        None => ul.add_child(li),
+    }
+}
+
+/// The key to keep entries sorted by: entries that reference a pull request or issue go first,
+/// by their number, the other entries follow alphabetically
+fn sort_key(entry: &str) -> (bool, Option<usize>, String) {
+    let number = entry_reference(entry)
+        .and_then(|reference| reference.strip_prefix('[')?.split_once(']'))
+        .and_then(|(label, _)| label.trim_start_matches(['#', '!']).parse().ok());
+
+    (
+        number.is_none(),
+        number,
+        strip_links(entry_title(entry)).to_lowercase(),
+    )
+}
+
+/// Capitalize the first letter of an entry and drop its trailing period, the reference of the entry
+/// is kept as-is, e.g.: "fix the parser. ([#12](...))" becomes "Fix the parser ([#12](...))"
+fn normalize_entry(entry: &str) -> String {
+    let title = entry_title(entry);
+    let (before, after) = match entry.starts_with(title) {
+        true => ("", &entry[title.len()..]),
+        false => (&entry[..entry.len() - title.len()], ""),
+    };
+
+    let mut chars = title.chars();
+    let title = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    };
+    let title = match title.ends_with('.') && !title.ends_with("..") {
+        true => &title[..title.len() - 1],
+        false => &title,
+    };
+
+    format!("{}{}{}", before, title, after)
+}
+
+/// The compare link with a different base and head, e.g.: ".../compare/v1.1.0...v1.2.0" for
+/// ".../compare/v1.0.0...HEAD". None for a link that doesn't compare two revisions.
+fn compare_link(link: &str, base: &str, head: &str) -> Option<String> {
+    let (url, range) = link.rsplit_once('/')?;
+    range
+        .contains("...")
+        .then(|| format!("{}/{}...{}", url, base, head))
+}
+
+/// Insert a version section above the other versions, after the preamble of the main heading
+fn insert_section(main: &mut Node, section: Node) {
+    let idx = main
+        .children
+        .iter()
+        .position(|node| matches!(&node.data, Some(MarkdownToken::H2(_))))
+        .unwrap_or(main.children.len());
+
+    main.add_child_at(idx, section);
+}
+
+/// Whether the node is the list with the "Nothing yet!" note of an empty version
+fn is_nothing_yet(node: &Node) -> bool {
+    matches!(&node.data, Some(MarkdownToken::UnorderedList))
+        && node.children.iter().all(|item| match &item.data {
+            Some(MarkdownToken::ListItem(text, _)) => text == NOTHING_YET,
+            _ => false,
+        })
+}
+
+/// Move the contents of a version or section into another one. Headings (e.g.: "### Added" or
+/// "#### API") with the same name are merged, the loose entries of a heading go into its first
+/// list, before its sub-headings. New sections are kept in the configured order.
+fn merge_section(into: &mut Node, from: Node, config: &Config) {
+    let heading = |node: &Node| match &node.data {
+        Some(MarkdownToken::H3(name)) => Some((3, name.to_lowercase())),
+        Some(MarkdownToken::H4(name)) => Some((4, name.to_lowercase())),
+        _ => None,
+    };
+
+    for child in from.children {
+        let first_heading = into
+            .children
+            .iter()
+            .position(|node| heading(node).is_some())
+            .unwrap_or(into.children.len());
+
+        match heading(&child) {
+            Some(name) => match into
+                .children
+                .iter_mut()
+                .find(|node| heading(node).as_ref() == Some(&name))
+            {
+                Some(existing) => merge_section(existing, child, config),
+                None => {
+                    let rank = config.section_rank(&name.1);
+                    match into.children.iter().position(|node| match &node.data {
+                        Some(MarkdownToken::H3(name)) => config.section_rank(name) > rank,
+                        _ => false,
+                    }) {
+                        Some(idx) if name.0 == 3 => into.add_child_at(idx, child),
+                        _ => into.add_child(child),
+                    }
+                }
+            },
+            None => match into.children[..first_heading].iter_mut().find(|node| {
+                matches!(&node.data, Some(MarkdownToken::UnorderedList))
+                    && matches!(&child.data, Some(MarkdownToken::UnorderedList))
+            }) {
+                Some(ul) => ul.children.extend(child.children),
+                None => into.add_child_at(first_heading, child),
+            },
+        }
+    }
+}
+
+/// Remove the placeholder entries of a version that is about to be released, including the
+/// sections that only contained a placeholder
+fn strip_placeholders(version: &mut Node, config: &Config) {
+    for section in version.children.iter_mut() {
+        let section_name = match &section.data {
+            Some(MarkdownToken::H3(name)) => name.to_string(),
+            _ => continue,
+        };
+
+        for ul in section.children.iter_mut() {
+            ul.children.retain(|li| match &li.data {
+                Some(MarkdownToken::ListItem(text, _)) => {
+                    !is_placeholder(config, Some(&section_name), text)
+                }
+                _ => true,
+            });
+        }
+
+        section.children.retain(|node| {
+            !matches!(&node.data, Some(MarkdownToken::UnorderedList)) || !node.children.is_empty()
+        });
+    }
+
+    version.children.retain(|node| {
+        !matches!(&node.data, Some(MarkdownToken::H3(_))) || !node.children.is_empty()
+    });
+
+    if version.children.is_empty() {
+        version.children = unreleased_scaffold(config, false);
+    }
+}
+
+/// Merge the entries of a section that share the same reference into a single entry, e.g.: when
+/// the same pull request was added multiple times from its commits
+fn dedupe_references(version: &mut Node) {
+    for section in version.children.iter_mut() {
+        for ul in section.children.iter_mut() {
+            let mut items: Vec<Node> = vec![];
+
+            for li in ul.children.drain(..) {
+                let existing = match &li.data {
+                    Some(MarkdownToken::ListItem(text, 0)) => {
+                        entry_reference(text).and_then(|reference| {
+                            items.iter_mut().find(|other| {
+                                matches!(
+                                    &other.data,
+                                    Some(MarkdownToken::ListItem(other, 0))
+                                        if entry_reference(other) == Some(reference)
+                                )
+                            })
+                        })
+                    }
+                    _ => None,
+                };
+
+                match (existing, &li.data) {
+                    (Some(existing), Some(MarkdownToken::ListItem(text, _))) => {
+                        if let Some(MarkdownToken::ListItem(other, _)) = &mut existing.data {
+                            *other = merge_entries(other, text);
+                        }
+                    }
+                    _ => items.push(li),
+                }
+            }
+
+            ul.children = items;
+        }
+    }
+}
+
+/// Combine the titles of two entries with the same reference, keeping the placement of the
+/// reference of the first entry. A tag that both titles start with (e.g.: "[api] ") is only kept
+/// once.
+fn merge_entries(entry: &str, other: &str) -> String {
+    let reference = entry_reference(entry).unwrap_or_default();
+    let other_title = match entry_tag(entry_title(entry)) {
+        Some(tag) => entry_title(other).strip_prefix(tag),
+        None => None,
+    };
+    let title = format!(
+        "{}; {}",
+        entry_title(entry),
+        other_title.unwrap_or(entry_title(other))
+    );
+
+    match entry.starts_with(reference) && !reference.is_empty() {
+        true => format!("{}: {}", reference, title),
+        false => format!("{} ({})", title, reference),
+    }
+}
+
+/// The tag at the start of the title of an entry, e.g.: "[api] " for "[api] Add tables". Links
+/// aren't tags.
+fn entry_tag(title: &str) -> Option<&str> {
+    let end = title.strip_prefix('[')?.find("] ")? + 3;
+
+    (!title[..end].contains("](")).then(|| &title[..end])
+}
+
+/// The version of a section, or the full heading for sections without a version like "Unreleased"
+fn version_name<'a>(node: &'a Node, config: &Config) -> &'a str {
+    match (section_version(node, config), &node.data) {
+        (Some(version), _) => version,
+        (None, Some(MarkdownToken::H2(heading))) => heading,
+        _ => "",
+    }
+}
+
+/// The version of a version heading, e.g.: "1.2.3" for "[1.2.3] - 2022-01-01", or for a custom
+/// `version_heading` like "v1.2.3 (2022-01-01)"
+fn section_version<'a>(node: &'a Node, config: &Config) -> Option<&'a str> {
+    match &node.data {
+        Some(MarkdownToken::H2(heading)) => match heading.strip_prefix('[') {
+            Some(heading) => heading.split(']').next(),
+            None => {
+                // The configured `version_heading`, e.g.: "Release 1.2.3 (2022-01-01)", or the
+                // version at the start of the heading
+                let name = match config.parse_version_heading(heading) {
+                    Some((version, _)) => version,
+                    None => heading.split_whitespace().next()?,
+                };
+                let number = name.rsplit_once('@').map_or(name, |(_, number)| number);
+                number.trim_start_matches('v').parse::<Version>().ok()?;
+
+                match name.contains('@') {
+                    true => Some(name),
+                    false => Some(name.trim_start_matches('v')),
+                }
+            }
+        },
+        _ => None,
+    }
+}
+
+/// Put the version headings at "##" and the section headings (of the known `sections`) at "###",
+/// e.g.: in a hand-edited changelog. Returns the fixed contents, and the corrections as the line
+/// number, the old heading and the new heading.
+pub fn fix_heading_levels(
+    contents: &str,
+    sections: &[String],
+) -> (String, Vec<(usize, String, String)>) {
+    let mut corrections = vec![];
+    let mut in_code = false;
+
+    let lines: Vec<String> = contents
+        .split('\n')
+        .enumerate()
+        .map(|(idx, line)| {
+            if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+                in_code = !in_code;
+            }
+
+            let level = line.chars().take_while(|c| *c == '#').count();
+            let text = match line[level..].strip_prefix(' ') {
+                Some(text) if !in_code && level > 0 => text.trim_end(),
+                _ => return line.to_string(),
+            };
+
+            let name = text
+                .trim_start_matches('[')
+                .split([']', ' '])
+                .next()
+                .unwrap_or_default();
+            let number = name.rsplit_once('@').map_or(name, |(_, number)| number);
+            let expected = if name.eq_ignore_ascii_case(UNRELEASED_HEADING)
+                || number.trim_start_matches('v').parse::<Version>().is_ok()
+            {
+                2
+            } else if sections
+                .iter()
+                .any(|section| section.eq_ignore_ascii_case(text))
+            {
+                3
+            } else {
+                return line.to_string();
+            };
+
+            if level == expected {
+                return line.to_string();
+            }
+
+            let fixed = format!("{}{}", "#".repeat(expected), &line[level..]);
+            corrections.push((
+                idx + 1,
+                line.trim_end().to_string(),
+                fixed.trim_end().to_string(),
+            ));
+            fixed
+        })
+        .collect();
+
+    (lines.join("\n"), corrections)
+}
+
+/// The duplicate and misordered version headings of a changelog file, with their line numbers.
+/// Versions should be ordered from the newest to the oldest, per package in monorepos.
+pub fn version_heading_problems(contents: &str) -> Vec<Problem> {
+    let mut problems = vec![];
+    // The versions that were seen so far, with their package and line number
+    let mut seen: Vec<(&str, &str, Version, usize)> = vec![];
+
+    for (idx, line) in contents.lines().enumerate() {
+        let name = match line
+            .strip_prefix("## [")
+            .and_then(|heading| heading.split(']').next())
+        {
+            Some(name) => name,
+            None => continue,
+        };
+        let (package, number) = name.rsplit_once('@').unwrap_or(("", name));
+        let version: Version = match number.trim_start_matches('v').parse() {
+            Ok(version) => version,
+            Err(_) => continue,
+        };
+        let line_number = idx + 1;
+
+        if let Some((_, _, _, first)) = seen
+            .iter()
+            .find(|(other_package, _, other, _)| *other_package == package && *other == version)
+        {
+            problems.push(Problem::Warning(format!(
+                "Duplicate version {} on line {}, it was already on line {}",
+                name, line_number, first
+            )));
+        } else if let Some((_, previous, _, previous_line)) = seen
+            .iter()
+            .rev()
+            .find(|(other_package, _, _, _)| *other_package == package)
+            .filter(|(_, _, other, _)| other < &version)
+        {
+            problems.push(Problem::Warning(format!(
+                "Version {} on line {} should come before {} on line {}",
+                name, line_number, previous, previous_line
+            )));
+        }
+
+        seen.push((package, name, version, line_number));
+    }
+
+    problems
+}
+
+/// Whether the node is the paragraph that links to the versions that were split into separate
+/// files
+fn is_older_versions(node: &Node) -> bool {
+    matches!(&node.data, Some(MarkdownToken::Paragraph(text)) if text.starts_with(OLDER_VERSIONS))
+}
+
+/// The date of a version heading, e.g.: "2022-01-01" for "[1.0.0] - 2022-01-01"
+fn section_date<'a>(node: &'a Node, config: &Config) -> Option<&'a str> {
+    match &node.data {
+        Some(MarkdownToken::H2(heading)) => {
+            let configured = config.parse_version_heading(heading);
+            if let Some((_, Some(date))) = configured.filter(|_| heading_link(heading).is_none()) {
+                return Some(date);
+            }
+
+            let rest = match (heading_link(heading), heading.strip_prefix('[')) {
+                (Some((_, rest)), _) => rest,
+                (None, Some(heading)) => heading.split_once(']')?.1,
+                // The text after the version, e.g.: "(2022-01-01)" for "Release 1.2.3 (2022-01-01)"
+                (None, None) => match configured {
+                    Some((version, _)) => heading.split_once(version)?.1,
+                    None => heading.split_once(' ')?.1,
+                },
+            };
+            let date =
+                rest.trim_matches(|c: char| c.is_whitespace() || matches!(c, '-' | '(' | ')'));
+
+            (!date.is_empty()).then_some(date)
+        }
+        _ => None,
+    }
+}
+
+/// The inline link of a version heading, e.g.: "https://..." for
+/// "[1.0.0](https://...) - 2022-01-01"
+fn section_link(node: &Node) -> Option<&str> {
+    match &node.data {
+        Some(MarkdownToken::H2(heading)) => heading_link(heading).map(|(link, _)| link),
+        _ => None,
+    }
+}
+
+/// The inline link of a heading and the rest of the heading after the link, e.g.: "https://..."
+/// and " - 2022-01-01" for "[1.0.0](https://...) - 2022-01-01"
+fn heading_link(heading: &str) -> Option<(&str, &str)> {
+    let (_, rest) = heading.strip_prefix('[')?.split_once("](")?;
+    rest.split_once(')')
+}
+
+/// The heading without its inline link, e.g.: "[Unreleased]" for "[Unreleased](https://...)"
+fn strip_heading_link(heading: &str) -> Cow<'_, str> {
+    match (heading.split_once("]("), heading_link(heading)) {
+        (Some((name, _)), Some((_, rest))) => Cow::Owned(format!("{}]{}", name, rest)),
+        _ => Cow::Borrowed(heading),
+    }
+}
+
+/// Whether the node is the reference of a version (e.g.: "[1.0.0]: https://..."), instead of the
+/// definition of a reference-style link in an entry (e.g.: "[1]: https://...")
+fn is_version_reference(node: &Node) -> bool {
+    node.data
+        .as_ref()
+        .is_some_and(MarkdownToken::is_version_reference)
+}
+
+/// The position to insert a node with the given version at, so that the nodes matching the
+/// predicate stay ordered from the newest to the oldest version.
+fn insert_position<F>(nodes: &[Node], version: &str, config: &Config, predicate: F) -> usize
+where
+    F: Fn(&Node) -> bool,
+{
+    let version: Option<Version> = version.parse().ok();
+    let version_of = |node: &Node| -> Option<Version> {
+        match &node.data {
+            Some(MarkdownToken::Reference(name, _)) => name.parse().ok(),
+            _ => section_version(node, config)?.parse().ok(),
+        }
+    };
+
+    nodes
+        .iter()
+        .position(|node| {
+            predicate(node)
+                && match (&version, version_of(node)) {
+                    (Some(version), Some(other)) => &other < version,
+                    _ => false,
+                }
+        })
+        .or_else(|| nodes.iter().rposition(&predicate).map(|idx| idx + 1))
+        .unwrap_or(nodes.len())
+}
+
+impl Display for Changelog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.root)
+    }
+}
+
+fn unreleased_heading(scope: Option<&PackageJSON>) -> String {
+    match scope {
+        Some(scope) => format!("[{} - {}]", UNRELEASED_HEADING, scope.name()),
+        None => format!("[{}]", UNRELEASED_HEADING),
+    }
+}
+
+/// Whether the node is the (H2) section of the given version. When no version is given, this
+/// matches the unreleased section if it has entries, otherwise the latest version.
+fn is_section(
+    node: &Node,
+    name: Option<&String>,
+    scope: Option<&PackageJSON>,
+    config: &Config,
+) -> bool {
+    if let Some(MarkdownToken::H2(section_name)) = &node.data {
+        match name {
+            Some(name) => {
+                if name.eq_ignore_ascii_case("latest") {
+                    !strip_heading_link(section_name)
+                        .eq_ignore_ascii_case(&unreleased_heading(scope))
+                } else {
+                    match scope {
+                        Some(scope) if !scope.is_root() => section_name
+                            .to_lowercase()
+                            .starts_with(&format!("[{}@v{}]", scope.name(), name.to_lowercase())),
+                        _ if !section_name.starts_with('[') => section_version(node, config)
+                            .is_some_and(|version| {
+                                version
+                                    .trim_start_matches('v')
+                                    .eq_ignore_ascii_case(name.trim_start_matches('v'))
+                            }),
+                        _ => section_name
+                            .to_lowercase()
+                            .starts_with(&format!("[{}]", name.to_lowercase())),
+                    }
+                }
+            }
+            None => {
+                if strip_heading_link(section_name).eq_ignore_ascii_case(&unreleased_heading(scope))
+                {
+                    // A scaffolded section only holds placeholders, so it isn't the latest notes
+                    node.find_node(|section| match &section.data {
+                        Some(MarkdownToken::H3(section_name)) => section
+                            .find_node(|item| match &item.data {
+                                Some(MarkdownToken::ListItem(text, _)) => {
+                                    !is_placeholder(config, Some(section_name), text)
+                                }
+                                _ => false,
+                            })
+                            .is_some(),
+                        _ => false,
+                    })
+                    .is_some()
+                } else {
+                    true
+                }
+            }
+        }
+    } else {
+        false
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InitOptions {
+    /// Scaffold the unreleased section, or add it to an existing changelog without one
+    pub ensure: bool,
+
+    /// Overwrite an existing changelog
+    pub force: bool,
+
+    /// Don't back up an overwritten changelog
+    pub no_backup: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    /// List the unreleased section at the top, with its amount of entries
+    pub include_unreleased: bool,
+
+    /// List the versions from the oldest to the newest
+    pub reverse: bool,
+
+    /// Take the requested amount of versions from the oldest instead of the newest
+    pub oldest: bool,
+
+    /// Truncate the rows that are wider than this, e.g.: the width of the terminal
+    pub max_width: Option<usize>,
+
+    /// Only list the versions with an entry that contains this text, ignoring the case
+    pub contains: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct NotesOptions {
+    /// Render links as plain text
+    pub strip_links: bool,
+
+    /// How to group the entries of a version
+    pub group_by: GroupBy,
+
+    /// Render every configured section, with a "_None_" entry in the sections without entries
+    pub include_empty_sections: bool,
+}
+
+/// How to group the entries of a version when rendering notes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    /// The sections of the changelog, e.g.: "### Added"
+    #[default]
+    Section,
+
+    /// The conventional commit type of the entries, e.g.: "### Features" for "feat: ..."
+    Type,
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "section" => Ok(GroupBy::Section),
+            "type" => Ok(GroupBy::Type),
+            _ => Err(format!("Invalid grouping: {}", s)),
+        }
+    }
+}
+
+/// A change between two versions of the same changelog file
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change {
+    Added(String),
+    Removed(String),
+}
+
+/// A problem in the structure of a changelog
+#[derive(Debug, PartialEq, Eq)]
+pub enum Problem {
+    /// The changelog doesn't work with the other commands, e.g.: an invalid version
+    Error(String),
+
+    /// The changelog works, but doesn't follow the conventions, e.g.: an unknown section
+    Warning(String),
+}
+
+impl Problem {
+    pub fn is_error(&self) -> bool {
+        matches!(self, Problem::Error(_))
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::Error(message) => write!(f, "{} {}", "error:".red().bold(), message),
+            Problem::Warning(message) => write!(f, "{} {}", "warning:".yellow().bold(), message),
+        }
+    }
+}
+
+/// The amount of entries of a version
+#[derive(Debug, PartialEq, Eq)]
+pub struct EntryCount {
+    pub total: usize,
+
+    /// The amount of entries per section, in the order of the changelog
+    pub sections: Vec<(String, usize)>,
+}
+
+impl EntryCount {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "total": self.total,
+            "sections": self
+                .sections
+                .iter()
+                .map(|(name, count)| (name.clone(), serde_json::json!(count)))
+                .collect::<serde_json::Map<_, _>>(),
+        })
+    }
+
+    /// The sections with their amount of entries on a single line, e.g.: "Added (3), Fixed (2)"
+    pub fn summary(&self) -> String {
+        match self.sections.is_empty() {
+            true => "No entries".to_string(),
+            false => self
+                .sections
+                .iter()
+                .map(|(name, count)| format!("{} ({})", name, count))
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+}
+
+impl Display for EntryCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.total,
+            if self.total == 1 { "entry" } else { "entries" }
+        )?;
+
+        if self.sections.is_empty() {
+            return Ok(());
+        }
+
+        // Align the amounts of the sections in a column, like the rows of `list`
+        let rows: Vec<_> = self
+            .sections
+            .iter()
+            .map(|(name, count)| vec![format!("- {}:", name), count.to_string()])
+            .collect();
+
+        write!(f, "\n{}", table(&rows, None))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFormat {
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(format!("Invalid export format: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NotesFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+impl FromStr for NotesFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" => Ok(NotesFormat::Markdown),
+            "json" => Ok(NotesFormat::Json),
+            _ => Err(format!("Invalid notes format: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ReleaseOptions {
+    /// Omit the date from the heading of the released version
+    pub no_date: bool,
+
+    /// Scaffold the new unreleased section with the placeholder entry of every section
+    pub scaffold: bool,
+
+    /// Merge the entries of the released version that share the same reference
+    pub dedupe_references: bool,
+
+    /// Show the release hooks that are run
+    pub verbose: bool,
+
+    /// Release an empty version when the changelog has no unreleased section, instead of failing
+    pub create_empty: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Amount {
+    All,
+    Value(usize),
+}
+
+impl FromStr for Amount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "all" => Amount::All,
+            _ => Amount::Value(s.parse::<usize>().map_err(|_| "Invalid amount")?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{SectionCase, VersionFile};
+
+    /// A changelog with the given contents, without a file
+    fn changelog(contents: &str, config: Config) -> Changelog {
+        Changelog {
+            root: Node::from_str(contents).unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config,
+        }
+    }
+
+    /// A changelog file in the temp dir, that is removed when it is dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new() -> Self {
+            Self(std::env::temp_dir().join(format!("{}.md", uuid::Uuid::new_v4())))
+        }
+
+        /// A changelog with the given contents, that is written to this file
+        fn changelog(&self, contents: &str, config: Config) -> Changelog {
+            Changelog {
+                file_path: self.0.clone(),
+                ..changelog(contents, config)
+            }
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn it_should_find_the_latest_version() {
+        let c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+
+        let latest_version = c.find_latest_version();
+        assert_eq!(latest_version, Some("0.1.0"));
+    }
+
+    #[test]
+    fn it_should_get_the_contents_of_a_section() {
+        let c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+
+        let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
+        assert!(unreleased_section.is_some());
+
+        let unreleased_section = unreleased_section.unwrap();
+        assert_eq!(
+            unreleased_section,
+            Node::from_str("- Nothing yet!").unwrap()
+        );
+
+        let first_release = c.get_contents_of_section(&Some("0.1.0".to_string()));
+        assert!(first_release.is_some());
+
+        let first_release = first_release.unwrap();
+        assert_eq!(
+            first_release,
+            Node::from_str("### Added\n- Everything!").unwrap()
+        );
+    }
+
+    #[test]
+    fn it_should_generate_a_list_of_releases() {
+        let c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+
+        assert_eq!(
+            c.list(Amount::All, &ListOptions::default()).unwrap(),
+            [
+                "- unreleased            https://github.com/RobinMalfait/changelog/compare/v0.1.0...HEAD",
+                "- 0.1.0      2022-01-09 https://github.com/RobinMalfait/changelog/releases/tag/v0.1.0"
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn it_should_be_possible_to_add_something_to_a_section() {
+        let mut c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+
+        let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
+        assert!(unreleased_section.is_some());
+        let unreleased_section = unreleased_section.unwrap();
+
+        assert_eq!(
+            unreleased_section,
+            Node {
+                data: None,
+                children: vec![Node {
+                    data: Some(MarkdownToken::UnorderedList,),
+                    children: vec![Node {
+                        data: Some(MarkdownToken::ListItem("Nothing yet!".to_string(), 0)),
+                        children: vec![],
+                    }],
+                }],
+            }
+        );
+
+        c.add_list_item_to_section("Added", "Something new", false, None);
+
+        let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
+        assert!(unreleased_section.is_some());
+        let unreleased_section = unreleased_section.unwrap();
+
+        assert_eq!(
+            unreleased_section,
+            Node {
+                data: None,
+                children: vec![Node {
+                    data: Some(MarkdownToken::H3("Added".to_string())),
+                    children: vec![Node {
+                        data: Some(MarkdownToken::UnorderedList),
+                        children: vec![Node {
+                            data: Some(MarkdownToken::ListItem("Something new".to_string(), 0)),
+                            children: vec![],
+                        }],
+                    }],
+                }],
+            }
+        );
+
+        c.add_list_item_to_section("Added", "Something newer", false, None);
+
+        let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
+        assert!(unreleased_section.is_some());
+        let unreleased_section = unreleased_section.unwrap();
+
+        assert_eq!(
+            unreleased_section,
+            Node {
+                data: None,
+                children: vec![Node {
+                    data: Some(MarkdownToken::H3("Added".to_string())),
+                    children: vec![Node {
+                        data: Some(MarkdownToken::UnorderedList),
+                        children: vec![
+                            Node {
+                                data: Some(MarkdownToken::ListItem("Something new".to_string(), 0)),
+                                children: vec![],
+                            },
+                            Node {
+                                data: Some(MarkdownToken::ListItem(
+                                    "Something newer".to_string(),
+                                    0
+                                )),
+                                children: vec![],
+                            }
+                        ],
+                    }],
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn it_should_merge_two_changelogs() {
+        let mut c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+        c.add_list_item_to_section("Added", "Something new", false, None);
+
+        let other = changelog(
+            &include_str!("../CHANGELOG.md")
+                .replace(
+                    "- Nothing yet!",
+                    "### Added\n\n- Something new\n- Something else\n\n## [0.2.0] - 2022-02-01\n\n### Fixed\n\n- A bug",
+                )
+                .replace(
+                    "[0.1.0]: ",
+                    "[0.2.0]: https://github.com/RobinMalfait/changelog/compare/v0.1.0...v0.2.0\n[0.1.0]: ",
+                ),
+            Config::default(),
+        );
+
+        let changes = c.merge(&other, None).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                "Added \"Something else\" to Added".to_string(),
+                "Added version 0.2.0".to_string()
+            ]
+        );
+
+        assert_eq!(
+            c.list(Amount::All, &ListOptions::default()).unwrap(),
+            ["- unreleased            https://github.com/RobinMalfait/changelog/compare/v0.1.0...HEAD",
+                "- 0.2.0      2022-02-01 https://github.com/RobinMalfait/changelog/compare/v0.1.0...v0.2.0",
+                "- 0.1.0      2022-01-09 https://github.com/RobinMalfait/changelog/releases/tag/v0.1.0"]
+            .join("\n")
+        );
+        assert_eq!(
+            c.get_contents_of_section(&Some("0.2.0".to_string())),
+            Some(Node::from_str("### Fixed\n- A bug").unwrap())
+        );
+
+        // Merging again is a no-op
+        assert!(c.merge(&other, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_should_release_without_a_date() {
+        let file = TempFile::new();
+        let mut c = file.changelog(include_str!("../CHANGELOG.md"), Config::default());
+        c.add_list_item_to_section("Added", "Something new", false, None);
+
+        let options = ReleaseOptions {
+            no_date: true,
+            ..Default::default()
+        };
+        c.release(&"0.2.0".parse().unwrap(), None, &options)
+            .unwrap();
+
+        assert!(c
+            .root
+            .find_node(|node| node.data == Some(MarkdownToken::H2("[0.2.0]".to_string())))
+            .is_some());
+        assert_eq!(
+            c.get_contents_of_section(&Some("0.2.0".to_string())),
+            Some(Node::from_str("### Added\n- Something new").unwrap())
+        );
+    }
+
+    #[test]
+    fn it_should_only_release_without_an_unreleased_section_when_creating_an_empty_version() {
+        let file = TempFile::new();
+        let mut c = file.changelog(
+            concat!(
+                "# Changelog\n\n## [0.1.0] - 2022-01-09\n\n### Added\n\n- Everything!\n\n",
+                "[unreleased]: https://github.com/RobinMalfait/changelog/compare/v0.1.0...HEAD\n",
+                "[0.1.0]: https://github.com/RobinMalfait/changelog/releases/tag/v0.1.0"
+            ),
+            Config::default(),
+        );
+        let version = "0.2.0".parse().unwrap();
+
+        assert!(c
+            .release(&version, None, &ReleaseOptions::default())
+            .is_err());
+        assert!(!c.has_version("0.2.0"));
+
+        c.release(
+            &version,
+            None,
+            &ReleaseOptions {
+                no_date: true,
+                create_empty: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(c.has_unreleased());
+        assert!(c.has_version("0.2.0"));
+        assert!(c.root.to_string().contains(
+            "[0.2.0]: https://github.com/RobinMalfait/changelog/compare/v0.1.0...v0.2.0"
+        ));
+    }
+
+    #[test]
+    fn it_should_release_a_changelog_without_references() {
+        let file = TempFile::new();
+        let mut c = file.changelog(
+            "# Changelog\n\n## [Unreleased]\n\n- B\n\n## [0.1.0] - 2022-01-09\n\n- A",
+            Config::default(),
+        );
+
+        c.release(
+            &"0.2.0".parse().unwrap(),
+            None,
+            &ReleaseOptions {
+                no_date: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(c.has_version("0.2.0"));
+        assert!(!c.root.to_string().contains("]: "));
+    }
+
+    #[test]
+    fn it_should_release_with_a_custom_version_heading() {
+        let file = TempFile::new();
+        let mut c = file.changelog(
+            include_str!("../CHANGELOG.md"),
+            Config {
+                version_heading: "## v{version} ({date})".to_string(),
+                ..Default::default()
+            },
+        );
+        c.add_list_item_to_section("Added", "Something new", false, None);
+        c.release(&"0.2.0".parse().unwrap(), None, &ReleaseOptions::default())
+            .unwrap();
+
+        let released = &c.versions()[1];
+        let date = Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(
+            released.data,
+            Some(MarkdownToken::H2(format!("v0.2.0 ({})", date)))
+        );
+        assert_eq!(section_version(released, &c.config), Some("0.2.0"));
+        assert_eq!(section_date(released, &c.config), Some(date.as_str()));
+        assert_eq!(
+            c.get_contents_of_section(&Some("0.2.0".to_string())),
+            Some(Node::from_str("### Added\n- Something new").unwrap())
+        );
+    }
+
+    #[test]
+    fn it_should_not_release_when_a_version_file_is_missing() {
+        let file = TempFile::new();
+        let mut c = Changelog {
+            pwd: std::env::temp_dir(),
+            ..file.changelog(
+                include_str!("../CHANGELOG.md"),
+                Config {
+                    version_files: vec![VersionFile {
+                        path: format!("{}.md", uuid::Uuid::new_v4()),
+                        pattern: "version-{version}".to_string(),
+                    }],
+                    ..Default::default()
+                },
+            )
+        };
+
+        let result = c.release(&"0.2.0".parse().unwrap(), None, &ReleaseOptions::default());
+        assert!(matches!(result, Err(ChangelogError::NotFound(_))));
+        assert!(!file.0.exists());
+    }
+
+    #[test]
+    fn it_should_read_the_notes_of_a_custom_version_heading() {
+        let file = TempFile::new();
+        let mut c = file.changelog(
+            include_str!("../CHANGELOG.md"),
+            Config {
+                version_heading: "## Release {version} ({date})".to_string(),
+                ..Default::default()
+            },
+        );
+        c.add_list_item_to_section("Added", "Something new", false, None);
+        c.release(&"0.2.0".parse().unwrap(), None, &ReleaseOptions::default())
+            .unwrap();
+        c.add_list_item_to_section("Fixed", "A bug", false, None);
+        c.release(&"0.3.0".parse().unwrap(), None, &ReleaseOptions::default())
+            .unwrap();
+
+        let released = &c.versions()[1];
+        let date = Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(section_version(released, &c.config), Some("0.3.0"));
+        assert_eq!(section_date(released, &c.config), Some(date.as_str()));
+        assert_eq!(c.version_date("0.3.0"), Some(date.as_str()));
+
+        let notes = |version: Option<&str>| {
+            c.notes(
+                version.map(|version| version.to_string()).as_ref(),
+                &NotesOptions::default(),
+            )
+            .unwrap()
+        };
+        assert_eq!(notes(None), "### Fixed\n\n- A bug\n");
+        assert_eq!(notes(Some("0.3.0")), "### Fixed\n\n- A bug\n");
+        assert_eq!(notes(Some("v0.2.0")), "### Added\n\n- Something new\n");
+    }
+
+    #[test]
+    fn it_should_base_compare_links_on_the_configured_source() {
+        let release = |compare_base: CompareBase| {
+            let file = TempFile::new();
+            let mut c = file.changelog(
+                "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- C\n\n## [1.1.0]\n\n- B\n\n## [1.0.0]\n\n- A\n\n[unreleased]: https://github.com/o/r/compare/v1.0.0...HEAD\n[1.1.0]: https://github.com/o/r/compare/v1.0.0...v1.1.0\n[1.0.0]: https://github.com/o/r/releases/tag/v1.0.0",
+                Config {
+                    compare_base,
+                    ..Config::default()
+                },
+            );
+            c.release(&"1.2.0".parse().unwrap(), None, &ReleaseOptions::default())
+                .unwrap();
+
+            c.root
+                .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::Reference(_, _))))
+                .into_iter()
+                .take(2)
+                .map(|node| node.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            release(CompareBase::Changelog),
+            vec![
+                "[unreleased]: https://github.com/o/r/compare/v1.2.0...HEAD",
+                "[1.2.0]: https://github.com/o/r/compare/v1.1.0...v1.2.0",
+            ]
+        );
+        assert_eq!(
+            release(CompareBase::Tags),
+            vec![
+                "[unreleased]: https://github.com/o/r/compare/v1.0.0...HEAD",
+                "[1.2.0]: https://github.com/o/r/compare/v1.0.0...v1.2.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_replace_scaffolded_placeholders_with_entries() {
+        let file = TempFile::new();
+        let mut config = Config {
+            section_order: vec!["Added".to_string(), "Fixed".to_string()],
+            ..Config::default()
+        };
+        config
+            .placeholders
+            .insert("Added".to_string(), "_Nothing yet_".to_string());
+        let mut c = file.changelog(include_str!("../CHANGELOG.md"), config);
+
+        let options = ReleaseOptions {
+            no_date: true,
+            scaffold: true,
+            ..Default::default()
+        };
+        c.release(&"0.2.0".parse().unwrap(), None, &options)
+            .unwrap();
+        assert_eq!(
+            c.get_contents_of_section(&Some("unreleased".to_string())),
+            Some(
+                Node::from_str("### Added\n- _Nothing yet_\n\n### Fixed\n- Nothing yet!").unwrap()
+            )
+        );
+        assert_eq!(c.count(None, None).unwrap().total, 0);
+        assert_eq!(c.version_date("0.2.0"), None);
+        assert_eq!(
+            c.notes(None, &NotesOptions::default()).unwrap(),
+            c.notes(Some(&"0.2.0".to_string()), &NotesOptions::default())
+                .unwrap()
+        );
+
+        c.add_list_item_to_section("Fixed", "A bug", false, None);
+        assert!(c
+            .notes(None, &NotesOptions::default())
+            .unwrap()
+            .contains("A bug"));
+        assert_eq!(
+            c.get_contents_of_section(&Some("unreleased".to_string())),
+            Some(Node::from_str("### Added\n- _Nothing yet_\n\n### Fixed\n- A bug").unwrap())
+        );
+
+        c.release(&"0.3.0".parse().unwrap(), None, &options)
+            .unwrap();
+
+        assert_eq!(
+            c.get_contents_of_section(&Some("0.3.0".to_string())),
+            Some(Node::from_str("### Fixed\n- A bug").unwrap())
+        );
+    }
+
+    #[test]
+    fn it_should_dedupe_the_references_of_a_release() {
+        let file = TempFile::new();
+        let mut c = file.changelog(include_str!("../CHANGELOG.md"), Config::default());
+        let reference = "[#12](https://github.com/org/repo/pull/12)";
+        c.add_list_item_to_section("Added", &format!("Add tables ({})", reference), false, None);
+        c.add_list_item_to_section("Added", "Something new", false, None);
+        c.add_list_item_to_section("Added", &format!("Add rows ({})", reference), false, None);
+        c.add_list_item_to_section("Fixed", &format!("Fix tables ({})", reference), false, None);
+
+        let options = ReleaseOptions {
+            no_date: true,
+            dedupe_references: true,
+            ..Default::default()
+        };
+        c.release(&"0.2.0".parse().unwrap(), None, &options)
+            .unwrap();
+
+        assert_eq!(
+            c.get_contents_of_section(&Some("0.2.0".to_string())),
+            Some(
+                Node::from_str(&format!(
+                    "### Added\n- Add tables; Add rows ({0})\n- Something new\n\n### Fixed\n- Fix tables ({0})",
+                    reference
+                ))
+                .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn it_should_decorate_new_entries_and_dedupe_them() {
+        let file = TempFile::new();
+        let mut c = file.changelog(
+            include_str!("../CHANGELOG.md"),
+            Config {
+                entry_prefix: "[api] ".to_string(),
+                ..Default::default()
+            },
+        );
+        let reference = "[#12](https://github.com/org/repo/pull/12)";
+        c.add_list_item_to_section("Added", &format!("Add tables ({})", reference), false, None);
+        c.add_list_item_to_section("Added", &format!("Add rows ({})", reference), false, None);
+        // Entries that already have the prefix aren't decorated again
+        c.add_list_item_to_section("Added", "[api] Add columns", false, None);
+
+        let options = ReleaseOptions {
+            no_date: true,
+            dedupe_references: true,
+            ..Default::default()
+        };
+        c.release(&"0.2.0".parse().unwrap(), None, &options)
+            .unwrap();
+
+        assert_eq!(
+            c.get_contents_of_section(&Some("0.2.0".to_string())),
+            Some(
+                Node::from_str(&format!(
+                    "### Added\n- [api] Add tables; Add rows ({})\n- [api] Add columns",
+                    reference
+                ))
+                .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn it_should_only_consider_newer_versions_as_upcoming() {
+        let c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+
+        assert_eq!(c.resolve_version("v0.1.0"), Some("0.1.0".to_string()));
+        assert_eq!(c.resolve_version("0.1.0"), Some("0.1.0".to_string()));
+        assert_eq!(c.resolve_version("v0.2.0"), None);
+
+        // Released versions, with or without their tag prefix, don't fall back
+        assert!(!c.is_upcoming_version("0.1.0"));
+        assert!(!c.is_upcoming_version("v0.1.0"));
+        assert!(!c.is_upcoming_version("0.0.9"));
+        assert!(!c.is_upcoming_version("latest"));
+
+        assert!(c.is_upcoming_version("0.2.0"));
+        assert!(c.is_upcoming_version("v0.2.0"));
+    }
+
+    #[test]
+    fn it_should_remind_to_release_when_there_are_too_many_entries() {
+        let mut c = changelog(
+            include_str!("../CHANGELOG.md"),
+            Config {
+                max_entries: Some(2),
+                ..Config::default()
+            },
+        );
+        c.add_list_item_to_section("Added", "Something new", false, None);
+        c.add_list_item_to_section("Fixed", "A bug", false, None);
+        assert_eq!(c.release_reminder(None), None);
+
+        c.add_list_item_to_section("Fixed", "Another bug", false, None);
+        assert!(c.release_reminder(None).unwrap().contains("has 3 entries"));
+
+        // The unreleased section of a package in a monorepo
+        let package: PackageJSON =
+            serde_json::from_str(r#"{ "name": "pkg", "version": "1.0.0" }"#).unwrap();
+        let mut c = Changelog {
+            root: Node::from_str(
+                "# Changelog\n\n## [Unreleased - pkg]\n\n### Added\n\n- A\n- B\n\n## [Unreleased]\n\n- Nothing yet!",
+            )
+            .unwrap(),
+            ..c
+        };
+        assert_eq!(c.release_reminder(Some(&package)), None);
+
+        c.add_list_item_to_section_scope("Fixed", None, "C".to_string(), Some(&package));
+        assert!(c
+            .release_reminder(Some(&package))
+            .unwrap()
+            .contains("has 3 entries"));
+        assert_eq!(c.release_reminder(None), None);
+    }
+
+    #[test]
+    fn it_should_abort_the_release_when_a_pre_release_hook_fails() {
+        let file = TempFile::new();
+        let mut c = Changelog {
+            pwd: std::env::temp_dir(),
+            ..file.changelog(
+                include_str!("../CHANGELOG.md"),
+                Config {
+                    pre_release: vec!["test \"$CHANGELOG_VERSION\" = 0.2.0".to_string()],
+                    ..Config::default()
+                },
+            )
+        };
+
+        assert!(c
+            .release(&"0.3.0".parse().unwrap(), None, &ReleaseOptions::default())
+            .is_err());
+        assert!(!file.0.exists());
+
+        c.release(&"0.2.0".parse().unwrap(), None, &ReleaseOptions::default())
+            .unwrap();
+
+        assert_eq!(c.latest_release(), Some("0.2.0"));
+    }
+
+    #[test]
+    fn it_should_list_the_unreleased_section() {
+        let mut c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+        c.add_list_item_to_section("Added", "Something new", false, None);
+        c.add_list_item_to_section("Fixed", "A bug", false, None);
+
+        let options = ListOptions {
+            include_unreleased: true,
+            ..Default::default()
+        };
+        let list = c.list(Amount::Value(1), &options).unwrap();
+        let rows: Vec<_> = list.lines().collect();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], "- unreleased            — (2 entries)");
+        assert!(!rows[1].starts_with("- unreleased"));
+    }
+
+    #[test]
+    fn it_should_list_the_versions_that_contain_a_text() {
+        let c = changelog(
+            "# Changelog\n\n## [Unreleased]\n\n- Fix the Login form\n\n## [1.2.0]\n\n- C\n\n## [1.1.0]\n\n### Added\n\n- Add a login page\n\n## [1.0.0]\n\n- A\n\n[unreleased]: u\n[1.2.0]: c\n[1.1.0]: b\n[1.0.0]: a",
+            Config::default(),
+        );
+        let options = |contains: &str| ListOptions {
+            include_unreleased: true,
+            contains: Some(contains.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            c.list_versions(Amount::All, &options("LOGIN")),
+            vec![
+                ("unreleased".to_string(), None),
+                ("1.1.0".to_string(), None),
+            ]
+        );
+        assert_eq!(
+            c.list(Amount::Value(1), &options("login page")).unwrap(),
+            "- 1.1.0 b"
+        );
+        assert_eq!(
+            c.list(Amount::All, &options("tables")).unwrap(),
+            "There are no releases that mention \"tables\"."
+        );
+    }
+
+    #[test]
+    fn it_should_search_the_entries_of_every_version() {
+        let c = changelog(
+            "# Changelog\n\n## [Unreleased]\n\n### Fixed\n\n- Fix a memory leak in the parser\n\n## [1.1.0]\n\n### Added\n\n- Add tables\n  - Without memory leaks\n\n### Fixed\n\n- Fix the Memory usage\n\n## [1.0.0]\n\n- A",
+            Config::default(),
+        );
+
+        assert_eq!(
+            c.search(&Regex::new("memory leak").unwrap(), 0),
+            vec![
+                (
+                    "Unreleased".to_string(),
+                    vec![(
+                        "Fixed".to_string(),
+                        "Fix a memory leak in the parser".to_string()
+                    )]
+                ),
+                (
+                    "1.1.0".to_string(),
+                    vec![("Added".to_string(), "Without memory leaks".to_string())]
+                ),
+            ]
+        );
+        assert_eq!(
+            c.search(&Regex::new("(?i)memory (usage|leaks)").unwrap(), 0)[0],
+            (
+                "1.1.0".to_string(),
+                vec![
+                    ("Added".to_string(), "Without memory leaks".to_string()),
+                    ("Fixed".to_string(), "Fix the Memory usage".to_string())
+                ]
+            )
+        );
+        assert_eq!(c.search(&Regex::new("tables?$").unwrap(), 0).len(), 1);
+
+        // The surrounding entries of the same version
+        assert_eq!(
+            c.search(&Regex::new("Without").unwrap(), 1),
+            vec![(
+                "1.1.0".to_string(),
+                vec![
+                    ("Added".to_string(), "Add tables".to_string()),
+                    ("Added".to_string(), "Without memory leaks".to_string()),
+                    ("Fixed".to_string(), "Fix the Memory usage".to_string())
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn it_should_list_the_versions_in_reverse() {
+        let c = changelog(
+            "# Changelog\n\n## [1.2.0]\n\n- C\n\n## [1.1.0]\n\n- B\n\n## [1.0.0]\n\n- A\n\n[1.2.0]: c\n[1.1.0]: b\n[1.0.0]: a",
+            Config::default(),
+        );
+        let list = |oldest: bool| {
+            let options = ListOptions {
+                reverse: true,
+                oldest,
+                ..Default::default()
+            };
+            c.list(Amount::Value(2), &options).unwrap()
+        };
+
+        assert_eq!(list(false), "- 1.1.0 b\n- 1.2.0 c".to_string());
+        assert_eq!(list(true), "- 1.0.0 a\n- 1.1.0 b".to_string());
+    }
+
+    #[test]
+    fn it_should_suggest_a_bump_based_on_the_unreleased_entries() {
+        let mut c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+
+        assert_eq!(c.suggested_bump(None), "patch");
+        c.add_list_item_to_section("Fixed", "A bug", false, None);
+        assert_eq!(c.suggested_bump(None), "patch");
+        c.add_list_item_to_section("Added", "Tables", false, None);
+        assert_eq!(c.suggested_bump(None), "minor");
+        c.add_list_item_to_section("Removed", "Rows", false, None);
+        assert_eq!(c.suggested_bump(None), "major");
+    }
+
+    #[test]
+    fn it_should_insert_entries_at_the_configured_position() {
+        let entries = |insert: InsertPosition| {
+            let mut c = changelog(
+                include_str!("../CHANGELOG.md"),
+                Config {
+                    insert,
+                    ..Config::default()
+                },
+            );
+
+            for entry in [
+                "Zebra",
+                "Fix b ([#12](https://github.com/o/r/pull/12))",
+                "Apple",
+                "Fix a ([#3](https://github.com/o/r/pull/3))",
+            ] {
+                c.add_list_item_to_section("Fixed", entry, false, None);
+            }
+
+            c.entries(Some(&"unreleased".to_string()), None)
+                .unwrap()
+                .into_iter()
+                .map(|(_, entry)| entry.split(' ').take(2).collect::<Vec<_>>().join(" "))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            entries(InsertPosition::Sorted),
+            vec!["Fix a", "Fix b", "Apple", "Zebra"]
+        );
+        assert_eq!(
+            entries(InsertPosition::Top),
+            vec!["Fix a", "Apple", "Fix b", "Zebra"]
+        );
+    }
+
+    #[test]
+    fn it_should_normalize_new_entries() {
+        let mut c = changelog(
+            include_str!("../CHANGELOG.md"),
+            Config {
+                normalize_entries: true,
+                ..Config::default()
+            },
+        );
+
+        for entry in [
+            "fix the parser.",
+            "[#3](https://github.com/o/r/pull/3): support tables.",
+            "wait for it... ([#12](https://github.com/o/r/pull/12))",
+            "`--copy` works on Windows",
+        ] {
+            c.add_list_item_to_section("Fixed", entry, false, None);
+        }
+
+        assert_eq!(
+            c.entries(Some(&"unreleased".to_string()), None)
+                .unwrap()
+                .into_iter()
+                .map(|(_, entry)| entry)
+                .collect::<Vec<_>>(),
+            vec![
+                "Fix the parser",
+                "[#3](https://github.com/o/r/pull/3): Support tables",
+                "Wait for it... ([#12](https://github.com/o/r/pull/12))",
+                "`--copy` works on Windows",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_truncate_the_list_to_the_max_width() {
+        let c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+        let options = ListOptions {
+            max_width: Some(30),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            c.list(Amount::All, &options).unwrap(),
+            "- unreleased            https…\n- 0.1.0      2022-01-09 https…"
+        );
+    }
+
+    #[test]
+    fn it_should_list_the_versions_and_entries_for_scripts() {
+        let c = changelog(
+            "# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.1.0] - 2022-02-01\n\n### Added\n\n- B\n  - Nested\n\n### Fixed\n\n- C\n\n## [1.0.0]\n\n- A\n\n[unreleased]: u\n[1.1.0]: b\n[1.0.0]: a",
+            Config::default(),
+        );
+        let options = ListOptions {
+            include_unreleased: true,
+            reverse: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            c.list_versions(Amount::All, &options),
+            vec![
+                ("1.0.0".to_string(), None),
+                ("1.1.0".to_string(), Some("2022-02-01".to_string())),
+                ("unreleased".to_string(), None),
+            ]
+        );
+        assert_eq!(
+            c.entries(Some(&"1.1.0".to_string()), None),
+            Some(vec![
+                ("Added".to_string(), "B".to_string()),
+                ("Added".to_string(), "Nested".to_string()),
+                ("Fixed".to_string(), "C".to_string()),
+            ])
+        );
+        assert_eq!(
+            c.entries(Some(&"1.0.0".to_string()), None),
+            Some(vec![("".to_string(), "A".to_string())])
+        );
+    }
+
+    #[test]
+    fn it_should_find_the_previous_version() {
+        let c = changelog(
+            "# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.1.0]\n\n- B\n\n## [1.0.1]\n\n- A\n\n## [1.0.0]\n\n- Everything!",
+            Config::default(),
+        );
+        let previous = |version: Option<&str>| {
+            c.previous_version(version.map(|v| v.to_string()).as_ref(), None)
+        };
+
+        assert_eq!(previous(Some("1.1.0")).unwrap(), "1.0.1");
+        assert_eq!(previous(Some("1.0.1")).unwrap(), "1.0.0");
+        assert_eq!(previous(None).unwrap(), "1.0.1");
+        assert_eq!(previous(Some("unreleased")).unwrap(), "1.1.0");
+        assert!(previous(Some("1.0.0"))
+            .unwrap_err()
+            .to_string()
+            .contains("no version before 1.0.0"));
+    }
+
+    #[test]
+    fn it_should_combine_the_notes_of_versions_released_in_a_date_range() {
+        let c = changelog(
+            "# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.2.0] - 2024-02-01\n\n- C\n\n## [1.1.0] - 2024-01-15\n\n- B\n\n## [1.0.0] - 2024-01-01\n\n- A",
+            Config::default(),
+        );
+        let date = |date: &str| date.parse::<NaiveDate>().ok();
+        let options = NotesOptions::default();
 
         assert_eq!(
-            unreleased_section,
-            Node {
-                data: None,
-                children: vec![Node {
-                    data: Some(MarkdownToken::H3("Added".to_string())),
-                    children: vec![Node {
-                        data: Some(MarkdownToken::UnorderedList),
-                        children: vec![
-                            Node {
-                                data: Some(MarkdownToken::ListItem("Something new".to_string(), 0)),
-                                children: vec![],
-                            },
-                            Node {
-                                data: Some(MarkdownToken::ListItem(
-                                    "Something newer".to_string(),
-                                    0
-                                )),
-                                children: vec![],
-                            }
-                        ],
-                    }],
-                }],
+            c.notes_between(date("2024-01-01"), date("2024-02-01"), None, &options),
+            "## [1.1.0] - 2024-01-15\n\n- B\n\n## [1.0.0] - 2024-01-01\n\n- A"
+        );
+        assert_eq!(
+            c.notes_between(date("2024-01-10"), None, None, &options),
+            "## [1.2.0] - 2024-02-01\n\n- C\n\n## [1.1.0] - 2024-01-15\n\n- B"
+        );
+        assert!(c
+            .notes_between(date("2023-01-01"), date("2023-02-01"), None, &options)
+            .contains("Couldn't find versions released between"));
+    }
+
+    #[test]
+    fn it_should_validate_the_structure_of_the_changelog() {
+        let c = changelog(
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- A\n\n## [1.1.0]\n\n### Features\n\n* B\n\n## [foo]\n\n- C\n\n## [1.0.0]\n\n- D\n\n[1.1.0]: https://github.com/o/r/compare/v1.0.0...v1.1.0\n[foo]: https://github.com/o/r/releases/tag/foo\n[1.0.0]: https://github.com/o/r/releases/tag/v1.0.0",
+            Config::default(),
+        );
+
+        assert_eq!(
+            c.validate(),
+            vec![
+                Problem::Warning("Unknown section \"Features\" in 1.1.0".to_string()),
+                Problem::Warning(
+                    "Use \"- \" instead of \"*\" or \"+\" for the entries in 1.1.0".to_string()
+                ),
+                Problem::Error("Invalid version: foo".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_split_versions_into_separate_files_and_merge_them_back() {
+        let pwd = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir(&pwd).unwrap();
+
+        let contents = "# Changelog\n\nAll notable changes.\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.2.0]\n\n- C\n\n## [1.1.0]\n\n- B\n\n## [1.0.0]\n\n### Added\n\n- A\n\n[1.2.0]: https://github.com/o/r/compare/v1.1.0...v1.2.0";
+        let mut c = Changelog {
+            pwd: pwd.clone(),
+            file_path: pwd.join("CHANGELOG.md"),
+            ..changelog(contents, Config::default())
+        };
+
+        let paths = c.split(2, "changelogs").unwrap();
+        assert_eq!(paths, vec![pwd.join("changelogs/1.0.0.md")]);
+        assert_eq!(
+            fs::read_to_string(&paths[0]).unwrap(),
+            "## [1.0.0]\n\n### Added\n\n- A\n"
+        );
+
+        c.split(1, "changelogs").unwrap();
+        let split = c.to_string();
+        assert!(split.contains("All notable changes.\n\nOlder versions: [1.1.0](changelogs/1.1.0.md), [1.0.0](changelogs/1.0.0.md)\n\n## [Unreleased]"));
+        assert!(!split.contains("## [1.1.0]"));
+
+        assert_eq!(c.merge_split().unwrap(), 2);
+        assert_eq!(c.to_string().trim_end(), contents);
+        assert!(!pwd.join("changelogs").exists());
+
+        fs::remove_dir_all(&pwd).unwrap();
+    }
+
+    #[test]
+    fn it_should_fix_the_heading_levels() {
+        let contents = "# Changelog\n\n### [Unreleased]\n\n## Added\n\n- A\n\n### [1.1.0] - 2022-01-01\n\n#### Fixed\n\n- B\n\n## [1.0.0]\n\n### Added\n\n- C\n  ```md\n  ### Fixed\n  ```\n\n### Notes\n";
+
+        let (fixed, corrections) = fix_heading_levels(contents, &Config::default().section_order);
+
+        assert_eq!(
+            fixed,
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- A\n\n## [1.1.0] - 2022-01-01\n\n### Fixed\n\n- B\n\n## [1.0.0]\n\n### Added\n\n- C\n  ```md\n  ### Fixed\n  ```\n\n### Notes\n"
+        );
+        assert_eq!(
+            corrections,
+            vec![
+                (
+                    3,
+                    "### [Unreleased]".to_string(),
+                    "## [Unreleased]".to_string()
+                ),
+                (5, "## Added".to_string(), "### Added".to_string()),
+                (
+                    9,
+                    "### [1.1.0] - 2022-01-01".to_string(),
+                    "## [1.1.0] - 2022-01-01".to_string()
+                ),
+                (11, "#### Fixed".to_string(), "### Fixed".to_string()),
+            ]
+        );
+        assert!(fix_heading_levels(&fixed, &Config::default().section_order)
+            .1
+            .is_empty());
+    }
+
+    #[test]
+    fn it_should_find_duplicate_and_misordered_versions() {
+        let contents = "# Changelog\n\n## [Unreleased]\n\n## [1.3.0]\n\n## [1.1.0]\n\n## [1.2.0]\n\n## [1.3.0] - 2022-01-01\n\n## [pkg@v2.0.0]\n\n## [1.0.0]";
+
+        assert_eq!(
+            version_heading_problems(contents),
+            vec![
+                Problem::Warning(
+                    "Version 1.2.0 on line 9 should come before 1.1.0 on line 7".to_string()
+                ),
+                Problem::Warning(
+                    "Duplicate version 1.3.0 on line 11, it was already on line 5".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_add_an_entry_to_a_released_version() {
+        let mut c = changelog(
+            "# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.1.0]\n\n### Added\n\n- B\n\n## [1.0.0]\n\n### Added\n\n- A",
+            Config::default(),
+        );
+
+        c.add_list_item_to_released_version("1.0.0", "Fixed", "A bug")
+            .unwrap();
+        c.add_list_item_to_released_version("1.1.0", "Added", "C")
+            .unwrap();
+        assert!(c
+            .add_list_item_to_released_version("0.1.0", "Fixed", "A bug")
+            .is_err());
+
+        assert_eq!(
+            c.to_string(),
+            "# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.1.0]\n\n### Added\n\n- B\n- C\n\n## [1.0.0]\n\n### Added\n\n- A\n\n### Fixed\n\n- A bug\n"
+        );
+    }
+
+    #[test]
+    fn it_should_export_the_changelog_as_json() {
+        let mut c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+        c.add_list_item_to_section(
+            "Added",
+            "Add tables ([#12](https://github.com/org/repo/pull/12))",
+            false,
+            None,
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&c.export(ExportFormat::Json)).unwrap();
+        let unreleased = &json["versions"][0];
+
+        assert_eq!(json["title"], "Changelog");
+        assert_eq!(unreleased["name"], "Unreleased");
+        assert_eq!(unreleased["date"], serde_json::Value::Null);
+        assert_eq!(unreleased["sections"][0]["name"], "Added");
+        assert_eq!(
+            unreleased["sections"][0]["entries"][0]["links"][0],
+            serde_json::json!({ "text": "#12", "url": "https://github.com/org/repo/pull/12" })
+        );
+        assert!(json["versions"][1]["date"].is_string());
+        assert!(json["versions"][1]["link"].is_string());
+    }
+
+    #[test]
+    fn it_should_render_the_notes_of_a_version_as_json() {
+        let c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+        let export: serde_json::Value =
+            serde_json::from_str(&c.export(ExportFormat::Json)).unwrap();
+        let version = export["versions"][1]["name"].as_str().unwrap().to_string();
+
+        assert_eq!(
+            c.notes_json(Some(&version), &NotesOptions::default()),
+            Some(export["versions"][1].clone())
+        );
+        assert_eq!(
+            c.notes_json(Some(&"9.9.9".to_string()), &NotesOptions::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn it_should_resolve_reference_style_links() {
+        let c = changelog(
+            "# Changelog\n\n## [1.1.0]\n\n### Fixed\n\n- Fix [the parser][1] and [tables][2]\n\n[1]: https://github.com/o/r/pull/1\n\n## [1.0.0]\n\n- A\n\n[1.1.0]: https://github.com/o/r/compare/v1.0.0...v1.1.0\n[1.0.0]: https://github.com/o/r/releases/tag/v1.0.0",
+            Config::default(),
+        );
+
+        assert_eq!(
+            c.to_string(),
+            "# Changelog\n\n## [1.1.0]\n\n### Fixed\n\n- Fix [the parser][1] and [tables][2]\n\n## [1.0.0]\n\n- A\n\n[1]: https://github.com/o/r/pull/1\n[1.1.0]: https://github.com/o/r/compare/v1.0.0...v1.1.0\n[1.0.0]: https://github.com/o/r/releases/tag/v1.0.0"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&c.export(ExportFormat::Json)).unwrap();
+        assert_eq!(
+            json["versions"][0]["sections"][0]["entries"][0]["links"],
+            serde_json::json!([{ "text": "the parser", "url": "https://github.com/o/r/pull/1" }])
+        );
+        assert_eq!(
+            c.validate(),
+            vec![Problem::Warning(
+                "Undefined link reference \"2\" in 1.1.0".to_string()
+            )]
+        );
+        assert_eq!(c.find_latest_version(), Some("1.1.0"));
+        assert_eq!(
+            c.list(Amount::All, &ListOptions::default()).unwrap(),
+            "- 1.1.0 https://github.com/o/r/compare/v1.0.0...v1.1.0\n- 1.0.0 https://github.com/o/r/releases/tag/v1.0.0"
+        );
+    }
+
+    #[test]
+    fn it_should_group_the_notes_by_type() {
+        let c = changelog(
+            "# Changelog\n\n## [1.0.0] - 2022-01-01\n\n### Added\n\n- Add tables\n- perf: Render tables faster\n  - Using a cache\n\n### Changed\n\n- docs: Document tables\n- Rename rows\n\n### Fixed\n\n- Fix the parser\n\n[1.0.0]: a",
+            Config::default(),
+        );
+        let options = NotesOptions {
+            group_by: GroupBy::Type,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            c.notes(Some(&"1.0.0".to_string()), &options).unwrap().trim_end(),
+            "### Features\n\n- Add tables\n\n### Performance\n\n- Render tables faster\n  - Using a cache\n\n### Documentation\n\n- Document tables\n\n### Changed\n\n- Rename rows\n\n### Bug Fixes\n\n- Fix the parser"
+        );
+    }
+
+    #[test]
+    fn it_should_summarize_the_changes_per_section() {
+        let file = TempFile::new();
+        let old = file.changelog(include_str!("../CHANGELOG.md"), Config::default());
+
+        let mut new = old.clone();
+        new.add_list_item_to_section("Fixed", "A bug", false, None);
+        new.add_list_item_to_section("Fixed", "Another bug", false, None);
+        assert_eq!(
+            old.summarize_changes(&new),
+            vec!["+2 entries under Fixed in Unreleased"]
+        );
+
+        new.release(&"0.2.0".parse().unwrap(), None, &ReleaseOptions::default())
+            .unwrap();
+        assert_eq!(
+            old.summarize_changes(&new),
+            vec!["+Version 0.2.0", "+2 entries under Fixed in 0.2.0"]
+        );
+
+        let reordered = Changelog {
+            root: Node::from_str(
+                &new.root
+                    .to_string()
+                    .replace("- A bug\n- Another bug", "- Another bug\n- A bug"),
+            )
+            .unwrap(),
+            ..new.clone()
+        };
+        assert_eq!(
+            reordered
+                .changes_since(Some(&new.root.to_string()))
+                .unwrap(),
+            vec!["~2 entries reordered under Fixed in 0.2.0"]
+        );
+    }
+
+    #[test]
+    fn it_should_match_sections_case_insensitively() {
+        let mut c = changelog(
+            "# Changelog\n\n## [Unreleased]\n\n### added\n\n- A\n\n[unreleased]: https://github.com/o/r/compare/v1.0.0...HEAD",
+            Config {
+                section_case: SectionCase::Lower,
+                ..Config::default()
+            },
+        );
+        c.add_list_item_to_section("Added", "B", false, None);
+        c.add_list_item_to_section("Fixed", "C", false, None);
+
+        assert_eq!(
+            c.notes(Some(&"unreleased".to_string()), &NotesOptions::default())
+                .unwrap()
+                .trim_end(),
+            "### added\n\n- A\n- B\n\n### fixed\n\n- C"
+        );
+    }
+
+    #[test]
+    fn it_should_include_the_empty_sections_in_the_notes() {
+        let c = changelog(
+            include_str!("../CHANGELOG.md"),
+            Config {
+                section_order: vec!["Added".to_string(), "Fixed".to_string()],
+                ..Config::default()
+            },
+        );
+        let options = NotesOptions {
+            include_empty_sections: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            c.notes(Some(&"0.1.0".to_string()), &options)
+                .unwrap()
+                .trim_end(),
+            "### Added\n\n- Everything!\n\n### Fixed\n\n- _None_"
+        );
+        assert!(matches!(
+            c.notes(Some(&"9.9.9".to_string()), &options),
+            Err(ChangelogError::NotFound(_))
+        ));
+        assert_eq!(
+            c.notes(Some(&"unreleased".to_string()), &options)
+                .unwrap()
+                .trim_end(),
+            "### Added\n\n- _None_\n\n### Fixed\n\n- _None_"
+        );
+    }
+
+    #[test]
+    fn it_should_preserve_the_preamble() {
+        let preamble = "# Changelog\n\nAll notable changes to this project will be documented in this file.\n\nThe format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),\nand this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).\n\n[Keep a Changelog](https://keepachangelog.com) rules apply: newest first.\n\n";
+        let file = TempFile::new();
+        let mut c = file.changelog(
+            &format!(
+                "{}## [Unreleased]\n\n- Nothing yet!\n\n[unreleased]: https://github.com/o/r/compare/v1.0.0...HEAD\n[1.0.0]: https://github.com/o/r/releases/tag/v1.0.0",
+                preamble
+            ),
+            Config::default(),
+        );
+        c.add_list_item_to_section("Added", "Something new", false, None);
+
+        assert_eq!(
+            c.root.to_string().trim_end(),
+            format!(
+                "{}## [Unreleased]\n\n### Added\n\n- Something new\n\n[unreleased]: https://github.com/o/r/compare/v1.0.0...HEAD\n[1.0.0]: https://github.com/o/r/releases/tag/v1.0.0",
+                preamble
+            )
+        );
+
+        // The new unreleased section goes after the whole preamble
+        c.release(
+            &Version::from_str("1.1.0").unwrap(),
+            None,
+            &ReleaseOptions {
+                no_date: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(c.root.to_string().starts_with(&format!(
+            "{}## [Unreleased]\n\n- Nothing yet!\n\n## [1.1.0]\n\n### Added",
+            preamble
+        )));
+    }
+
+    #[test]
+    fn it_should_parse_versions_with_an_inline_link() {
+        let contents = "# Changelog\n\n## [Unreleased](https://github.com/o/r/compare/v1.2.0...HEAD)\n\n- Nothing yet!\n\n## [1.2.0](https://github.com/o/r/compare/v1.1.0...v1.2.0) - 2022-01-01\n\n### Added\n\n- Add tables";
+        let mut c = changelog(contents, Config::default());
+        assert_eq!(c.root.to_string().trim_end(), contents);
+
+        let json: serde_json::Value = serde_json::from_str(&c.export(ExportFormat::Json)).unwrap();
+        assert_eq!(json["versions"][1]["name"], "1.2.0");
+        assert_eq!(json["versions"][1]["date"], "2022-01-01");
+        assert_eq!(
+            json["versions"][1]["link"],
+            "https://github.com/o/r/compare/v1.1.0...v1.2.0"
+        );
+
+        c.add_list_item_to_section("Fixed", "Fix the parser", false, None);
+        assert!(c.root.to_string().starts_with(
+            "# Changelog\n\n## [Unreleased](https://github.com/o/r/compare/v1.2.0...HEAD)\n\n### Fixed\n\n- Fix the parser"
+        ));
+    }
+
+    #[test]
+    fn it_should_insert_new_sections_in_order() {
+        let mut c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+        c.add_list_item_to_section("Fixed", "A bug", false, None);
+        c.add_list_item_to_section("Security", "A vulnerability", false, None);
+        c.add_list_item_to_section("Added", "Something new", false, None);
+        c.add_list_item_to_section("Deprecated", "Something old", false, None);
+
+        let sections: Vec<_> = c
+            .get_contents_of_section(&Some("unreleased".to_string()))
+            .unwrap()
+            .children
+            .iter()
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::H3(name)) => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(sections, vec!["Added", "Deprecated", "Fixed", "Security"]);
+    }
+
+    #[test]
+    fn it_should_add_entries_under_a_sub_heading() {
+        let mut c = changelog(
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n#### API\n\n- Add tables\n\n#### CLI\n\n- Add --json",
+            Config::default(),
+        );
+        c.add_list_item_to_section_under("Added", Some("cli"), "Add --yaml", false, None);
+        c.add_list_item_to_section_under("Added", Some("Docs"), "Add a guide", false, None);
+        c.add_list_item_to_section("Added", "Add themes", false, None);
+        c.add_list_item_to_section_under("Fixed", Some("API"), "Fix rows", false, None);
+
+        assert_eq!(
+            c.root.to_string().trim_end(),
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- Add themes\n\n#### API\n\n- Add tables\n\n#### CLI\n\n- Add --json\n- Add --yaml\n\n#### Docs\n\n- Add a guide\n\n### Fixed\n\n#### API\n\n- Fix rows"
+        );
+    }
+
+    #[test]
+    fn it_should_count_the_entries_of_a_version() {
+        let mut c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+
+        let unreleased = Some("unreleased".to_string());
+        assert_eq!(
+            c.count(unreleased.as_ref(), None),
+            Some(EntryCount {
+                total: 0,
+                sections: vec![]
+            })
+        );
+
+        c.add_list_item_to_section("Added", "Something new", false, None);
+        c.add_list_item_to_section("Added", "Something else", false, None);
+        c.add_list_item_to_section("Fixed", "A bug", false, None);
+
+        let count = c.count(unreleased.as_ref(), None).unwrap();
+        assert_eq!(count.to_string(), "3 entries\n- Added: 2\n- Fixed: 1");
+        assert_eq!(
+            EntryCount {
+                total: 12,
+                sections: vec![("Deprecated".to_string(), 1), ("Added".to_string(), 11)],
+            }
+            .to_string(),
+            "12 entries\n- Deprecated: 1\n- Added:      11"
+        );
+        assert_eq!(count.summary(), "Added (2), Fixed (1)");
+        assert_eq!(
+            count.to_json(),
+            serde_json::json!({ "total": 3, "sections": { "Added": 2, "Fixed": 1 } })
+        );
+        assert_eq!(c.count(Some(&"9.9.9".to_string()), None), None);
+    }
+
+    #[test]
+    fn it_should_diff_two_versions_of_a_changelog() {
+        let older = changelog(include_str!("../CHANGELOG.md"), Config::default());
+        let mut newer = older.clone();
+        newer.add_list_item_to_section("Fixed", "A bug", false, None);
+
+        assert_eq!(
+            older.diff(&newer),
+            vec![Change::Added("Fixed in Unreleased: A bug".to_string())]
+        );
+        assert_eq!(
+            newer.diff(&older),
+            vec![Change::Removed("Fixed in Unreleased: A bug".to_string())]
+        );
+        assert!(older.diff(&older).is_empty());
+    }
+
+    #[test]
+    fn it_should_amend_the_latest_release() {
+        let file = TempFile::new();
+        let mut c = file.changelog(include_str!("../CHANGELOG.md"), Config::default());
+        c.add_list_item_to_section("Fixed", "One more thing", false, None);
+
+        let version = c
+            .amend(&ReleaseOptions {
+                no_date: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(version, "0.1.0");
+        assert_eq!(c.latest_release(), Some("0.1.0"));
+        assert_eq!(
+            c.get_contents_of_section(&Some("0.1.0".to_string())),
+            Some(
+                Node::from_str("### Added\n- Everything!\n\n### Fixed\n- One more thing").unwrap()
+            )
+        );
+        assert_eq!(
+            c.get_contents_of_section(&Some("unreleased".to_string())),
+            Some(Node::from_str("- Nothing yet!").unwrap())
+        );
+    }
+
+    #[test]
+    fn it_should_unrelease_the_latest_release() {
+        let file = TempFile::new();
+        let mut c = file.changelog(include_str!("../CHANGELOG.md"), Config::default());
+        c.add_list_item_to_section("Fixed", "One more thing", false, None);
+        c.release(
+            &Version::from_str("0.2.0").unwrap(),
+            None,
+            &ReleaseOptions::default(),
+        )
+        .unwrap();
+
+        assert!(c.unrelease("0.1.0", None).is_err());
+        c.unrelease("0.2.0", None).unwrap();
+
+        assert_eq!(c.latest_release(), Some("0.1.0"));
+        assert_eq!(
+            c.get_contents_of_section(&Some("unreleased".to_string())),
+            Some(Node::from_str("### Fixed\n- One more thing").unwrap())
+        );
+        assert_eq!(
+            c.root.children[1..]
+                .iter()
+                .map(|node| node.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "[unreleased]: https://github.com/RobinMalfait/changelog/compare/v0.1.0...HEAD",
+                "[0.1.0]: https://github.com/RobinMalfait/changelog/releases/tag/v0.1.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_unrelease_loose_nested_and_sub_section_entries() {
+        let unrelease = |unreleased: &str| {
+            let file = TempFile::new();
+            let mut c = file.changelog(
+                &format!(
+                    concat!(
+                        "# Changelog\n\n{}## [1.1.0] - 2022-02-01\n\n- Loose entry\n\n",
+                        "### Added\n\n- Parent\n  - Child\n\n#### API\n\n- Api thing\n\n",
+                        "## [1.0.0] - 2022-01-01\n\n- A"
+                    ),
+                    unreleased
+                ),
+                Config::default(),
+            );
+
+            c.unrelease("1.1.0", None).unwrap();
+            c
+        };
+
+        let c = unrelease("## [Unreleased]\n\n### Added\n\n- Newer\n\n");
+        assert_eq!(
+            c.get_contents_of_section(&Some("unreleased".to_string())),
+            Some(
+                Node::from_str(
+                    "- Loose entry\n\n### Added\n\n- Newer\n- Parent\n  - Child\n\n#### API\n\n- Api thing"
+                )
+                .unwrap()
+            )
+        );
+
+        // Without an unreleased section, the entries aren't lost
+        let c = unrelease("");
+        assert_eq!(c.latest_release(), Some("1.0.0"));
+        assert_eq!(
+            c.get_contents_of_section(&Some("unreleased".to_string())),
+            Some(
+                Node::from_str(
+                    "- Loose entry\n\n### Added\n\n- Parent\n  - Child\n\n#### API\n\n- Api thing"
+                )
+                .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn it_should_merge_three_versions_of_a_changelog() {
+        let with_entries = |entries: &[&str]| {
+            let mut c = changelog(include_str!("../CHANGELOG.md"), Config::default());
+
+            for entry in entries {
+                c.add_list_item_to_section("Added", entry, false, None);
             }
+
+            c
+        };
+
+        let base = with_entries(&["Shared", "Removed by them"]);
+        let theirs = with_entries(&["Shared", "Theirs"]);
+        let mut ours = with_entries(&["Shared", "Removed by them", "Ours"]);
+
+        let changes = ours.merge(&theirs, Some(&base)).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                "Added \"Theirs\" to Added".to_string(),
+                "Removed \"Removed by them\" from Added".to_string()
+            ]
+        );
+        assert_eq!(
+            ours.unreleased_entries(None),
+            vec![
+                ("Added".to_string(), "Shared".to_string()),
+                ("Added".to_string(), "Ours".to_string()),
+                ("Added".to_string(), "Theirs".to_string())
+            ]
         );
     }
 }
@@ -0,0 +1,835 @@
+use crate::error::{ChangelogError, Result};
+use crate::package::{PackageJSON, Version};
+use crate::shell::Shell;
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+const CONFIG_FILENAME: &str = ".changelogrc.json";
+
+/// The regex of the `{version}` placeholder in the pattern of a version file, e.g.: "1.2.3" or
+/// "v1.2.3-beta.1"
+const VERSION_PLACEHOLDER: &str = r"(v?\d[0-9A-Za-z.+-]*)";
+
+/// The order of the sections in a version, as described on https://keepachangelog.com
+const SECTION_ORDER: [&str; 6] = [
+    "Added",
+    "Changed",
+    "Deprecated",
+    "Removed",
+    "Fixed",
+    "Security",
+];
+
+/// Optional configuration, read from a `.changelogrc.json` file in the current working directory
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Where to put the reference (link to the PR, issue, ...) of an entry
+    pub reference_placement: ReferencePlacement,
+
+    /// The order of the sections in a version, new sections are inserted at the correct position
+    pub section_order: Vec<String>,
+
+    /// Categorize entries based on their (git)moji
+    pub gitmoji: Gitmoji,
+
+    /// The placeholder entry of every section in a scaffolded unreleased section, e.g.:
+    /// `{ "Added": "_Nothing yet_" }`. A placeholder is removed once a real entry is added to
+    /// its section.
+    pub placeholders: HashMap<String, String>,
+
+    /// Where to read the current version from, when releasing "major", "minor", "infer", ...
+    pub version_source: VersionSource,
+
+    /// The git remote to base the links of a new changelog on, e.g.: when the repo is mirrored on
+    /// multiple hosts
+    pub remote: String,
+
+    /// Warn when adding an entry makes the unreleased section grow beyond this amount of entries
+    pub max_entries: Option<usize>,
+
+    /// Shell commands to run (in the directory of the changelog) before releasing, the release is
+    /// aborted when one of them fails
+    pub pre_release: Vec<String>,
+
+    /// Shell commands to run (in the directory of the changelog) after releasing
+    pub post_release: Vec<String>,
+
+    /// The prefix of the git tags of versions, e.g.: "v" for "v1.2.3", or "" for "1.2.3"
+    pub tag_prefix: String,
+
+    /// The text of the references to pull requests and issues
+    pub link_format: LinkFormat,
+
+    /// The names to credit authors with when using `--thanks`, by their login, e.g.:
+    /// `{ "octocat": "Mona Lisa" }`
+    pub author_map: HashMap<String, String>,
+
+    /// A shell command that prints a GitHub token (e.g.: `gh auth token`), used when the
+    /// `GITHUB_API_TOKEN` environment variable isn't set
+    pub token_command: Option<String>,
+
+    /// The GitHub Enterprise Server hosts to use the API of, besides github.com. The GitHub token
+    /// isn't sent to other hosts.
+    pub github_hosts: Vec<String>,
+
+    /// The heading of a released version, with `{version}` and `{date}` placeholders, e.g.:
+    /// "## [{version}] - {date}"
+    pub version_heading: String,
+
+    /// The command that `release --with-npm` runs instead of `npm version`, with a `{version}`
+    /// placeholder, e.g.: "pnpm version {version} --no-git-tag-version"
+    pub version_command: Option<String>,
+
+    /// The message of the commit that `release --with-npm` creates, with `{version}` and
+    /// `{package}` placeholders, e.g.: "chore(release): {version}"
+    pub commit_template: Option<String>,
+
+    /// Files to update the version in when releasing, e.g.: a version badge in the README
+    pub version_files: Vec<VersionFile>,
+
+    /// Remove the emoji (e.g.: gitmoji) from entries that are generated from commits, pull
+    /// requests or an imported changelog
+    pub strip_emoji: bool,
+
+    /// Wrap the mentions of users and teams (e.g.: "@octocat") in entries that are generated from
+    /// commits or pull requests in backticks, so that posting the notes doesn't notify them
+    pub escape_mentions: bool,
+
+    /// Where to insert new entries in a section
+    pub insert: InsertPosition,
+
+    /// Capitalize the first letter of new entries and drop their trailing period
+    pub normalize_entries: bool,
+
+    /// Where the base of the compare link of a released version comes from
+    pub compare_base: CompareBase,
+
+    /// The order to add the entries of commits in, e.g.: with `release --from-commits`
+    pub commit_order: CommitOrder,
+
+    /// Don't release when the unreleased section has no entries, instead of releasing an empty
+    /// version. `release --allow-empty` releases anyway.
+    pub skip_empty_release: bool,
+
+    /// Warn when the conventional commit type of a pull request title doesn't match the section an
+    /// entry is added to, e.g.: "fix: ..." with `changelog add`
+    pub suggest_section: bool,
+
+    /// The packages that depend on a package in a monorepo, besides the dependencies in their
+    /// package.json, e.g.: `{ "a": ["b"] }`. Used by `release --propagate`.
+    pub dependents: HashMap<String, Vec<String>>,
+
+    /// Shell commands that resolve the links of other systems (e.g.: an internal issue tracker)
+    /// to an entry, instead of GitHub
+    pub link_resolvers: Vec<LinkResolver>,
+
+    /// The casing of new section headings, e.g.: "added" for a changelog with lowercase sections.
+    /// Existing sections are matched case-insensitively and keep their casing.
+    pub section_case: SectionCase,
+
+    /// The text to put before new entries, e.g.: "[api] " for a component tag, only set by the
+    /// `--entry-prefix` flag of `add`, `fix`, ...
+    #[serde(skip)]
+    pub entry_prefix: String,
+
+    /// The text to put after new entries, only set by the `--entry-suffix` flag of `add`, `fix`,
+    /// ...
+    #[serde(skip)]
+    pub entry_suffix: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            reference_placement: ReferencePlacement::default(),
+            section_order: SECTION_ORDER.iter().map(|s| s.to_string()).collect(),
+            gitmoji: Gitmoji::default(),
+            placeholders: HashMap::new(),
+            version_source: VersionSource::default(),
+            remote: "origin".to_string(),
+            max_entries: None,
+            pre_release: vec![],
+            post_release: vec![],
+            tag_prefix: "v".to_string(),
+            link_format: LinkFormat::default(),
+            author_map: HashMap::new(),
+            token_command: None,
+            github_hosts: vec![],
+            version_heading: "## [{version}] - {date}".to_string(),
+            version_command: None,
+            commit_template: None,
+            version_files: vec![],
+            strip_emoji: false,
+            escape_mentions: false,
+            insert: InsertPosition::default(),
+            normalize_entries: false,
+            compare_base: CompareBase::default(),
+            commit_order: CommitOrder::default(),
+            skip_empty_release: false,
+            suggest_section: true,
+            dependents: HashMap::new(),
+            link_resolvers: vec![],
+            section_case: SectionCase::default(),
+            entry_prefix: String::new(),
+            entry_suffix: String::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Put the `entry_prefix` and `entry_suffix` around an entry, unless it already has them, e.g.:
+    /// when an entry is merged from another changelog
+    pub fn decorate_entry(&self, entry: &str) -> String {
+        let mut entry = entry.to_string();
+
+        if !entry.starts_with(&self.entry_prefix) {
+            entry.insert_str(0, &self.entry_prefix);
+        }
+        if !entry.ends_with(&self.entry_suffix) {
+            entry.push_str(&self.entry_suffix);
+        }
+
+        entry
+    }
+
+    pub fn from_directory(dir: &Path) -> Result<Self> {
+        let config_path = dir.join(CONFIG_FILENAME);
+
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        tracing::debug!(path = %config_path.display(), "read config");
+        let contents = std::fs::read_to_string(&config_path)?;
+        let config = serde_json::from_str::<Self>(&contents).map_err(|e| {
+            ChangelogError::ParseError(format!("Invalid {} file: {}", CONFIG_FILENAME, e))
+        })?;
+
+        for file in &config.version_files {
+            file.regex()?;
+        }
+
+        Ok(config)
+    }
+
+    /// The position of a section in the configured order, unknown sections go last
+    pub fn section_rank(&self, name: &str) -> usize {
+        self.section_order
+            .iter()
+            .position(|section| section.eq_ignore_ascii_case(name))
+            .unwrap_or(self.section_order.len())
+    }
+
+    /// The configured placeholder entry of a section
+    pub fn placeholder(&self, section_name: &str) -> Option<&str> {
+        self.placeholders
+            .iter()
+            .find(|(section, _)| section.eq_ignore_ascii_case(section_name))
+            .map(|(_, placeholder)| placeholder.as_str())
+    }
+
+    /// The git tag of a version, e.g.: "v1.2.3", or "my-package@v1.2.3" for a package in a
+    /// monorepo. An empty version results in the prefix of the tags.
+    pub fn tag(&self, version: &str, package: Option<&PackageJSON>) -> String {
+        match package {
+            Some(package) if !package.is_root() => {
+                format!("{}@{}{}", package.name(), self.tag_prefix, version)
+            }
+            _ => format!("{}{}", self.tag_prefix, version),
+        }
+    }
+
+    /// The heading of a released version (without the "## "), e.g.: "[1.2.3] - 2022-01-01". Without
+    /// a date, the separators around the date placeholder are dropped as well, e.g.: "[1.2.3]".
+    pub fn version_heading(&self, version: &str, date: Option<&str>) -> String {
+        let format = self.version_heading.trim_start_matches('#').trim();
+        let heading = format.replace("{version}", version);
+
+        match date {
+            Some(date) => heading.replace("{date}", date),
+            None => {
+                let (before, after) = heading.split_once("{date}").unwrap_or((&heading, ""));
+                let separators = |c: char| c.is_whitespace() || matches!(c, '-' | '(' | ')');
+
+                format!(
+                    "{}{}",
+                    before.trim_end_matches(separators),
+                    after.trim_start_matches(separators)
+                )
+            }
+        }
+    }
+
+    /// The version and the date of a heading in the `version_heading` format (without the "## "),
+    /// e.g.: "1.2.3" and "2022-01-01" for "Release 1.2.3 (2022-01-01)". The date is optional, like
+    /// in a release without a date.
+    pub fn parse_version_heading<'a>(
+        &self,
+        heading: &'a str,
+    ) -> Option<(&'a str, Option<&'a str>)> {
+        let format = self.version_heading.trim_start_matches('#').trim();
+        let (before, after) = format.split_once("{version}")?;
+        let rest = heading.strip_prefix(before)?;
+
+        // The version ends where the text after it in the format starts, e.g.: the "]" of "[1.2.3]"
+        let end = after.chars().next().filter(|c| !c.is_whitespace());
+        let len = rest
+            .find(|c: char| c.is_whitespace() || Some(c) == end)
+            .unwrap_or(rest.len());
+        let version = &rest[..len];
+        let number = version
+            .rsplit_once('@')
+            .map_or(version, |(_, number)| number);
+        number.trim_start_matches('v').parse::<Version>().ok()?;
+
+        let date = after.split_once("{date}").and_then(|(between, tail)| {
+            rest[len..]
+                .strip_prefix(between)?
+                .strip_suffix(tail)
+                .filter(|date| !date.is_empty())
+        });
+
+        Some((version, date))
+    }
+
+    /// The name to credit an author with, e.g.: "@octocat" or the name from the author map
+    pub fn author(&self, login: &str) -> String {
+        let login = login.trim_start_matches('@');
+
+        match self.author_map.get(login) {
+            Some(name) => name.to_string(),
+            None => format!("@{}", login),
+        }
+    }
+
+    /// Whether `package` depends on `dependency` according to the configured dependents, or the
+    /// dependencies in its package.json
+    pub fn depends_on(&self, package: &PackageJSON, dependency: &str) -> bool {
+        package.depends_on(dependency)
+            || self
+                .dependents
+                .get(dependency)
+                .is_some_and(|dependents| dependents.iter().any(|name| name == package.name()))
+    }
+
+    /// The first configured resolver that handles the link
+    pub fn link_resolver(&self, link: &str) -> Option<&LinkResolver> {
+        self.link_resolvers
+            .iter()
+            .find(|resolver| link.starts_with(&resolver.prefix))
+    }
+}
+
+/// A shell command that resolves a link to the text of an entry
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkResolver {
+    /// The start of the links that are resolved with the command, e.g.:
+    /// "https://jira.example.com/browse/"
+    pub prefix: String,
+
+    /// The command that prints the entry, the link is in the `CHANGELOG_LINK` environment
+    /// variable, e.g.: "jira-title \"$CHANGELOG_LINK\""
+    pub command: String,
+}
+
+impl LinkResolver {
+    /// Run the command for a link, fails when it doesn't print anything
+    pub fn resolve(&self, link: &str, pwd: &Path) -> Result<String> {
+        let entry = Shell::new(pwd)
+            .env("CHANGELOG_LINK", link)
+            .run(&self.command)?;
+
+        match entry.is_empty() {
+            true => Err(ChangelogError::Command(format!(
+                "`{}` didn't print an entry for: {}",
+                self.command, link
+            ))),
+            false => Ok(entry),
+        }
+    }
+}
+
+/// A file to update the version in when releasing
+#[derive(Debug, Clone, Deserialize)]
+pub struct VersionFile {
+    /// The path of the file, relative to the changelog
+    pub path: String,
+
+    /// A regex of the text around the version, the version is its first capture group (or the
+    /// whole match). A `{version}` placeholder matches a version, e.g.:
+    /// "badge/version-{version}-blue" or "img\.shields\.io/badge/v-(\d+\.\d+\.\d+)-"
+    pub pattern: String,
+}
+
+impl VersionFile {
+    /// The compiled pattern, fails for an invalid regex
+    pub fn regex(&self) -> Result<Regex> {
+        Regex::new(&self.pattern.replace("{version}", VERSION_PLACEHOLDER)).map_err(|e| {
+            ChangelogError::ParseError(format!(
+                "Invalid pattern of version file {}: {}",
+                self.path, e
+            ))
+        })
+    }
+
+    /// Replace the version in every match of the pattern, or None when nothing matches. A "v"
+    /// prefix of the old version is kept.
+    pub fn replace(&self, contents: &str, version: &str) -> Option<String> {
+        let regex = self.regex().ok()?;
+        let mut found = false;
+
+        let result = regex.replace_all(contents, |captures: &Captures| {
+            let all = captures.get(0).expect("a match always has the whole match");
+            let old = captures.get(1).unwrap_or(all);
+            found = true;
+
+            format!(
+                "{}{}{}{}",
+                &contents[all.start()..old.start()],
+                match old.as_str().starts_with('v') && !version.starts_with('v') {
+                    true => "v",
+                    false => "",
+                },
+                version,
+                &contents[old.end()..all.end()]
+            )
+        });
+
+        found.then(|| result.into_owned())
+    }
+}
+
+/// Emoji based categorization, like https://gitmoji.dev
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Gitmoji {
+    /// Use the leading emoji of a commit message to find its section
+    pub detect: bool,
+
+    /// Prepend the emoji of the section to new entries
+    pub prepend: bool,
+
+    /// The emoji (or gitmoji codes like ":sparkles:") of every section, the first one is used
+    /// when prepending
+    pub sections: HashMap<String, Vec<String>>,
+}
+
+impl Default for Gitmoji {
+    fn default() -> Self {
+        let sections = [
+            ("Added", vec!["✨", ":sparkles:"]),
+            ("Changed", vec!["♻️", ":recycle:", "⚡️", ":zap:"]),
+            ("Deprecated", vec!["🗑️", ":wastebasket:"]),
+            ("Removed", vec!["🔥", ":fire:"]),
+            ("Fixed", vec!["🐛", ":bug:"]),
+            ("Security", vec!["🔒️", ":lock:"]),
+        ];
+
+        Self {
+            detect: false,
+            prepend: false,
+            sections: sections
+                .into_iter()
+                .map(|(section, emoji)| {
+                    (
+                        section.to_string(),
+                        emoji.into_iter().map(|e| e.to_string()).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Gitmoji {
+    /// The section of a message with a leading emoji, and the message without the emoji
+    pub fn classify(&self, message: &str) -> Option<(String, String)> {
+        self.sections.iter().find_map(|(section, emoji)| {
+            emoji.iter().find_map(|emoji| {
+                let rest = strip_emoji(message, emoji)?;
+                Some((section.to_string(), rest.to_string()))
+            })
+        })
+    }
+
+    /// Prepend the emoji of the section to an entry, when it doesn't have it yet
+    pub fn decorate(&self, section_name: &str, entry: &str) -> String {
+        let emoji = self
+            .sections
+            .iter()
+            .find(|(section, _)| section.eq_ignore_ascii_case(section_name))
+            .and_then(|(_, emoji)| emoji.first());
+
+        match emoji {
+            Some(emoji) if strip_emoji(entry, emoji).is_none() => format!("{} {}", emoji, entry),
+            _ => entry.to_string(),
+        }
+    }
+}
+
+/// Strip a leading emoji, ignoring the invisible variation selector that some emoji have
+fn strip_emoji<'a>(message: &'a str, emoji: &str) -> Option<&'a str> {
+    let emoji = emoji.trim_end_matches('\u{fe0f}');
+    let rest = message.trim_start().strip_prefix(emoji)?;
+    let rest = rest.trim_start_matches('\u{fe0f}');
+
+    // Don't match a prefix of another emoji or gitmoji code
+    match rest.chars().next() {
+        None => Some(rest),
+        Some(c) if c.is_whitespace() => Some(rest.trim_start()),
+        Some(_) => None,
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferencePlacement {
+    /// E.g.: "[#123](...): Fix a bug"
+    Prefix,
+
+    /// E.g.: "Fix a bug ([#123](...))"
+    #[default]
+    Suffix,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompareBase {
+    /// The tag in the compare link of the unreleased section, e.g.: "v1.2.0" for
+    /// ".../compare/v1.2.0...HEAD"
+    #[default]
+    Tags,
+
+    /// The version heading below the released version, even when its tag doesn't exist yet
+    Changelog,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InsertPosition {
+    /// Above the existing entries of the section
+    Top,
+
+    /// Below the existing entries of the section
+    #[default]
+    Bottom,
+
+    /// Keep the section sorted, by the number of the pull request or issue of the entries and
+    /// then alphabetically
+    Sorted,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkFormat {
+    /// Based on the host of the link, "gitlab" for hosts like "gitlab.com" and "github" otherwise
+    #[default]
+    Auto,
+
+    /// E.g.: "#123" for pull requests and issues
+    GitHub,
+
+    /// E.g.: "!123" for merge requests and "#123" for issues
+    GitLab,
+}
+
+impl FromStr for LinkFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(LinkFormat::Auto),
+            "github" => Ok(LinkFormat::GitHub),
+            "gitlab" => Ok(LinkFormat::GitLab),
+            _ => Err(format!("Invalid link format: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionCase {
+    /// As given, e.g.: "Added" for `changelog add`
+    #[default]
+    Preserve,
+
+    /// Every word capitalized, e.g.: "Bug Fixes"
+    Title,
+
+    /// Every word in lowercase, e.g.: "bug fixes"
+    Lower,
+}
+
+impl SectionCase {
+    /// The section name in this casing
+    pub fn apply(&self, name: &str) -> String {
+        match self {
+            SectionCase::Preserve => name.to_string(),
+            SectionCase::Title => name
+                .split(' ')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first
+                            .to_uppercase()
+                            .chain(chars.flat_map(char::to_lowercase))
+                            .collect(),
+                        None => String::new(),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+            SectionCase::Lower => name.to_lowercase(),
+        }
+    }
+}
+
+impl FromStr for SectionCase {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "preserve" => Ok(SectionCase::Preserve),
+            "title" => Ok(SectionCase::Title),
+            "lower" => Ok(SectionCase::Lower),
+            _ => Err(format!("Invalid section case: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitOrder {
+    /// The order of the git history, from the oldest to the newest commit
+    #[default]
+    Log,
+
+    /// The date of the commits, e.g.: the date a pull request was merged for merge and squashed
+    /// commits
+    Date,
+}
+
+impl FromStr for CommitOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "log" => Ok(CommitOrder::Log),
+            "date" => Ok(CommitOrder::Date),
+            _ => Err(format!("Invalid commit order: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionSource {
+    /// The "version" of the package.json file
+    #[default]
+    Package,
+
+    /// A plain `VERSION` file that only contains the version, e.g.: "1.2.3"
+    File,
+
+    /// The latest git tag, using `git describe --tags`
+    Git,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_find_the_configured_dependents() {
+        let package: PackageJSON = serde_json::from_str(
+            r#"{ "name": "b", "version": "1.0.0", "peerDependencies": { "a": "^1.0.0" } }"#,
+        )
+        .unwrap();
+        let config = Config {
+            dependents: HashMap::from([("c".to_string(), vec!["b".to_string()])]),
+            ..Default::default()
+        };
+
+        assert!(config.depends_on(&package, "a"));
+        assert!(config.depends_on(&package, "c"));
+        assert!(!config.depends_on(&package, "d"));
+    }
+
+    #[test]
+    fn it_should_resolve_links_with_the_configured_command() {
+        let config = Config {
+            link_resolvers: vec![LinkResolver {
+                prefix: "https://jira.example.com/".to_string(),
+                command: "echo \"Fix the parser ($CHANGELOG_LINK)\"".to_string(),
+            }],
+            ..Default::default()
+        };
+        let link = "https://jira.example.com/browse/ABC-1";
+
+        assert!(config
+            .link_resolver("https://github.com/org/repo/pull/1")
+            .is_none());
+        assert_eq!(
+            config
+                .link_resolver(link)
+                .unwrap()
+                .resolve(link, &std::env::temp_dir())
+                .unwrap(),
+            "Fix the parser (https://jira.example.com/browse/ABC-1)"
+        );
+    }
+
+    #[test]
+    fn it_should_apply_the_section_case() {
+        assert_eq!(SectionCase::Preserve.apply("bug Fixes"), "bug Fixes");
+        assert_eq!(SectionCase::Title.apply("bug fixes"), "Bug Fixes");
+        assert_eq!(SectionCase::Lower.apply("Bug Fixes"), "bug fixes");
+    }
+
+    #[test]
+    fn it_should_map_authors_to_names() {
+        let config = Config {
+            author_map: HashMap::from([("octocat".to_string(), "Mona Lisa".to_string())]),
+            ..Default::default()
+        };
+
+        assert_eq!(config.author("octocat"), "Mona Lisa");
+        assert_eq!(config.author("@octocat"), "Mona Lisa");
+        assert_eq!(config.author("hubot"), "@hubot");
+    }
+
+    #[test]
+    fn it_should_prefix_version_tags() {
+        let mut config = Config::default();
+        assert_eq!(config.tag("1.2.3", None), "v1.2.3");
+
+        config.tag_prefix = "".to_string();
+        assert_eq!(config.tag("1.2.3", None), "1.2.3");
+    }
+
+    #[test]
+    fn it_should_replace_the_version_in_version_files() {
+        let file = VersionFile {
+            path: "README.md".to_string(),
+            pattern: "badge/version-{version}-blue".to_string(),
+        };
+
+        assert_eq!(
+            file.replace(
+                "![version](https://img.shields.io/badge/version-1.2.3-blue)",
+                "1.3.0"
+            ),
+            Some("![version](https://img.shields.io/badge/version-1.3.0-blue)".to_string())
+        );
+        assert_eq!(file.replace("badge/version-unknown-blue", "1.3.0"), None);
+
+        let file = VersionFile {
+            path: "README.md".to_string(),
+            pattern: "my-tool@{version}".to_string(),
+        };
+        assert_eq!(
+            file.replace("npx my-tool@v1.2.3-beta.1 init", "1.3.0"),
+            Some("npx my-tool@v1.3.0 init".to_string())
+        );
+
+        // A regex of the badge url, with the version as its capture group
+        let file = VersionFile {
+            path: "README.md".to_string(),
+            pattern: r"img\.shields\.io/badge/version-(\d+\.\d+\.\d+)-\w+".to_string(),
+        };
+        assert_eq!(
+            file.replace(
+                "![version](https://img.shields.io/badge/version-1.2.3-green) and version-1.2.3-blue",
+                "1.3.0"
+            ),
+            Some(
+                "![version](https://img.shields.io/badge/version-1.3.0-green) and version-1.2.3-blue"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn it_should_reject_invalid_version_file_patterns() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(CONFIG_FILENAME),
+            r#"{ "version_files": [{ "path": "README.md", "pattern": "badge/(version-{version}" }] }"#,
+        )
+        .unwrap();
+
+        let result = Config::from_directory(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid pattern of version file README.md"));
+    }
+
+    #[test]
+    fn it_should_format_version_headings() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.version_heading("1.2.3", Some("2022-01-01")),
+            "[1.2.3] - 2022-01-01"
+        );
+        assert_eq!(config.version_heading("1.2.3", None), "[1.2.3]");
+
+        config.version_heading = "v{version} ({date})".to_string();
+        assert_eq!(
+            config.version_heading("1.2.3", Some("2022-01-01")),
+            "v1.2.3 (2022-01-01)"
+        );
+        assert_eq!(config.version_heading("1.2.3", None), "v1.2.3");
+        assert_eq!(
+            config.parse_version_heading("v1.2.3 (2022-01-01)"),
+            Some(("1.2.3", Some("2022-01-01")))
+        );
+        assert_eq!(
+            config.parse_version_heading("v1.2.3"),
+            Some(("1.2.3", None))
+        );
+        assert_eq!(config.parse_version_heading("Unreleased"), None);
+
+        config.version_heading = "Release {version} ({date})".to_string();
+        assert_eq!(
+            config.parse_version_heading("Release 1.2.3 (2022-01-01)"),
+            Some(("1.2.3", Some("2022-01-01")))
+        );
+    }
+
+    #[test]
+    fn it_should_classify_messages_by_gitmoji() {
+        let gitmoji = Gitmoji::default();
+
+        assert_eq!(
+            gitmoji.classify("✨ Add tables"),
+            Some(("Added".to_string(), "Add tables".to_string()))
+        );
+        assert_eq!(
+            gitmoji.classify(":bug: Fix the parser"),
+            Some(("Fixed".to_string(), "Fix the parser".to_string()))
+        );
+        assert_eq!(
+            gitmoji.classify("⚡ Speed up parsing"),
+            Some(("Changed".to_string(), "Speed up parsing".to_string()))
+        );
+        assert_eq!(gitmoji.classify("Add tables"), None);
+        assert_eq!(gitmoji.classify(":bugfix: Not a gitmoji"), None);
+    }
+
+    #[test]
+    fn it_should_prepend_the_emoji_of_a_section() {
+        let gitmoji = Gitmoji::default();
+
+        assert_eq!(gitmoji.decorate("Fixed", "A bug"), "🐛 A bug");
+        assert_eq!(gitmoji.decorate("Fixed", "🐛 A bug"), "🐛 A bug");
+        assert_eq!(gitmoji.decorate("Unknown", "A thing"), "A thing");
+    }
+}
@@ -0,0 +1,98 @@
+/// A commit message following the conventional commits spec: https://www.conventionalcommits.org
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    /// The type of the commit, e.g.: "feat" or "fix"
+    pub kind: String,
+
+    /// The optional scope of the commit, e.g.: "parser" in "fix(parser): ..."
+    pub scope: Option<String>,
+
+    /// Whether the commit is marked as a breaking change with a `!`
+    pub breaking: bool,
+
+    /// The description of the commit, without the type and scope
+    pub description: String,
+}
+
+impl ConventionalCommit {
+    pub fn parse(message: &str) -> Option<Self> {
+        let (prefix, description) = message.split_once(": ")?;
+        let (prefix, breaking) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?.to_string())),
+            None => (prefix, None),
+        };
+
+        if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_lowercase()) {
+            return None;
+        }
+
+        Some(Self {
+            kind: kind.to_string(),
+            scope,
+            breaking,
+            description: description.trim().to_string(),
+        })
+    }
+
+    /// The changelog section this commit belongs to. Commits that typically don't matter for the
+    /// users of a project (chore, docs, ci, ...) don't belong to any section.
+    pub fn section(&self) -> Option<&'static str> {
+        match self.kind.as_str() {
+            "feat" => Some("Added"),
+            "fix" => Some("Fixed"),
+            "perf" | "refactor" | "revert" => Some("Changed"),
+            "deprecate" => Some("Deprecated"),
+            "remove" => Some("Removed"),
+            _ => None,
+        }
+    }
+}
+
+/// Classify a commit message into a changelog section. Commit messages that don't follow the
+/// conventional commits spec end up in the "Changed" section.
+pub fn classify(message: &str) -> Option<(&'static str, String)> {
+    match ConventionalCommit::parse(message) {
+        Some(commit) => commit
+            .section()
+            .map(|section| (section, commit.description)),
+        None => Some(("Changed", message.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_parse_a_conventional_commit() {
+        assert_eq!(
+            ConventionalCommit::parse("feat(parser)!: support tables"),
+            Some(ConventionalCommit {
+                kind: "feat".to_string(),
+                scope: Some("parser".to_string()),
+                breaking: true,
+                description: "support tables".to_string(),
+            })
+        );
+
+        assert_eq!(ConventionalCommit::parse("Update README.md"), None);
+        assert_eq!(ConventionalCommit::parse("Note: this is not a type"), None);
+    }
+
+    #[test]
+    fn it_should_classify_commit_messages() {
+        assert_eq!(
+            classify("fix: off by one"),
+            Some(("Fixed", "off by one".to_string()))
+        );
+        assert_eq!(classify("chore: bump dependencies"), None);
+        assert_eq!(
+            classify("Improve performance"),
+            Some(("Changed", "Improve performance".to_string()))
+        );
+    }
+}
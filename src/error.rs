@@ -0,0 +1,53 @@
+use std::fmt::Display;
+
+pub type Result<T, E = ChangelogError> = std::result::Result<T, E>;
+
+/// The errors of the core modules, the binary converts these to `eyre` reports
+#[derive(Debug)]
+pub enum ChangelogError {
+    /// A changelog, manifest or link couldn't be parsed
+    ParseError(String),
+
+    /// A version, section, file, ... couldn't be found
+    NotFound(String),
+
+    /// Talking to the GitHub API failed
+    Network(String),
+
+    /// A version is not a valid semver version
+    InvalidVersion(String),
+
+    /// Running an external command like `git` or `npm` failed
+    Command(String),
+
+    /// Reading or writing a file failed
+    Io(std::io::Error),
+}
+
+impl Display for ChangelogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangelogError::ParseError(message)
+            | ChangelogError::NotFound(message)
+            | ChangelogError::Network(message)
+            | ChangelogError::InvalidVersion(message)
+            | ChangelogError::Command(message) => write!(f, "{}", message),
+            ChangelogError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ChangelogError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ChangelogError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ChangelogError {
+    fn from(e: std::io::Error) -> Self {
+        ChangelogError::Io(e)
+    }
+}
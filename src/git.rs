@@ -1,6 +1,8 @@
-use color_eyre::eyre::{eyre, Result};
+use crate::error::{ChangelogError, Result};
+use chrono::{DateTime, FixedOffset};
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Output};
+use std::time::Instant;
 
 #[derive(Debug)]
 pub struct Git {
@@ -28,15 +30,82 @@ impl Git {
     pub fn commit_message(&self, hash: &str) -> Result<String> {
         self.exec(vec!["log", "-1", "--format=%B", hash])
             .and_then(|msg| match msg.is_empty() {
-                true => Err(eyre!("No commit message found")),
+                true => Err(ChangelogError::NotFound(
+                    "No commit message found".to_string(),
+                )),
                 false => Ok(msg.trim().split('\n').next().unwrap_or(&msg).to_string()),
             })
     }
 
-    pub fn is_git_repo(&self) -> bool {
-        self.exec(vec!["rev-parse", "--is-inside-work-tree"])
-            .map(|output| output.trim() == "true")
-            .unwrap_or(false)
+    /// The most recent version tag with the given prefix that is reachable from `rev`, e.g.:
+    /// "v1.2.3" for the "v" prefix
+    pub fn latest_tag(&self, rev: &str, prefix: &str) -> Result<String> {
+        let pattern = format!("--match={}[0-9]*", prefix);
+        self.exec(vec!["describe", "--tags", "--abbrev=0", &pattern, rev])
+            .and_then(|tag| match tag.is_empty() {
+                true => Err(ChangelogError::NotFound("No tags found".to_string())),
+                false => Ok(tag),
+            })
+    }
+
+    /// The commits in the `<from>..<to>` range (or everything up to `to` when there is no
+    /// `from`), oldest first. Optionally limited to the commits that touched `path`. Only the
+    /// first parent of merge commits is followed, so the commits of a merged pull request are
+    /// represented by its merge commit.
+    pub fn log_range(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        path: Option<&str>,
+    ) -> Result<Vec<LogEntry>> {
+        let range = match from {
+            Some(from) => format!("{}..{}", from, to),
+            None => to.to_string(),
+        };
+
+        let mut args = vec![
+            "log",
+            "--reverse",
+            "--first-parent",
+            "--format=%H%x1f%cI%x1f%B%x1e",
+            &range,
+        ];
+        if let Some(path) = path {
+            args.extend(["--", path]);
+        }
+
+        Ok(self
+            .exec(args)?
+            .split('\x1e')
+            .filter_map(|entry| {
+                let mut fields = entry.trim().splitn(3, '\x1f');
+                Some((fields.next()?, fields.next()?, fields.next()?))
+            })
+            .map(|(hash, date, message)| LogEntry {
+                hash: hash.to_string(),
+                date: DateTime::parse_from_rfc3339(date).ok(),
+                message: message.trim().to_string(),
+            })
+            .collect())
+    }
+
+    /// The amount of commits in the `<from>..<to>` range (or everything up to `to` when there is
+    /// no `from`). Optionally limited to the commits that touched `path`.
+    pub fn count_commits(&self, from: Option<&str>, to: &str, path: Option<&str>) -> Result<usize> {
+        let range = match from {
+            Some(from) => format!("{}..{}", from, to),
+            None => to.to_string(),
+        };
+
+        let mut args = vec!["rev-list", "--count", &range];
+        if let Some(path) = path {
+            args.extend(["--", path]);
+        }
+
+        let count = self.exec_checked(args)?;
+        count.parse().map_err(|_| {
+            ChangelogError::ParseError(format!("Couldn't count the commits of: {}", range))
+        })
     }
 
     pub fn add(&self, path: &str) -> Result<&Self> {
@@ -49,29 +118,280 @@ impl Git {
         Ok(self)
     }
 
+    /// Create an annotated tag with the given message
+    pub fn annotated_tag(&self, tag: &str, message: &str) -> Result<&Self> {
+        self.exec_checked(vec!["tag", "-a", tag, "-m", message])?;
+        Ok(self)
+    }
+
     pub fn commit(&self, msg: &str) -> Result<&Self> {
         self.exec(vec!["commit", "-m", msg])?;
         Ok(self)
     }
 
+    /// Whether the working tree has no uncommitted changes
+    pub fn is_clean(&self) -> Result<bool> {
+        Ok(self
+            .exec_checked(vec!["status", "--porcelain"])?
+            .trim()
+            .is_empty())
+    }
+
+    pub fn tag_exists(&self, tag: &str) -> bool {
+        self.exec_checked(vec!["rev-parse", "--verify", &format!("refs/tags/{}", tag)])
+            .is_ok()
+    }
+
+    /// The upstream branch of the current branch, e.g.: "origin/main"
+    pub fn upstream(&self) -> Option<String> {
+        self.exec_checked(vec!["rev-parse", "--abbrev-ref", "@{upstream}"])
+            .ok()
+    }
+
+    /// The name and url of every remote, e.g.: ("origin", "git@github.com:org/repo.git")
+    pub fn remotes(&self) -> Result<Vec<(String, String)>> {
+        Ok(self
+            .exec(vec!["config", "--get-regexp", r"^remote\..*\.url$"])?
+            .lines()
+            .filter_map(|line| {
+                let (key, url) = line.split_once(' ')?;
+                let name = key.strip_prefix("remote.")?.strip_suffix(".url")?;
+                Some((name.to_string(), url.trim().to_string()))
+            })
+            .collect())
+    }
+
+    /// The contents of a file at the given revision, the path is relative to the working directory
+    pub fn show_file(&self, rev: &str, path: &str) -> Result<String> {
+        self.exec_checked(vec![
+            "rev-parse",
+            "--verify",
+            &format!("{}^{{commit}}", rev),
+        ])
+        .map_err(|_| ChangelogError::NotFound(format!("Unknown git revision: {}", rev)))?;
+
+        self.exec_checked(vec!["show", &format!("{}:./{}", rev, path)])
+    }
+
     pub fn exec(&self, args: Vec<&str>) -> Result<String> {
+        self.run(args).map(|output| {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stdout = stdout.trim();
+            stdout.to_string()
+        })
+    }
+
+    /// Same as `exec`, but fails when git exits with a non-zero status
+    pub fn exec_checked(&self, args: Vec<&str>) -> Result<String> {
+        let output = self.run(args)?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(ChangelogError::Command(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ))
+        }
+    }
+
+    fn run(&self, args: Vec<&str>) -> Result<Output> {
         let mut cmd = Command::new("git");
 
         cmd.current_dir(&self.pwd);
 
-        for arg in args {
+        for arg in &args {
             cmd.arg(arg);
         }
 
-        match cmd.output() {
-            Ok(output) => {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let stdout = stdout.trim();
-                let stdout = stdout.to_string();
+        let start = Instant::now();
+        let output = cmd
+            .output()
+            .map_err(|e| ChangelogError::Command(format!("Failed running git: {}", e)));
+
+        tracing::debug!(
+            args = %args.join(" "),
+            status = ?output.as_ref().map(|output| output.status.code()),
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "git"
+        );
 
-                Ok(stdout)
+        output
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub hash: String,
+
+    /// The committer date, e.g.: the date a pull request was merged for merge and squashed commits
+    pub date: Option<DateTime<FixedOffset>>,
+
+    pub message: String,
+}
+
+impl LogEntry {
+    /// The first line of the commit message
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+
+    /// Whether this is a merge commit created by GitHub, e.g.: "Merge pull request #123 from ..."
+    pub fn is_merge(&self) -> bool {
+        self.subject().starts_with("Merge pull request #")
+    }
+
+    /// The pull request number of merge commits, and of squashed commits e.g.: "Title (#123)"
+    pub fn pull_request(&self) -> Option<usize> {
+        let subject = self.subject();
+
+        if self.is_merge() {
+            return subject["Merge pull request #".len()..]
+                .split_whitespace()
+                .next()?
+                .parse()
+                .ok();
+        }
+
+        subject.strip_suffix(')')?.rsplit_once("(#")?.1.parse().ok()
+    }
+
+    /// The title of the commit, merge commits contain the title of the pull request in the body
+    pub fn title(&self) -> &str {
+        if self.is_merge() {
+            if let Some(title) = self
+                .message
+                .lines()
+                .skip(1)
+                .find(|line| !line.trim().is_empty())
+            {
+                return title.trim();
             }
-            Err(e) => Err(eyre!(e)),
         }
+
+        self.subject()
+    }
+}
+
+/// Squash merges and the merge commits of the same pull request both end up in the log. Only keep
+/// one commit per pull request, preferring the merge commit.
+pub fn dedupe_pull_requests(entries: Vec<LogEntry>) -> Vec<LogEntry> {
+    let mut result: Vec<LogEntry> = vec![];
+
+    for entry in entries {
+        let existing = entry.pull_request().and_then(|number| {
+            result
+                .iter()
+                .position(|other| other.pull_request() == Some(number))
+        });
+
+        match existing {
+            Some(idx) if entry.is_merge() && !result[idx].is_merge() => result[idx] = entry,
+            Some(_) => {}
+            None => result.push(entry),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: &str, message: &str) -> LogEntry {
+        LogEntry {
+            hash: hash.to_string(),
+            date: None,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_should_detect_pull_request_numbers() {
+        assert_eq!(entry("a", "Fix the parser (#12)").pull_request(), Some(12));
+        assert_eq!(
+            entry("b", "Merge pull request #34 from org/branch\n\nAdd tables").pull_request(),
+            Some(34)
+        );
+        assert_eq!(entry("c", "Fix the parser").pull_request(), None);
+    }
+
+    #[test]
+    fn it_should_dedupe_commits_of_the_same_pull_request() {
+        let entries = vec![
+            entry("a", "feat: add tables (#12)"),
+            entry("b", "fix: typo"),
+            entry(
+                "c",
+                "Merge pull request #12 from org/tables\n\nfeat: add tables",
+            ),
+            entry("d", "fix: the parser (#13)"),
+            entry("e", "fix: the parser (#13)"),
+        ];
+
+        let entries = dedupe_pull_requests(entries);
+
+        assert_eq!(
+            entries.iter().map(|e| e.hash.as_str()).collect::<Vec<_>>(),
+            vec!["c", "b", "d"]
+        );
+        assert_eq!(entries[0].title(), "feat: add tables");
+    }
+
+    #[test]
+    fn it_should_only_log_the_merge_commit_of_a_merged_branch() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(&dir)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+
+        git(&["init", "-q", "-b", "main"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "chore: init"]);
+        git(&["checkout", "-q", "-b", "tables"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "feat: add tables"]);
+        git(&[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "fix: align the tables",
+        ]);
+        git(&["checkout", "-q", "main"]);
+        git(&[
+            "merge",
+            "-q",
+            "--no-ff",
+            "tables",
+            "-m",
+            "Merge pull request #12 from org/tables\n\nfeat: add tables",
+        ]);
+        git(&[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "fix: the parser (#13)",
+        ]);
+
+        let entries = Git::new(Some(&dir))
+            .unwrap()
+            .log_range(None, "HEAD", None)
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            dedupe_pull_requests(entries)
+                .iter()
+                .map(|entry| entry.title())
+                .collect::<Vec<_>>(),
+            vec!["chore: init", "feat: add tables", "fix: the parser (#13)"]
+        );
     }
 }
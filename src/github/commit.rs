@@ -1,7 +1,7 @@
+use crate::error::{ChangelogError, Result};
 use crate::git::Git;
 use crate::github::{github_url::GitHubURL, repo::Repo};
 use crate::graphql::graphql;
-use color_eyre::eyre::Result;
 use serde_json::json;
 use std::fmt::{Debug, Display};
 use std::path::PathBuf;
@@ -17,15 +17,16 @@ pub struct Commit {
 
 impl Commit {
     pub fn from_local_commit(pwd: &PathBuf, maybe_hash: &str) -> Result<Self> {
-        let repo = Repo::from_git_repo(pwd)?;
+        Self::from_local_commit_of(pwd, maybe_hash, Repo::from_git_repo(pwd)?)
+    }
 
+    /// A local commit that links to the given repo, e.g.: to link to a mirror of the repo
+    fn from_local_commit_of(pwd: &PathBuf, maybe_hash: &str, repo: Repo) -> Result<Self> {
         let git = Git::new(Some(pwd))?;
 
         let long_hash = git.long_hash(maybe_hash)?;
         let short_hash = git.short_hash(maybe_hash)?;
-        let mut title = git.commit_message(maybe_hash)?;
-        // Uppercase first letter of `title`
-        title.replace_range(..1, &title[..1].to_uppercase());
+        let title = capitalize(&git.commit_message(maybe_hash)?);
 
         Ok(Self {
             hash: long_hash,
@@ -34,25 +35,66 @@ impl Commit {
             repo,
         })
     }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// A markdown link to this commit on its host
+    pub fn reference(&self) -> String {
+        format!(
+            "[{}]({}/commit/{})",
+            self.short_hash,
+            self.repo.url(),
+            self.hash
+        )
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = capitalize(title);
+        self
+    }
+}
+
+/// Uppercase the first letter of a title
+fn capitalize(title: &str) -> String {
+    let mut chars = title.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
 }
 
 impl Display for Commit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} ([{}](https://github.com/{}/{}/commit/{}))",
-            self.title, self.short_hash, self.repo.org, self.repo.repo, self.hash
-        )
+        write!(f, "{} ({})", self.title, self.reference())
     }
 }
 
 impl FromStr for Commit {
-    type Err = String;
+    type Err = ChangelogError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.parse::<GitHubURL>() {
             Ok(url) => {
-                let commit = url.parts.get("commit").expect("Missing commit hash in URL");
+                let commit = url.parts.get("commit").ok_or_else(|| {
+                    ChangelogError::ParseError("Missing commit hash in URL".to_string())
+                })?;
+
+                // Only GitHub has an API to fetch commits, but the commits of mirrors on other
+                // hosts also exist in the local clone.
+                if !url.repo.is_github() {
+                    let pwd = std::fs::canonicalize(".")?;
+                    if let Ok(commit) = Commit::from_local_commit_of(&pwd, commit, url.repo) {
+                        return Ok(commit);
+                    }
+
+                    return Err(ChangelogError::NotFound(format!(
+                        "Couldn't find commit {} in the local repository",
+                        commit
+                    )));
+                }
 
                 let data = json!({
                     "query": include_str!("./graphql/commit-info/query.graphql"),
@@ -63,14 +105,19 @@ impl FromStr for Commit {
                     }
                 });
 
-                let json = graphql(data)?;
+                let json = graphql(&url.repo.host, data)?;
 
-                let title = json["data"]["repository"]["object"]["title"]
-                    .as_str()
-                    .unwrap();
-                let short_hash = json["data"]["repository"]["object"]["short_hash"]
-                    .as_str()
-                    .unwrap();
+                let object = &json["data"]["repository"]["object"];
+                let (title, short_hash) =
+                    match (object["title"].as_str(), object["short_hash"].as_str()) {
+                        (Some(title), Some(short_hash)) => (title, short_hash),
+                        _ => {
+                            return Err(ChangelogError::NotFound(format!(
+                                "Couldn't find commit {}",
+                                commit
+                            )))
+                        }
+                    };
 
                 Ok(Self {
                     hash: commit.to_string(),
@@ -81,9 +128,9 @@ impl FromStr for Commit {
             }
             Err(_) => {
                 // TODO: Get from root
-                let pwd = std::fs::canonicalize(".").expect("File path doesn't seem to exist");
+                let pwd = std::fs::canonicalize(".")?;
 
-                Commit::from_local_commit(&pwd, s).map_err(|e| e.to_string())
+                Commit::from_local_commit(&pwd, s)
             }
         }
     }
@@ -1,3 +1,4 @@
+use crate::error::ChangelogError;
 use crate::github::{github_url::GitHubURL, repo::Repo};
 use crate::graphql::graphql;
 use serde_json::json;
@@ -11,18 +12,30 @@ pub struct Discussion {
     repo: Repo,
 }
 
+impl Discussion {
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// A markdown link to this discussion on its host
+    pub fn reference(&self) -> String {
+        format!(
+            "[#{}]({}/discussions/{})",
+            self.number,
+            self.repo.url(),
+            self.number
+        )
+    }
+}
+
 impl Display for Discussion {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} ([#{}](https://github.com/{}/{}/discussions/{}))",
-            self.title, self.number, self.repo.org, self.repo.repo, self.number
-        )
+        write!(f, "{} ({})", self.title, self.reference())
     }
 }
 
 impl FromStr for Discussion {
-    type Err = String;
+    type Err = ChangelogError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let url: GitHubURL = s.parse()?;
@@ -30,9 +43,11 @@ impl FromStr for Discussion {
         let discussion: usize = url
             .parts
             .get("discussion")
-            .expect("Missing discussion in URL")
+            .ok_or_else(|| {
+                ChangelogError::ParseError("Missing discussion number in URL".to_string())
+            })?
             .parse()
-            .map_err(|_| "Invalid discussion number")?;
+            .map_err(|_| ChangelogError::ParseError("Invalid discussion number".to_string()))?;
 
         let data = json!({
             "query": include_str!("./graphql/discussion-info/query.graphql"),
@@ -43,11 +58,13 @@ impl FromStr for Discussion {
             }
         });
 
-        let json = graphql(data)?;
+        let json = graphql(&url.repo.host, data)?;
 
         let title = json["data"]["repository"]["discussion"]["title"]
             .as_str()
-            .unwrap();
+            .ok_or_else(|| {
+                ChangelogError::NotFound(format!("Couldn't find discussion #{}", discussion))
+            })?;
 
         Ok(Self {
             number: discussion,
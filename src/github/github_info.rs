@@ -1,6 +1,11 @@
+use crate::config::{LinkFormat, ReferencePlacement};
+use crate::error::ChangelogError;
 use crate::github::{
-    commit::Commit, discussion::Discussion, issue::Issue, pull_request::PullRequest,
+    commit::Commit, discussion::Discussion, github_url::GitHubURL, issue::Issue,
+    pull_request::PullRequest, repo::Repo,
 };
+use crate::graphql::graphql;
+use serde_json::json;
 use std::fmt::Display;
 use std::str::FromStr;
 
@@ -17,24 +22,30 @@ impl Display for GitHubInfo {
         write!(
             f,
             "{}",
-            match self {
-                GitHubInfo::PullRequest(pr) => format!("{}", pr),
-                GitHubInfo::Commit(commit) => format!("{}", commit),
-                GitHubInfo::Issue(issue) => format!("{}", issue),
-                GitHubInfo::Discussion(discussion) => format!("{}", discussion),
-            }
+            self.render(ReferencePlacement::default(), LinkFormat::default())
         )
     }
 }
 
 impl FromStr for GitHubInfo {
-    type Err = String;
+    type Err = ChangelogError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((repo, number)) = parse_short_reference(s) {
+            return GitHubInfo::from_short_reference(repo, number);
+        }
+
         if s.contains("/commit/") || s.contains("/commits/") {
             return Ok(GitHubInfo::Commit(s.parse()?));
         }
 
+        if is_merge_request(s) {
+            return Err(ChangelogError::ParseError(format!(
+                "Can't fetch the title of a GitLab merge request, add it with --literal: {}",
+                s
+            )));
+        }
+
         if s.contains("/pull/") || s.contains("/pulls/") {
             return Ok(GitHubInfo::PullRequest(s.parse()?));
         }
@@ -50,3 +61,286 @@ impl FromStr for GitHubInfo {
         Ok(GitHubInfo::Commit(s.parse()?))
     }
 }
+
+impl GitHubInfo {
+    pub fn title(&self) -> &str {
+        match self {
+            GitHubInfo::PullRequest(pr) => pr.title(),
+            GitHubInfo::Commit(commit) => commit.title(),
+            GitHubInfo::Issue(issue) => issue.title(),
+            GitHubInfo::Discussion(discussion) => discussion.title(),
+        }
+    }
+
+    /// The login of the author of a pull request
+    pub fn author(&self) -> Option<&str> {
+        match self {
+            GitHubInfo::PullRequest(pr) => pr.author(),
+            _ => None,
+        }
+    }
+
+    /// Credit the author of a pull request in its title, e.g.: with the name from the author map
+    pub fn thank(&mut self, name: &str) {
+        if let GitHubInfo::PullRequest(pr) = self {
+            pr.thank(name);
+        }
+    }
+
+    pub fn reference(&self, format: LinkFormat) -> String {
+        match self {
+            GitHubInfo::PullRequest(pr) => pr.reference(format),
+            GitHubInfo::Commit(commit) => commit.reference(),
+            GitHubInfo::Issue(issue) => issue.reference(),
+            GitHubInfo::Discussion(discussion) => discussion.reference(),
+        }
+    }
+
+    /// Render the entry with the reference at the given placement, in the given format
+    pub fn render(&self, placement: ReferencePlacement, format: LinkFormat) -> String {
+        let reference = self.reference(format);
+
+        match placement {
+            ReferencePlacement::Prefix => format!("{}: {}", reference, self.title()),
+            ReferencePlacement::Suffix => format!("{} ({})", self.title(), reference),
+        }
+    }
+
+    /// Resolve a short reference to an issue or pull request. When the repo is omitted, it is
+    /// inferred from the git remote of the current directory.
+    fn from_short_reference(repo: Option<Repo>, number: usize) -> Result<Self, ChangelogError> {
+        let repo = match repo {
+            Some(repo) => repo,
+            None => {
+                let pwd = std::fs::canonicalize(".")?;
+
+                Repo::from_git_repo(&pwd).map_err(|_| {
+                    ChangelogError::NotFound(format!(
+                        "Couldn't infer the repo for #{}, use <owner>/<repo>#{} instead",
+                        number, number
+                    ))
+                })?
+            }
+        };
+
+        let data = json!({
+            "query": include_str!("./graphql/issue-or-pr-info/query.graphql"),
+            "variables": {
+                "org": repo.org,
+                "repo": repo.repo,
+                "number": number
+            }
+        });
+
+        let json = graphql(&repo.host, data)?;
+        let node = &json["data"]["repository"]["issueOrPullRequest"];
+        let title = node["title"]
+            .as_str()
+            .ok_or_else(|| {
+                ChangelogError::NotFound(format!("Couldn't find issue or pull request #{}", number))
+            })?
+            .to_string();
+
+        match node["__typename"].as_str() {
+            Some("PullRequest") => Ok(GitHubInfo::PullRequest(PullRequest::new(
+                number,
+                title,
+                node["author"]["login"]
+                    .as_str()
+                    .map(|login| login.to_string()),
+                repo,
+            ))),
+            _ => Ok(GitHubInfo::Issue(Issue::new(number, title, repo))),
+        }
+    }
+}
+
+/// The reference (markdown link) of an entry, in either the prefix or the suffix placement. A
+/// tag like "[api] " before the title isn't mistaken for a prefixed reference.
+pub fn entry_reference(entry: &str) -> Option<&str> {
+    if let Some(end) = entry.strip_prefix('[').and_then(|rest| rest.find("): ")) {
+        return Some(&entry[..end + 2]);
+    }
+
+    let start = entry.strip_suffix("))")?.rfind(" ([")?;
+    Some(&entry[start + 2..entry.len() - 1])
+}
+
+/// The sigil of a reference to a pull request in the given format, e.g.: "#" for "#12" on GitHub
+/// and "!" for "!12" (a merge request) on GitLab
+pub fn request_sigil(format: LinkFormat, repo: &Repo) -> char {
+    match format {
+        LinkFormat::Auto if repo.is_gitlab() => '!',
+        LinkFormat::Auto | LinkFormat::GitHub => '#',
+        LinkFormat::GitLab => '!',
+    }
+}
+
+/// A markdown link to a URL without fetching its title, e.g.: "[#12](.../pull/12)". Links that
+/// aren't URLs are used as-is.
+pub fn literal_reference(link: &str, format: LinkFormat) -> String {
+    match link.parse::<GitHubURL>() {
+        Ok(url) => {
+            let label = url
+                .parts
+                .get("pull")
+                .map(|number| format!("{}{}", request_sigil(format, &url.repo), number))
+                .or_else(|| {
+                    ["issue", "discussion"]
+                        .iter()
+                        .find_map(|kind| url.parts.get(*kind))
+                        .map(|number| format!("#{}", number))
+                })
+                .or_else(|| {
+                    url.parts
+                        .get("commit")
+                        .map(|hash| hash.chars().take(7).collect())
+                })
+                .unwrap_or_else(|| link.to_string());
+
+            format!("[{}]({})", label, link)
+        }
+        Err(_) => link.to_string(),
+    }
+}
+
+/// Whether the link is a GitLab merge request, e.g.: "https://gitlab.com/org/repo/-/merge_requests/12".
+/// Only the GitHub API is supported, so their titles can't be fetched.
+pub fn is_merge_request(link: &str) -> bool {
+    link.contains("/merge_requests/")
+}
+
+/// The text of an entry without its reference
+pub fn entry_title(entry: &str) -> &str {
+    match entry_reference(entry) {
+        Some(reference) if entry.starts_with(reference) => &entry[reference.len() + 2..],
+        Some(reference) => &entry[..entry.len() - reference.len() - 3],
+        None => entry,
+    }
+}
+
+/// Parse short references like `owner/repo#123` or `#123`
+fn parse_short_reference(s: &str) -> Option<(Option<Repo>, usize)> {
+    let (repo, number) = s.split_once('#')?;
+    let number = number.parse().ok()?;
+
+    if repo.is_empty() {
+        return Some((None, number));
+    }
+
+    match repo.split_once('/') {
+        Some((org, repo))
+            if !org.is_empty() && !repo.is_empty() && !org.contains(':') && !repo.contains('/') =>
+        {
+            Some((Some(Repo::new(org.to_string(), repo.to_string())), number))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_find_the_reference_of_an_entry() {
+        let reference = "[#12](https://github.com/org/repo/pull/12)";
+
+        assert_eq!(
+            entry_reference(&format!("Add tables ({})", reference)),
+            Some(reference)
+        );
+        assert_eq!(
+            entry_reference(&format!("{}: Add tables", reference)),
+            Some(reference)
+        );
+        assert_eq!(entry_reference("Add tables"), None);
+        assert_eq!(
+            entry_reference(&format!("[api] Add tables ({})", reference)),
+            Some(reference)
+        );
+    }
+
+    #[test]
+    fn it_should_render_literal_references() {
+        let format = LinkFormat::Auto;
+
+        assert_eq!(
+            literal_reference("https://github.com/org/repo/pull/12", format),
+            "[#12](https://github.com/org/repo/pull/12)"
+        );
+        assert_eq!(
+            literal_reference("https://github.com/org/repo/commit/a1b2c3d4e5f6", format),
+            "[a1b2c3d](https://github.com/org/repo/commit/a1b2c3d4e5f6)"
+        );
+        assert_eq!(
+            literal_reference("https://example.com/org/repo", format),
+            "[https://example.com/org/repo](https://example.com/org/repo)"
+        );
+        assert_eq!(literal_reference("org/repo#12", format), "org/repo#12");
+    }
+
+    #[test]
+    fn it_should_render_references_in_the_format_of_the_host() {
+        let merge_request = "https://gitlab.com/org/repo/-/merge_requests/12";
+        let issue = "https://gitlab.com/org/repo/-/issues/13";
+
+        assert!(is_merge_request(merge_request));
+        assert!(merge_request.parse::<GitHubInfo>().is_err());
+        assert_eq!(
+            literal_reference(merge_request, LinkFormat::Auto),
+            format!("[!12]({})", merge_request)
+        );
+        assert_eq!(
+            literal_reference(merge_request, LinkFormat::GitHub),
+            format!("[#12]({})", merge_request)
+        );
+        assert_eq!(
+            literal_reference(issue, LinkFormat::Auto),
+            format!("[#13]({})", issue)
+        );
+        assert_eq!(
+            literal_reference("https://github.com/org/repo/pull/12", LinkFormat::GitLab),
+            "[!12](https://github.com/org/repo/pull/12)"
+        );
+    }
+
+    #[test]
+    fn it_should_find_the_title_of_an_entry() {
+        let reference = "[#12](https://github.com/org/repo/pull/12)";
+
+        assert_eq!(
+            entry_title(&format!("Add tables ({})", reference)),
+            "Add tables"
+        );
+        assert_eq!(
+            entry_title(&format!("{}: Add tables", reference)),
+            "Add tables"
+        );
+        assert_eq!(entry_title("Add tables"), "Add tables");
+        assert_eq!(
+            entry_title(&format!("[api] Add tables ({})", reference)),
+            "[api] Add tables"
+        );
+    }
+
+    #[test]
+    fn it_should_parse_short_references() {
+        let (repo, number) = parse_short_reference("owner/repo#123").unwrap();
+        let repo = repo.unwrap();
+        assert_eq!(
+            (repo.org.as_str(), repo.repo.as_str(), number),
+            ("owner", "repo", 123)
+        );
+
+        let (repo, number) = parse_short_reference("#42").unwrap();
+        assert!(repo.is_none());
+        assert_eq!(number, 42);
+
+        assert!(
+            parse_short_reference("https://github.com/owner/repo/pull/1#issuecomment-1").is_none()
+        );
+        assert!(parse_short_reference("owner/repo#abc").is_none());
+        assert!(parse_short_reference("a1b2c3d").is_none());
+    }
+}
@@ -1,4 +1,5 @@
-use crate::github::repo::Repo;
+use crate::error::ChangelogError;
+use crate::github::repo::{Repo, GITHUB_HOST};
 use reqwest::Url;
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -11,27 +12,37 @@ pub struct GitHubURL {
 }
 
 impl FromStr for GitHubURL {
-    type Err = String;
+    type Err = ChangelogError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parts: HashMap<String, String> = HashMap::new();
 
-        let url = Url::parse(s).map_err(|_| "Invalid URL")?;
-        let mut segments = url.path()[1..].split('/');
+        let url =
+            Url::parse(s).map_err(|_| ChangelogError::ParseError(format!("Invalid URL: {}", s)))?;
+        // GitLab separates the repo from the rest of the path with a "-" segment
+        let mut segments = url.path()[1..].split('/').filter(|segment| *segment != "-");
 
         // Insert known parts
         parts.insert(
             "org".to_string(),
             segments
                 .next()
-                .expect("URL should contain the organization/owner of the repo")
+                .filter(|org| !org.is_empty())
+                .ok_or_else(|| {
+                    ChangelogError::ParseError(
+                        "URL should contain the organization/owner of the repo".to_string(),
+                    )
+                })?
                 .to_string(),
         );
         parts.insert(
             "repo".to_string(),
             segments
                 .next()
-                .expect("URL should contain the repo")
+                .filter(|repo| !repo.is_empty())
+                .ok_or_else(|| {
+                    ChangelogError::ParseError("URL should contain the repo".to_string())
+                })?
                 .to_string(),
         );
 
@@ -43,15 +54,17 @@ impl FromStr for GitHubURL {
                     parts.insert("discussion".to_string(), value.to_string())
                 }
                 "issues" | "issue" => parts.insert("issue".to_string(), value.to_string()),
+                "merge_requests" => parts.insert("pull".to_string(), value.to_string()),
                 _ => parts.insert(key.to_string(), value.to_string()),
             };
         }
 
         Ok(Self {
-            repo: Repo {
-                org: parts.get("org").unwrap().to_string(),
-                repo: parts.get("repo").unwrap().to_string(),
-            },
+            repo: Repo::with_host(
+                url.host_str().unwrap_or(GITHUB_HOST).to_string(),
+                parts.get("org").unwrap().to_string(),
+                parts.get("repo").unwrap().to_string(),
+            ),
             parts,
         })
     }
@@ -1,3 +1,4 @@
+use crate::error::ChangelogError;
 use crate::github::github_url::GitHubURL;
 use crate::github::repo::Repo;
 use crate::graphql::graphql;
@@ -12,18 +13,44 @@ pub struct Issue {
     repo: Repo,
 }
 
+impl Issue {
+    pub fn new(number: usize, title: String, repo: Repo) -> Self {
+        Self {
+            number,
+            title,
+            repo,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// A markdown link to this issue on its host
+    pub fn reference(&self) -> String {
+        let path = match self.repo.is_gitlab() {
+            true => "-/issues",
+            false => "issues",
+        };
+
+        format!(
+            "[#{}]({}/{}/{})",
+            self.number,
+            self.repo.url(),
+            path,
+            self.number
+        )
+    }
+}
+
 impl Display for Issue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} ([#{}](https://github.com/{}/{}/issues/{}))",
-            self.title, self.number, self.repo.org, self.repo.repo, self.number
-        )
+        write!(f, "{} ({})", self.title, self.reference())
     }
 }
 
 impl FromStr for Issue {
-    type Err = String;
+    type Err = ChangelogError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let url: GitHubURL = s.parse()?;
@@ -31,9 +58,9 @@ impl FromStr for Issue {
         let issue: usize = url
             .parts
             .get("issue")
-            .expect("Missing issue in URL")
+            .ok_or_else(|| ChangelogError::ParseError("Missing issue number in URL".to_string()))?
             .parse()
-            .map_err(|_| "Invalid issue number")?;
+            .map_err(|_| ChangelogError::ParseError("Invalid issue number".to_string()))?;
 
         let data = json!({
             "query": include_str!("./graphql/issue-info/query.graphql"),
@@ -44,11 +71,11 @@ impl FromStr for Issue {
             }
         });
 
-        let json = graphql(data)?;
+        let json = graphql(&url.repo.host, data)?;
 
         let title = json["data"]["repository"]["issue"]["title"]
             .as_str()
-            .unwrap();
+            .ok_or_else(|| ChangelogError::NotFound(format!("Couldn't find issue #{}", issue)))?;
 
         Ok(Self {
             number: issue,
@@ -1,3 +1,6 @@
+use crate::config::{LinkFormat, ReferencePlacement};
+use crate::error::ChangelogError;
+use crate::github::github_info::{is_merge_request, request_sigil};
 use crate::github::github_url::GitHubURL;
 use crate::github::repo::Repo;
 use crate::graphql::graphql;
@@ -9,31 +12,99 @@ use std::str::FromStr;
 pub struct PullRequest {
     number: usize,
     title: String,
+    author: Option<String>,
+    commits: Vec<String>,
     repo: Repo,
 }
 
+impl PullRequest {
+    pub fn new(number: usize, title: String, author: Option<String>, repo: Repo) -> Self {
+        Self {
+            number,
+            title,
+            author,
+            commits: vec![],
+            repo,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The login of the author, e.g.: "octocat"
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// An entry for every commit of this pull request (oldest first) that references the pull
+    /// request, e.g.: "Add rows ([#12](...))". The commits are only known when the pull request was
+    /// fetched by its URL.
+    pub fn commit_entries(&self, placement: ReferencePlacement, format: LinkFormat) -> Vec<String> {
+        let reference = self.reference(format);
+
+        self.commits
+            .iter()
+            .map(|subject| match placement {
+                ReferencePlacement::Prefix => format!("{}: {}", reference, subject),
+                ReferencePlacement::Suffix => format!("{} ({})", subject, reference),
+            })
+            .collect()
+    }
+
+    /// Credit the author in the title, e.g.: "Add tables, thanks @octocat"
+    pub fn thank(&mut self, name: &str) {
+        self.title = format!("{}, thanks {}", self.title, name);
+    }
+
+    /// A markdown link to this pull request (or merge request) on its host
+    pub fn reference(&self, format: LinkFormat) -> String {
+        let path = match self.repo.is_gitlab() {
+            true => "-/merge_requests",
+            false => "pull",
+        };
+
+        format!(
+            "[{}{}]({}/{}/{})",
+            request_sigil(format, &self.repo),
+            self.number,
+            self.repo.url(),
+            path,
+            self.number
+        )
+    }
+}
+
 impl Display for PullRequest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{} ([#{}](https://github.com/{}/{}/pull/{}))",
-            self.title, self.number, self.repo.org, self.repo.repo, self.number
+            "{} ({})",
+            self.title,
+            self.reference(LinkFormat::default())
         )
     }
 }
 
 impl FromStr for PullRequest {
-    type Err = String;
+    type Err = ChangelogError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_merge_request(s) {
+            return Err(ChangelogError::ParseError(format!(
+                "Can't fetch a GitLab merge request, only GitHub pull requests: {}",
+                s
+            )));
+        }
+
         let url: GitHubURL = s.parse()?;
 
         let pull: usize = url
             .parts
             .get("pull")
-            .expect("Missing repo in URL")
+            .ok_or_else(|| ChangelogError::ParseError("Missing pull number in URL".to_string()))?
             .parse()
-            .map_err(|_| "Invalid pull number")?;
+            .map_err(|_| ChangelogError::ParseError("Invalid pull number".to_string()))?;
 
         let data = json!({
             "query": include_str!("./graphql/pr-info/query.graphql"),
@@ -44,15 +115,34 @@ impl FromStr for PullRequest {
             }
         });
 
-        let json = graphql(data)?;
+        let json = graphql(&url.repo.host, data)?;
 
         let title = json["data"]["repository"]["pullRequest"]["title"]
             .as_str()
-            .unwrap();
+            .ok_or_else(|| {
+                ChangelogError::NotFound(format!("Couldn't find pull request #{}", pull))
+            })?;
+
+        let author = json["data"]["repository"]["pullRequest"]["author"]["login"]
+            .as_str()
+            .map(|login| login.to_string());
+
+        let commits = json["data"]["repository"]["pullRequest"]["commits"]["nodes"]
+            .as_array()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .filter_map(|node| node["commit"]["messageHeadline"].as_str())
+                    .map(|subject| subject.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
 
         Ok(Self {
             number: pull,
             title: title.to_string(),
+            author,
+            commits,
             repo: url.repo,
         })
     }
@@ -1,37 +1,125 @@
+use crate::error::{ChangelogError, Result};
 use crate::git::Git;
-use color_eyre::eyre::{eyre, Result};
+use crate::graphql::is_github_host;
+use reqwest::Url;
 use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const GITHUB_HOST: &str = "github.com";
 
 #[derive(Debug)]
 pub struct Repo {
+    pub host: String,
     pub org: String,
     pub repo: String,
 }
 
 impl Repo {
     pub fn new(org: String, repo: String) -> Self {
-        Self { org, repo }
+        Self::with_host(GITHUB_HOST.to_string(), org, repo)
+    }
+
+    pub fn with_host(host: String, org: String, repo: String) -> Self {
+        Self { host, org, repo }
+    }
+
+    /// The web url of the repo, e.g.: "https://github.com/org/repo"
+    pub fn url(&self) -> String {
+        format!("https://{}/{}/{}", self.host, self.org, self.repo)
+    }
+
+    /// Whether the repo is hosted on github.com or a configured GitHub Enterprise Server
+    pub fn is_github(&self) -> bool {
+        is_github_host(&self.host)
+    }
+
+    /// Whether the repo is hosted on GitLab, e.g.: "gitlab.com" or "gitlab.example.com"
+    pub fn is_gitlab(&self) -> bool {
+        self.host.split('.').any(|part| part == "gitlab")
     }
 
     pub fn from_git_repo(pwd: &PathBuf) -> Result<Self> {
-        match Git::new(Some(pwd))?.exec(vec!["config", "--get", "remote.origin.url"]) {
-            Ok(output) => {
-                let output = output.replace(".git", "");
-
-                let parts = output
-                    .split(':')
-                    .collect::<Vec<&str>>()
-                    .pop()
-                    .unwrap()
-                    .split('/')
-                    .collect::<Vec<&str>>();
-
-                match (parts.first(), parts.get(1)) {
-                    (Some(owner), Some(repo)) => Ok(Self::new(owner.to_string(), repo.to_string())),
-                    _ => Err(eyre!("Could not parse git remote url")),
-                }
+        Self::from_remote(pwd, "origin")
+    }
+
+    /// The repo of a git remote, e.g.: "origin"
+    pub fn from_remote(pwd: &PathBuf, name: &str) -> Result<Self> {
+        Git::new(Some(pwd))?
+            .remotes()?
+            .into_iter()
+            .find(|(remote, _)| remote == name)
+            .ok_or_else(|| ChangelogError::NotFound(format!("Couldn't find git remote: {}", name)))
+            .and_then(|(_, url)| url.parse())
+    }
+}
+
+impl FromStr for Repo {
+    type Err = ChangelogError;
+
+    /// Parse a git remote url, e.g.: "git@github.com:org/repo.git" or
+    /// "https://gitlab.example.com/group/subgroup/repo.git"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || ChangelogError::ParseError(format!("Could not parse git remote url: {}", s));
+
+        let (host, path) = match s.contains("://") {
+            true => {
+                let url = Url::parse(s).map_err(|_| invalid())?;
+                let host = url.host_str().ok_or_else(invalid)?.to_string();
+                (host, url.path().to_string())
+            }
+            // The scp-like syntax, e.g.: "git@github.com:org/repo.git"
+            false => {
+                let (host, path) = s.split_once(':').ok_or_else(invalid)?;
+                let host = host.rsplit('@').next().unwrap_or(host).to_string();
+                (host, path.to_string())
+            }
+        };
+
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+
+        match path.rsplit_once('/') {
+            Some((org, repo)) if !org.is_empty() && !repo.is_empty() => {
+                Ok(Self::with_host(host, org.to_string(), repo.to_string()))
             }
-            Err(e) => Err(eyre!("Failed running git: {}", e)),
+            _ => Err(invalid()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_parse_git_remote_urls() {
+        let repo: Repo = "git@github.com:org/repo.git".parse().unwrap();
+        assert_eq!(repo.url(), "https://github.com/org/repo");
+        assert!(repo.is_github());
+
+        let repo: Repo = "https://gitlab.example.com/group/subgroup/repo.git"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            (repo.host.as_str(), repo.org.as_str(), repo.repo.as_str()),
+            ("gitlab.example.com", "group/subgroup", "repo")
+        );
+
+        let repo: Repo = "ssh://git@gitlab.example.com:2222/org/repo"
+            .parse()
+            .unwrap();
+        assert_eq!(repo.url(), "https://gitlab.example.com/org/repo");
+
+        assert!("not a remote".parse::<Repo>().is_err());
+    }
+
+    #[test]
+    fn it_should_not_send_the_token_to_other_hosts() {
+        let repo: Repo = "https://git.example.com/org/repo.git".parse().unwrap();
+        assert!(!repo.is_github());
+
+        let result = crate::graphql::graphql(&repo.host, serde_json::json!({}));
+        assert!(matches!(result, Err(ChangelogError::NotFound(_))));
+    }
+}
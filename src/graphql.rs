@@ -1,20 +1,110 @@
+use crate::error::{ChangelogError, Result};
+use crate::github::repo::GITHUB_HOST;
+use crate::shell::Shell;
 use reqwest::header::{HeaderValue, CONTENT_TYPE, USER_AGENT};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Instant;
 
-pub fn graphql(data: serde_json::Value) -> Result<serde_json::Value, String> {
+/// The command that prints a GitHub token (e.g.: `gh auth token`), with the directory to run it in
+static TOKEN_COMMAND: OnceLock<(String, PathBuf)> = OnceLock::new();
+
+/// The token that was printed by the token command, it only runs once
+static COMMAND_TOKEN: OnceLock<Option<String>> = OnceLock::new();
+
+/// The configured GitHub Enterprise Server hosts
+static GITHUB_HOSTS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Use a command to get a GitHub token when the `GITHUB_API_TOKEN` environment variable isn't set.
+/// The command only runs when a GitHub request is made.
+pub fn set_token_command(command: &str, pwd: &Path) {
+    let _ = TOKEN_COMMAND.set((command.to_string(), pwd.to_path_buf()));
+}
+
+/// Allow the API of GitHub Enterprise Server hosts to be used, besides github.com
+pub fn set_github_hosts(hosts: &[String]) {
+    let _ = GITHUB_HOSTS.set(hosts.to_vec());
+}
+
+/// Whether the host is github.com or a configured GitHub Enterprise Server, only their API is
+/// sent the GitHub token
+pub fn is_github_host(host: &str) -> bool {
+    host == GITHUB_HOST
+        || GITHUB_HOSTS
+            .get()
+            .is_some_and(|hosts| hosts.iter().any(|github_host| github_host == host))
+}
+
+/// The GitHub token from the `GITHUB_API_TOKEN` environment variable, or from the token command.
+/// A failing token command is treated as if there is no token.
+fn token() -> Option<String> {
+    if let Ok(token) = std::env::var("GITHUB_API_TOKEN") {
+        return Some(token);
+    }
+
+    COMMAND_TOKEN
+        .get_or_init(|| {
+            let (command, pwd) = TOKEN_COMMAND.get()?;
+
+            match Shell::new(pwd).run(command) {
+                Ok(token) if !token.is_empty() => Some(token),
+                Ok(_) => None,
+                Err(e) => {
+                    tracing::warn!(error = %e, "token command failed");
+                    None
+                }
+            }
+        })
+        .clone()
+}
+
+/// Run a query against the GraphQL API of a host, either github.com or a GitHub Enterprise Server
+pub fn graphql(host: &str, data: serde_json::Value) -> Result<serde_json::Value> {
+    if !is_github_host(host) {
+        return Err(ChangelogError::NotFound(format!(
+            "{} isn't a GitHub host, add it to `github_hosts` in the config or use --literal",
+            host
+        )));
+    }
+
+    let token =
+        token().ok_or_else(|| ChangelogError::Network("GITHUB_API_TOKEN not set".to_string()))?;
+
+    let start = Instant::now();
     let json = reqwest::blocking::Client::new()
-        .post("https://api.github.com/graphql")
-        .bearer_auth(std::env::var("GITHUB_API_TOKEN").expect("GITHUB_API_TOKEN not set"))
+        .post(endpoint(host))
+        .bearer_auth(token)
         .header(USER_AGENT, HeaderValue::from_static("reqwest"))
         .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
         .body(data.to_string())
         .send()
-        .unwrap()
-        .json::<serde_json::Value>()
-        .unwrap();
+        .and_then(|response| response.json::<serde_json::Value>())
+        .map_err(|e| ChangelogError::Network(e.to_string()));
+
+    tracing::debug!(
+        endpoint = %endpoint(host),
+        ok = json.is_ok(),
+        elapsed_ms = start.elapsed().as_millis() as u64,
+        "graphql"
+    );
 
+    let json = json?;
     if let Some(errors) = json["errors"].as_array() {
-        return Err(errors[0]["message"].as_str().unwrap().to_string());
+        return Err(ChangelogError::Network(
+            errors[0]["message"]
+                .as_str()
+                .unwrap_or("Unknown GitHub API error")
+                .to_string(),
+        ));
     }
 
     Ok(json)
 }
+
+/// The GraphQL endpoint of a host, GitHub Enterprise Server hosts the API on the same host
+fn endpoint(host: &str) -> String {
+    match host {
+        GITHUB_HOST => "https://api.github.com/graphql".to_string(),
+        _ => format!("https://{}/api/graphql", host),
+    }
+}
@@ -1,4 +1,7 @@
 mod changelog;
+mod config;
+mod conventional_commit;
+mod error;
 mod git;
 mod github;
 mod graphql;
@@ -8,21 +11,43 @@ mod npm;
 mod output;
 mod package;
 mod rich_edit;
-
-use crate::changelog::{Amount, Changelog};
+mod shell;
+
+use crate::changelog::{
+    fix_heading_levels, version_heading_problems, Amount, Change, Changelog, ExportFormat, GroupBy,
+    InitOptions, ListOptions, NotesFormat, NotesOptions, Problem, ReleaseOptions,
+};
+use crate::config::{CommitOrder, Config, LinkFormat, SectionCase};
+use crate::conventional_commit::ConventionalCommit;
 use crate::git::Git;
-use crate::github::github_info::GitHubInfo;
+use crate::github::github_info::{is_merge_request, literal_reference, GitHubInfo};
+use crate::github::pull_request::PullRequest;
 use crate::list_format::conjunction;
+use crate::markdown::normalize::Tool;
 use crate::markdown::{ast::Node, tokens::MarkdownToken};
-use crate::npm::{Npm, Options};
-use crate::output::{output, output_indented, output_title};
-use crate::package::{PackageJSON, SemVer};
+use crate::npm::{Npm, Options, LOCKFILES};
+use crate::output::{
+    copy_to_clipboard, output, output_error, output_indented, output_paged, output_porcelain,
+    output_status, output_title, output_warning, strip_colors, terminal_width, ErrorFormat,
+    Progress,
+};
+use crate::package::{PackageJSON, SemVer, Version};
 use crate::rich_edit::rich_edit;
-use clap::{Parser, Subcommand};
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
 use color_eyre::eyre::{eyre, Result};
 use colored::*;
-use dialoguer::MultiSelect;
-use std::{collections::HashMap, fmt::Debug, fs, path::PathBuf};
+use dialoguer::{Confirm, MultiSelect};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+use tracing_subscriber::filter::LevelFilter;
+
+const VERSION_PIN_FILENAME: &str = ".changelog-version";
 
 /// Make CHANGELOG.md changes easier
 #[derive(Parser, Debug)]
@@ -47,6 +72,86 @@ struct Cli {
     )]
     scopes: Vec<String>,
 
+    /// The format of errors that scripts might want to detect, one of: "human" or "json"
+    #[clap(long, default_value = "human", global = true)]
+    error_format: ErrorFormat,
+
+    /// Don't show the progress of long running operations
+    #[clap(short, long, global = true)]
+    quiet: bool,
+
+    /// Don't pipe long output (of `list`, `notes` and `search`) through the `$PAGER`
+    #[clap(long, global = true)]
+    no_pager: bool,
+
+    /// Only print plain ASCII, without emoji or Unicode punctuation like "…", e.g.: for terminals
+    /// or CI logs without Unicode support
+    #[clap(long, global = true)]
+    ascii: bool,
+
+    /// Don't color the output, like setting `$NO_COLOR`
+    #[clap(long, global = true)]
+    no_color: bool,
+
+    /// Print the confirmation of `add`, `fix`, ... as plain text, without highlighting the added
+    /// entries
+    #[clap(long, global = true)]
+    no_bold_confirmation: bool,
+
+    /// Show the commands that are run, like the release hooks
+    #[clap(long, global = true)]
+    verbose: bool,
+
+    /// Log the file I/O, git commands and GitHub requests (with their timings) to stderr, one of
+    /// "off", "error", "warn", "info", "debug" or "trace". Defaults to `$RUST_LOG`, or "off".
+    #[clap(long, global = true)]
+    log_level: Option<LevelFilter>,
+
+    /// The prefix of the git tags of versions, e.g.: "v" for "v1.2.3", or "" for "1.2.3". Overrides
+    /// the `tag_prefix` config.
+    #[clap(long, global = true)]
+    tag_prefix: Option<String>,
+
+    /// The text of the references to pull requests and issues, one of "auto" (based on the host),
+    /// "github" (e.g.: "#123") or "gitlab" (e.g.: "!123" for merge requests). Overrides the
+    /// `link_format` config.
+    #[clap(long, global = true)]
+    link_format: Option<LinkFormat>,
+
+    /// The casing of new section headings, one of "preserve" (as given, e.g.: "Added"), "title"
+    /// (e.g.: "Bug Fixes") or "lower" (e.g.: "bug fixes"). Overrides the `section_case` config.
+    #[clap(long, value_name = "CASE", global = true)]
+    section_case: Option<SectionCase>,
+
+    /// A JSON file with the names to credit authors with when using `--thanks`, by their login,
+    /// e.g.: `{ "octocat": "Mona Lisa" }`. Extends the `author_map` config.
+    #[clap(long, global = true)]
+    author_map: Option<String>,
+
+    /// Fail instead of warning when the changelog has duplicate or misordered version headings
+    #[clap(long, global = true)]
+    strict: bool,
+
+    /// Remove the emoji (e.g.: gitmoji) from entries that are generated from commits, pull
+    /// requests or an imported changelog, like the `strip_emoji` config.
+    #[clap(long, global = true)]
+    strip_emoji: bool,
+
+    /// Wrap the mentions of users and teams (e.g.: "@octocat") in entries that are generated from
+    /// commits or pull requests in backticks, so that posting the notes doesn't notify them. Like
+    /// the `escape_mentions` config.
+    #[clap(long, global = true)]
+    escape_mentions: bool,
+
+    /// Print a summary of the versions and entries that a command changed, e.g.: "+1 entry under
+    /// Fixed in Unreleased" or "~2 entries reordered under Fixed in Unreleased"
+    #[clap(long, global = true)]
+    preview_diff: bool,
+
+    /// Fail instead of warning when this version doesn't satisfy the `.changelog-version` pin
+    #[clap(long, global = true)]
+    strict_version: bool,
+
     /// The subcommand to run
     #[clap(subcommand)]
     command: Commands,
@@ -55,141 +160,347 @@ struct Cli {
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Initialize a new CHANGELOG.md file, if it doesn't exist yet
-    Init,
+    Init {
+        /// Scaffold the unreleased section with the placeholder entry of every section, and add
+        /// it to an existing changelog that doesn't have an unreleased section
+        #[clap(long)]
+        ensure: bool,
+
+        /// Overwrite an existing changelog with a new one, the old one is backed up to
+        /// `<filename>.bak`
+        #[clap(long)]
+        force: bool,
+
+        /// Don't ask for confirmation before overwriting an existing changelog
+        #[clap(short, long, requires = "force")]
+        yes: bool,
+
+        /// Don't back up the existing changelog when overwriting it
+        #[clap(long, requires = "force")]
+        no_backup: bool,
+    },
 
     /// Add a new entry to the changelog in the "Added" section
     Add {
-        /// A link to the commit, pr, issue, ...
-        #[clap(conflicts_with = "message")]
-        link: Option<String>,
-
-        /// A manual message you want to add
-        #[clap(short, long, conflicts_with = "link")]
-        message: Option<String>,
+        #[clap(flatten)]
+        add: AddArgs,
 
         /// The section name to add the entry to
         #[clap(hide = true, default_value = "Added")]
         name: String,
-
-        /// Whether or not to commit the changes
-        #[clap(short, long)]
-        commit: bool,
-
-        /// Whether you want to edit the (automated) message after it got fetched from GitHub
-        #[clap(short, long)]
-        edit: bool,
     },
 
     /// Add a new entry to the changelog in the "Fixed" section
     Fix {
-        /// A link to the commit, pr, issue, ...
-        #[clap(conflicts_with = "message")]
-        link: Option<String>,
-
-        /// A manual message you want to add
-        #[clap(short, long, conflicts_with = "link")]
-        message: Option<String>,
+        #[clap(flatten)]
+        add: AddArgs,
 
         /// The section name to add the entry to
         #[clap(hide = true, default_value = "Fixed")]
         name: String,
-
-        /// Whether or not to commit the changes
-        #[clap(short, long)]
-        commit: bool,
-
-        /// Whether you want to edit the (automated) message after it got fetched from GitHub
-        #[clap(short, long)]
-        edit: bool,
     },
 
     /// Add a new entry to the changelog in the "Changed" section
     Change {
-        /// A link to the commit, pr, issue, ...
-        #[clap(conflicts_with = "message")]
-        link: Option<String>,
-
-        /// A manual message you want to add
-        #[clap(short, long, conflicts_with = "link")]
-        message: Option<String>,
+        #[clap(flatten)]
+        add: AddArgs,
 
         /// The section name to add the entry to
         #[clap(hide = true, default_value = "Changed")]
         name: String,
-
-        /// Whether or not to commit the changes
-        #[clap(short, long)]
-        commit: bool,
-
-        /// Whether you want to edit the (automated) message after it got fetched from GitHub
-        #[clap(short, long)]
-        edit: bool,
     },
 
     /// Add a new entry to the changelog in the "Deprecated" section
     Deprecate {
-        /// A link to the commit, pr, issue, ...
-        #[clap(conflicts_with = "message")]
-        link: Option<String>,
-
-        /// A manual message you want to add
-        #[clap(short, long, conflicts_with = "link")]
-        message: Option<String>,
+        #[clap(flatten)]
+        add: AddArgs,
 
         /// The section name to add the entry to
         #[clap(hide = true, default_value = "Deprecated")]
         name: String,
-
-        /// Whether or not to commit the changes
-        #[clap(short, long)]
-        commit: bool,
-
-        /// Whether you want to edit the (automated) message after it got fetched from GitHub
-        #[clap(short, long)]
-        edit: bool,
     },
 
     /// Add a new entry to the changelog in the "Removed" section
     Remove {
-        /// A link to the commit, pr, issue, ...
-        #[clap(conflicts_with = "message")]
-        link: Option<String>,
-
-        /// A manual message you want to add
-        #[clap(short, long, conflicts_with = "link")]
-        message: Option<String>,
+        #[clap(flatten)]
+        add: AddArgs,
 
         /// The section name to add the entry to
         #[clap(hide = true, default_value = "Removed")]
         name: String,
-
-        /// Whether or not to commit the changes
-        #[clap(short, long)]
-        commit: bool,
-
-        /// Whether you want to edit the (automated) message after it got fetched from GitHub
-        #[clap(short, long)]
-        edit: bool,
     },
 
     /// Release a new version
     Release {
-        /// The version of the release, which can be one of: "major", "minor", "patch", "infer"
-        /// (infer from current package.json version) or an explicit version number like "1.2.3"
+        /// The version of the release, which can be one of: "major", "minor", "patch", "final"
+        /// (finalize a pre-release version like "1.2.0-rc.2" to "1.2.0"), "infer" (infer from the
+        /// current version, see the `version_source` config) or an explicit version number like
+        /// "1.2.3"
         #[clap(default_value = "infer")]
         version: String,
 
+        /// Only accept a keyword or a full semver version (MAJOR.MINOR.PATCH with an optional
+        /// pre-release and build metadata), e.g.: to reject a typo like "1.2" or a calendar version
+        #[clap(long)]
+        verify_semver: bool,
+
         /// Whether or not to run `npm version <version>` (which in turn updates package.json and
-        /// creates a new git tag)
+        /// creates a new git tag), or the `version_command` config
         #[clap(long)]
         with_npm: bool,
+
+        /// Add an entry for every commit since the latest git tag before releasing, the section is
+        /// based on the conventional commit type of each commit
+        #[clap(long)]
+        from_commits: bool,
+
+        /// The git revision to start from when using `--from-commits` (exclusive) [default: the
+        /// latest tag]
+        #[clap(long, requires = "from-commits")]
+        from: Option<String>,
+
+        /// The git revision to end at when using `--from-commits` (inclusive) [default: HEAD]
+        #[clap(long, requires = "from-commits")]
+        to: Option<String>,
+
+        /// The order to add the entries of the commits in when using `--from-commits`, one of
+        /// "log" (the git history) or "date" (e.g.: the date a pull request was merged).
+        /// Overrides the `commit_order` config.
+        #[clap(long, value_name = "ORDER", requires = "from-commits")]
+        sort_by: Option<CommitOrder>,
+
+        /// Don't add the release date to the heading of the released version
+        #[clap(long)]
+        no_date: bool,
+
+        /// Move the unreleased entries into the latest release and update its date, instead of
+        /// creating a new version. Refuses when the latest release was already tagged or pushed.
+        #[clap(long, conflicts_with = "with-npm")]
+        amend: bool,
+
+        /// Amend the latest release, even when it was already tagged or pushed
+        #[clap(long, requires = "amend")]
+        force: bool,
+
+        /// Scaffold the new unreleased section with the placeholder entry of every section
+        #[clap(long)]
+        scaffold: bool,
+
+        /// Merge the entries of the released version that reference the same pull request,
+        /// issue, ... into a single entry
+        #[clap(long)]
+        dedupe_references: bool,
+
+        /// Write the notes of the released version to this file, e.g.: "RELEASE_NOTES.md" for a
+        /// GitHub release. The notes of multiple packages are combined.
+        #[clap(long)]
+        notes_out: Option<String>,
+
+        /// Create an annotated tag with this message when using `--with-npm`, with `{version}` and
+        /// `{notes}` placeholders, e.g.: "Release {version}\n\n{notes}", or "{notes}" for the
+        /// bare notes
+        #[clap(long, value_name = "TEMPLATE", requires = "with-npm")]
+        tag_message_template: Option<String>,
+
+        /// The message of the commit when using `--with-npm`, with `{version}` and `{package}`
+        /// placeholders, e.g.: "chore(release): {version}". Overrides the `commit_template`
+        /// config.
+        #[clap(long, value_name = "TEMPLATE", requires = "with-npm")]
+        commit_template: Option<String>,
+
+        /// Add a "Bump <package> to <version>" entry to the changelogs of the packages that depend
+        /// on a released package in a monorepo, based on their package.json and the `dependents`
+        /// config
+        #[clap(long, conflicts_with = "amend")]
+        propagate: bool,
+
+        /// Release even when the unreleased section has no entries, when the `skip_empty_release`
+        /// config is set
+        #[clap(long)]
+        allow_empty: bool,
+
+        /// Release an empty version when the changelog has no unreleased section, e.g.: after it
+        /// was fully released, instead of failing
+        #[clap(long, conflicts_with = "amend")]
+        create_empty: bool,
+
+        /// Only create the git tag of a version that is already in the changelog, e.g.: when the
+        /// changelog was updated and committed manually. Without a version, the latest release is
+        /// tagged.
+        #[clap(
+            long,
+            conflicts_with_all = &["with-npm", "from-commits", "amend", "scaffold", "dedupe-references", "propagate", "no-date"]
+        )]
+        tag_only: bool,
+
+        /// Print a single line per released version for scripts, e.g.: "released 1.2.3 on
+        /// 2024-01-15 with 7 entries" (or "released <package>@1.2.3 ..." in a monorepo). The human
+        /// output goes to stderr instead.
+        #[clap(long, conflicts_with_all = &["amend", "tag-only"])]
+        porcelain: bool,
+    },
+
+    /// Move the entries of the latest release back into the unreleased section and remove its
+    /// heading and reference, the inverse of `release`. Refuses when the version was already
+    /// tagged or pushed.
+    Unrelease {
+        /// The version to unrelease, which has to be the latest release
+        version: String,
+
+        /// Unrelease the version, even when it was already tagged or pushed
+        #[clap(long)]
+        force: bool,
     },
 
-    /// Get the release notes of a specific version (or unreleased)
+    /// Print the version that `release` would produce, without releasing anything, e.g.: to set a
+    /// build variable in CI
+    NextVersion {
+        /// The bump, one of: "major", "minor", "patch", "final", "infer" or an explicit version
+        /// [default: the bump that the unreleased entries call for]
+        bump: Option<String>,
+    },
+
+    /// Get the release notes of a specific version (or unreleased). The filename can be a glob to
+    /// get the notes of every matching changelog, e.g.: `--filename 'packages/*/CHANGELOG.md'`
     Notes {
         /// The version you want to get the notes from. Should be a valid semver version or one of
         /// "unreleased" or "latest".
         version: Option<String>,
+
+        /// Render the notes as plain text, without the urls of links
+        #[clap(long)]
+        strip_links: bool,
+
+        /// Group the entries by "section" (like the changelog), or by their conventional commit
+        /// "type", e.g.: "feat: ..." entries under "### Features"
+        #[clap(long, default_value = "section")]
+        group_by: GroupBy,
+
+        /// Render every configured section, with a "_None_" entry in the sections without
+        /// entries, e.g.: for release pages that always show the same sections
+        #[clap(long)]
+        include_empty_sections: bool,
+
+        /// Only print the amount of entries, in total and per section
+        #[clap(long, group = "counts")]
+        count: bool,
+
+        /// Only print the sections with their amount of entries on a single line, e.g.:
+        /// "Added (3), Fixed (2)"
+        #[clap(long, group = "counts")]
+        summary_only: bool,
+
+        /// Print the amount of entries as JSON, when using `--count` or `--summary-only`
+        #[clap(long, requires = "counts")]
+        json: bool,
+
+        /// Copy the notes to the clipboard, e.g.: to paste them in a GitHub release
+        #[clap(long, conflicts_with = "counts")]
+        copy: bool,
+
+        /// Get the notes of the version before the given version, e.g.: the version you are
+        /// upgrading from
+        #[clap(long)]
+        previous: bool,
+
+        /// Get the combined notes of the versions released on or after this date, e.g.:
+        /// "2024-01-01"
+        #[clap(long, conflicts_with_all = &["version", "previous", "counts"])]
+        after: Option<NaiveDate>,
+
+        /// Get the combined notes of the versions released before this date, e.g.: "2024-02-01"
+        #[clap(long, conflicts_with_all = &["version", "previous", "counts"])]
+        before: Option<NaiveDate>,
+
+        /// Print a stable format for scripts, a "<section><TAB><entry>" line per entry (or
+        /// "<package><TAB><section><TAB><entry>" in a monorepo)
+        #[clap(long, conflicts_with_all = &["counts", "copy", "after", "before"])]
+        porcelain: bool,
+
+        /// Get the notes of the unreleased section, annotated with the amount of commits since the
+        /// latest tag, e.g.: "12 commits since v1.2.0"
+        #[clap(
+            long,
+            conflicts_with_all = &["version", "previous", "counts", "after", "before", "porcelain"]
+        )]
+        since_tag: bool,
+
+        /// The format of the notes, one of "markdown" or "json" (the version with its date, link
+        /// and entries, like `export`)
+        #[clap(
+            long,
+            default_value = "markdown",
+            conflicts_with_all = &["counts", "after", "before", "porcelain"]
+        )]
+        format: NotesFormat,
+
+        /// Write the notes to this file instead of printing them, e.g.: "notes.json" for an
+        /// artifact in CI
+        #[clap(long, value_name = "FILE", conflicts_with_all = &["counts", "porcelain"])]
+        output_file: Option<String>,
+
+        /// Get the notes of the unreleased section (with a warning) when the given version isn't
+        /// released yet, e.g.: to get the notes of a version in CI before releasing it
+        #[clap(long, requires = "version")]
+        fallback_unreleased: bool,
+    },
+
+    /// Verify that new entries were added to the unreleased section compared to a base git
+    /// revision, e.g.: to require a changelog entry in every pull request
+    Changed {
+        /// The git revision to compare against
+        #[clap(long, default_value = "origin/main")]
+        base: String,
+    },
+
+    /// Show the versions and entries that were added or removed in the changelog file between two
+    /// git revisions, e.g.: to review the changelog edits of a pull request
+    Diff {
+        /// The git revisions to compare, e.g.: "v1.0.0..HEAD". When only one revision is passed,
+        /// it is compared against the working tree.
+        #[clap(long = "git", name = "RANGE")]
+        range: String,
+
+        /// Render the changes as a markdown list with "Added" and "Removed" sections
+        #[clap(long)]
+        markdown: bool,
+    },
+
+    /// Merge another changelog file into this one, e.g.: after merging two long-lived branches
+    Merge {
+        /// The path to the other changelog file
+        file: String,
+
+        /// Print the merged changelog instead of writing it
+        #[clap(long)]
+        dry_run: bool,
+    },
+
+    /// Move older versions into a file per version, and link to them from the changelog
+    Split {
+        /// The amount of recent versions to keep in the changelog
+        #[clap(long, default_value = "5")]
+        keep_latest: usize,
+
+        /// The directory of the files of the older versions, relative to the changelog
+        #[clap(long, default_value = "changelogs")]
+        dir: String,
+    },
+
+    /// Move the versions that were split into separate files back into the changelog
+    MergeSplit,
+
+    /// Merge the three versions of a changelog file, to be used as a git merge driver. The
+    /// entries of the unreleased sections are combined, so that parallel branches don't conflict.
+    Resolve {
+        /// The common ancestor's version of the file (%O)
+        base: String,
+
+        /// Our version of the file (%A), the result is written to this file
+        ours: String,
+
+        /// Their version of the file (%B)
+        theirs: String,
     },
 
     /// Get a list of all versions
@@ -201,7 +512,175 @@ enum Commands {
         /// Shorthand for "--amount all"
         #[clap(long, conflicts_with = "amount")]
         all: bool,
+
+        /// List the unreleased section at the top, with its amount of entries
+        #[clap(long)]
+        include_unreleased: bool,
+
+        /// List the versions from the oldest to the newest. The amount is still taken from the
+        /// newest versions, unless `--oldest` is used.
+        #[clap(long)]
+        reverse: bool,
+
+        /// Take the amount of versions from the oldest instead of the newest versions
+        #[clap(long)]
+        oldest: bool,
+
+        /// Print a stable format for scripts, a "<version><TAB><date>" line per version (or
+        /// "<package><TAB><version><TAB><date>" in a monorepo)
+        #[clap(long)]
+        porcelain: bool,
+
+        /// Truncate the rows that are wider than this amount of characters [default: the width of
+        /// the terminal]
+        #[clap(long, conflicts_with = "porcelain")]
+        max_width: Option<usize>,
+
+        /// Only list the versions with an entry that contains this text, ignoring the case, e.g.:
+        /// "login" for the releases that mentioned the login
+        #[clap(long, value_name = "TEXT")]
+        contains: Option<String>,
+    },
+
+    /// Find the entries that match a query in all versions, including the unreleased section
+    Search {
+        /// The text to search for, or a regular expression when using `--regex`
+        query: String,
+
+        /// Treat the query as a regular expression, e.g.: "memory (leak|usage)"
+        #[clap(long)]
+        regex: bool,
+
+        /// Ignore the case when matching the query
+        #[clap(short, long)]
+        ignore_case: bool,
+
+        /// Also show this amount of entries before and after every match, within the same version,
+        /// like `grep --context`
+        #[clap(short = 'C', long, value_name = "N", default_value = "0")]
+        context: usize,
+    },
+
+    /// Export the whole changelog in a structured format, e.g.: for integrations
+    Export {
+        /// The format of the export, currently only "json"
+        #[clap(long, default_value = "json")]
+        format: ExportFormat,
+
+        /// Write the export to this file instead of printing it
+        #[clap(long, value_name = "FILE")]
+        output_file: Option<String>,
+    },
+
+    /// Check the structure of the changelog, e.g.: in CI. Exits with a non-zero exit code when an
+    /// error is found.
+    Validate {
+        /// Also exit with a non-zero exit code when a warning is found, e.g.: an unknown section
+        #[clap(long)]
+        fail_on_warning: bool,
+
+        /// Put the version headings at "##" and the section headings at "###" before validating,
+        /// e.g.: in a hand-edited changelog
+        #[clap(long)]
+        fix: bool,
     },
+
+    /// Rewrite a changelog of another tool into the structure of this tool
+    #[clap(alias = "import")]
+    Normalize {
+        /// The tool that generated the changelog, one of "auto", "git-cliff" or "github"
+        #[clap(long, default_value = "auto", conflicts_with = "whitespace")]
+        from: Tool,
+
+        /// Only normalize the whitespace of the changelog, e.g.: of a hand-edited changelog. Trims
+        /// trailing whitespace, collapses multiple blank lines into one and surrounds headings
+        /// with exactly one blank line, without changing the contents
+        #[clap(long)]
+        whitespace: bool,
+
+        /// Don't write the changelog, but exit with a non-zero exit code when its whitespace isn't
+        /// normalized, e.g.: in CI
+        #[clap(long, requires = "whitespace")]
+        check: bool,
+    },
+}
+
+/// The arguments of the commands that add an entry, e.g.: `add` and `fix`
+#[derive(Args, Debug)]
+struct AddArgs {
+    /// A link to the commit, pr, issue, ...
+    #[clap(conflicts_with = "message")]
+    link: Option<String>,
+
+    /// A manual message you want to add
+    #[clap(short, long, conflicts_with = "link")]
+    message: Option<String>,
+
+    /// Read the message from a file (or `-` for stdin), the lines of the file are kept as a
+    /// multi-line entry
+    #[clap(long, conflicts_with_all = &["link", "message"])]
+    message_file: Option<String>,
+
+    /// Compose the message in your `$EDITOR`, the lines are kept as a multi-line entry
+    #[clap(long, conflicts_with_all = &["link", "message", "message-file"])]
+    editor: bool,
+
+    /// Whether or not to commit the changes
+    #[clap(short, long)]
+    commit: bool,
+
+    /// Whether you want to edit the (automated) message after it got fetched from GitHub
+    #[clap(short, long)]
+    edit: bool,
+
+    /// Add the link as-is instead of fetching its title from GitHub, e.g.: when offline
+    #[clap(long, alias = "no-link-resolution", requires = "link")]
+    literal: bool,
+
+    /// Credit the author of a pull request in the entry, e.g.: "Add tables, thanks @octocat"
+    #[clap(long, requires = "link", conflicts_with = "literal")]
+    thanks: bool,
+
+    /// Add the entry to this released version instead of the unreleased section, e.g.: to
+    /// backfill a change that was forgotten
+    #[clap(long, conflicts_with = "editor")]
+    version: Option<String>,
+
+    /// Add the entry under a sub-heading of the section, e.g.: "API" for a "#### API" heading
+    /// below "### Added". The sub-heading is created when it doesn't exist yet.
+    #[clap(long, conflicts_with = "version")]
+    under: Option<String>,
+
+    /// Resolve and validate the entry, and print it without adding it to the changelog, e.g.:
+    /// in a pre-commit hook
+    #[clap(long, conflicts_with_all = &["commit", "edit"])]
+    check: bool,
+
+    /// Expand the `${VAR}` (or `${VAR:-fallback}`) references to environment variables in the
+    /// message, e.g.: to compose the message in CI
+    #[clap(long, conflicts_with = "link")]
+    expand_env: bool,
+
+    /// Add an entry for every commit of this pull request, that references the pull request
+    #[clap(
+        long,
+        value_name = "URL",
+        conflicts_with_all = &["link", "message", "message-file", "editor", "version", "check"]
+    )]
+    from_pr: Option<String>,
+
+    /// Add a single entry for the pull request when using `--from-pr`, instead of an entry per
+    /// commit
+    #[clap(long, requires = "from-pr")]
+    squash: bool,
+
+    /// Put this text before the entry, e.g.: "[api] " for a component tag
+    #[clap(long, value_name = "TEXT")]
+    entry_prefix: Option<String>,
+
+    /// Put this text after the entry
+    #[clap(long, value_name = "TEXT")]
+    entry_suffix: Option<String>,
 }
 
 #[tokio::main]
@@ -210,9 +689,62 @@ async fn main() -> Result<()> {
 
     let args = Cli::parse();
 
+    init_logging(args.log_level);
+
+    output::set_ascii(args.ascii);
+    if args.no_color {
+        colored::control::set_override(false);
+    }
+
     // Resolve the current working directory
     let pwd = fs::canonicalize(&args.pwd)?;
 
+    // Verify the optional pin of the changelog version
+    check_version_pin(&pwd, args.strict_version)?;
+
+    // Resolve the optional configuration
+    let mut config = Config::from_directory(&pwd)?;
+    if let Some(tag_prefix) = &args.tag_prefix {
+        config.tag_prefix = tag_prefix.clone();
+    }
+    if let Some(link_format) = args.link_format {
+        config.link_format = link_format;
+    }
+    if let Some(section_case) = args.section_case {
+        config.section_case = section_case;
+    }
+    if args.strip_emoji {
+        config.strip_emoji = true;
+    }
+    if args.escape_mentions {
+        config.escape_mentions = true;
+    }
+    if let Commands::Add { add, .. }
+    | Commands::Fix { add, .. }
+    | Commands::Change { add, .. }
+    | Commands::Remove { add, .. }
+    | Commands::Deprecate { add, .. } = &args.command
+    {
+        config.entry_prefix = add.entry_prefix.clone().unwrap_or_default();
+        config.entry_suffix = add.entry_suffix.clone().unwrap_or_default();
+    }
+    if let Commands::Release {
+        sort_by: Some(order),
+        ..
+    } = &args.command
+    {
+        config.commit_order = *order;
+    }
+    if let Some(path) = &args.author_map {
+        let authors: HashMap<String, String> = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| eyre!("Invalid author map {}: {}", path, e))?;
+        config.author_map.extend(authors);
+    }
+    if let Some(command) = &config.token_command {
+        graphql::set_token_command(command, &pwd);
+    }
+    graphql::set_github_hosts(&config.github_hosts);
+
     // Resolve the package.json manifest file
     let root_package = PackageJSON::from_directory(&pwd)?;
 
@@ -255,14 +787,113 @@ async fn main() -> Result<()> {
         None
     };
 
+    // Catch merge mistakes early, except for commands that create or rewrite the whole changelog
+    if !matches!(
+        &args.command,
+        Commands::Init { .. } | Commands::Normalize { .. }
+    ) {
+        let dirs = match &scopes {
+            Some(scopes) => scopes
+                .iter()
+                .map(|scope| scope.pwd().to_path_buf())
+                .collect(),
+            None => vec![pwd.clone()],
+        };
+
+        for dir in dirs {
+            check_version_headings(&dir.join(&args.filename), args.strict)?;
+        }
+    }
+
+    // The contents of the changelogs before running the command, to summarize what it changed
+    let snapshots = match args.preview_diff {
+        true => changelog_paths(&pwd, &args.filename, &root_package)
+            .into_iter()
+            .map(|path| {
+                let contents = fs::read_to_string(&path).ok();
+                (path, contents)
+            })
+            .collect(),
+        false => vec![],
+    };
+
+    run(args, pwd, config.clone(), root_package, scopes)?;
+
+    for (path, contents) in snapshots {
+        let written = fs::read_to_string(&path).ok();
+        if written.is_none() || written == contents {
+            continue;
+        }
+
+        let changelog = Changelog::from_file(&path)?.with_config(&config);
+        let changes = changelog.changes_since(contents.as_deref())?;
+
+        output(match changes.is_empty() {
+            true => format!(
+                "No changes to the entries of {}",
+                changelog.relative_path()?
+            ),
+            false => format!(
+                "Changes to {}:\n{}",
+                changelog.relative_path()?,
+                changes.join("\n")
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Run the command on the resolved directory, configuration and packages
+fn run(
+    args: Cli,
+    pwd: PathBuf,
+    config: Config,
+    root_package: PackageJSON,
+    scopes: Option<Vec<PackageJSON>>,
+) -> Result<()> {
     match &args.command {
-        Commands::Init => {
+        Commands::Init {
+            ensure,
+            force,
+            yes,
+            no_backup,
+        } => {
+            let options = InitOptions {
+                ensure: *ensure,
+                force: *force,
+                no_backup: *no_backup,
+            };
+
+            // Only overwrite an existing changelog when confirmed
+            let init = |changelog: &mut Changelog| -> Result<String> {
+                if *force && !*yes && changelog.exists() {
+                    let confirmed = Confirm::new()
+                        .with_prompt(format!(
+                            "Overwrite the existing changelog at {}?",
+                            changelog.relative_path()?
+                        ))
+                        .default(false)
+                        .interact()?;
+
+                    if !confirmed {
+                        return Ok(format!(
+                            "Kept the existing changelog at: {}",
+                            changelog.relative_path()?.white().dimmed()
+                        ));
+                    }
+                }
+
+                Ok(changelog.init(&options)?)
+            };
+
             match scopes {
                 Some(scopes) => {
                     let mut messages: Vec<_> = vec![];
                     for scope in scopes {
-                        let mut changelog = Changelog::new(scope.pwd(), &args.filename)?;
-                        messages.push(changelog.init()?);
+                        let mut changelog =
+                            Changelog::new(scope.pwd(), &args.filename)?.with_config(&config);
+                        messages.push(init(&mut changelog)?);
                     }
 
                     output(
@@ -274,135 +905,196 @@ async fn main() -> Result<()> {
                     )
                 }
                 None => {
-                    let mut changelog = Changelog::new(&pwd, &args.filename)?;
-                    output(changelog.init()?);
+                    let mut changelog = Changelog::new(&pwd, &args.filename)?.with_config(&config);
+                    output(init(&mut changelog)?);
                 }
             }
 
             Ok(())
         }
-        Commands::Add {
-            link,
-            name,
-            message,
-            commit,
-            edit,
-        }
-        | Commands::Fix {
-            link,
-            name,
-            message,
-            commit,
-            edit,
-        }
-        | Commands::Change {
-            link,
-            name,
-            message,
-            commit,
-            edit,
-        }
-        | Commands::Remove {
-            link,
-            name,
-            message,
-            commit,
-            edit,
-        }
-        | Commands::Deprecate {
-            link,
-            name,
-            message,
-            commit,
-            edit,
-        } => {
+        Commands::Add { add, name }
+        | Commands::Fix { add, name }
+        | Commands::Change { add, name }
+        | Commands::Remove { add, name }
+        | Commands::Deprecate { add, name } => {
+            let AddArgs {
+                link,
+                message,
+                message_file,
+                editor,
+                commit,
+                edit,
+                literal,
+                thanks,
+                version,
+                under,
+                check,
+                expand_env,
+                from_pr,
+                squash,
+                ..
+            } = add;
+            let composed_message = match (message_file, editor) {
+                (Some(path), _) => Some(read_message_file(path)?),
+                (None, true) => Some(compose_message(name)?),
+                (None, false) => None,
+            };
+            let message = composed_message.as_ref().or(message.as_ref());
+            let expanded_message = match expand_env {
+                true => message
+                    .map(|message| shell::expand_env(message))
+                    .transpose()?,
+                false => None,
+            };
+            let message = expanded_message.as_ref().or(message);
+
+            // The entry of a message or link, the editor is only used for the unreleased section
+            let entry = || -> Result<String> {
+                Ok(match (message, link) {
+                    (Some(message), _) => message.to_string(),
+                    (None, Some(link)) => match literal {
+                        true => literal_reference(link, config.link_format),
+                        false => resolve_link(link, name, *thanks, &config, &pwd)?,
+                    },
+                    (None, None) => missing_input(&args.command, args.error_format),
+                })
+            };
+
+            let dirs = match &scopes {
+                Some(scopes) => scopes
+                    .iter()
+                    .map(|scope| scope.pwd().to_path_buf())
+                    .collect(),
+                None => vec![pwd.clone()],
+            };
+
+            // The entries of a pull request, one for every commit or a single one with `--squash`
+            let pr_entries = match (from_pr, squash) {
+                (Some(url), true) => Some(vec![resolve_link(url, name, false, &config, &pwd)?]),
+                (Some(url), false) => Some(pull_request_entries(url, &config)?),
+                (None, _) => None,
+            };
+
+            // Add the entries to the unreleased section of a changelog, they are returned to
+            // highlight them in the output
+            let add_entries =
+                |changelog: &mut Changelog, package: Option<&PackageJSON>| -> Result<Vec<String>> {
+                    let entries = if let Some(entries) = &pr_entries {
+                        entries.clone()
+                    } else if let Some(message) = message {
+                        vec![message.to_string()]
+                    } else if let Some(link) = link {
+                        vec![match literal {
+                            true => literal_reference(link, config.link_format),
+                            false => resolve_link(link, name, *thanks, &config, &pwd)?,
+                        }]
+                    } else {
+                        let preface = &format!(
+                            include_str!("./fixtures/add_entry.txt"),
+                            name.to_lowercase()
+                        );
+
+                        let lines: Vec<_> = rich_edit(Some(preface))
+                            .unwrap_or_default()
+                            .lines()
+                            .map(|line| line.trim())
+                            .filter(|line| !line.is_empty())
+                            .filter(|line| !line.starts_with('#'))
+                            .map(|line| line.to_string())
+                            .collect();
+
+                        if lines.is_empty() {
+                            missing_input(&args.command, args.error_format)
+                        }
+                        lines
+                    };
+
+                    for entry in &entries {
+                        changelog.add_list_item_to_section_under(
+                            name,
+                            under.as_deref(),
+                            entry,
+                            *edit,
+                            package,
+                        );
+                    }
+
+                    Ok(entries)
+                };
+
+            // Add the entry without persisting it, so that every step is validated
+            if *check {
+                let data = entry()?;
+
+                for dir in &dirs {
+                    let mut changelog = Changelog::new(dir, &args.filename)?.with_config(&config);
+                    match version {
+                        Some(version) => {
+                            changelog.add_list_item_to_released_version(version, name, &data)?
+                        }
+                        None => changelog.add_list_item_to_section_under(
+                            name,
+                            under.as_deref(),
+                            &data,
+                            false,
+                            None,
+                        ),
+                    }
+                }
+
+                println!("{}", data);
+                return Ok(());
+            }
+
+            // Backfill an entry in a released version
+            if let Some(version) = version {
+                let data = entry()?;
+
+                for dir in &dirs {
+                    let mut changelog = Changelog::new(dir, &args.filename)?.with_config(&config);
+                    changelog.add_list_item_to_released_version(version, name, &data)?;
+                    changelog.persist()?;
+                }
+
+                output_warning(format!(
+                    "Edited the notes of the released version {}, make sure it wasn't published yet",
+                    version.blue().bold()
+                ));
+                output(format!(
+                    "Added a new entry to the {} section of {}: {}",
+                    name.blue().bold(),
+                    version.blue().bold(),
+                    highlight_entry(&data, !args.no_bold_confirmation)
+                ));
+
+                if *commit {
+                    let g = Git::new(Some(&pwd))?;
+                    for dir in dirs {
+                        g.add(dir.join(&args.filename).to_str().unwrap())?;
+                    }
+                    g.commit("update changelog")?;
+                }
+
+                return Ok(());
+            }
+
             match &scopes {
                 Some(scopes) => {
                     let mut output_messages: HashMap<PathBuf, Vec<String>> = HashMap::default();
 
                     for package in scopes {
-                        let mut changelog = Changelog::new(package.pwd(), &args.filename)?;
-
-                        let messages = if let Some(message) = message {
-                            changelog.add_list_item_to_section(
-                                name,
-                                &message.to_string(),
-                                *edit,
-                                Some(package),
-                            );
-                            vec![message.to_string()]
-                        } else if let Some(link) = link {
-                            let data: GitHubInfo = link.parse().unwrap();
-                            changelog.add_list_item_to_section(
-                                name,
-                                &data.to_string(),
-                                *edit,
-                                Some(package),
-                            );
-                            vec![data.to_string()]
-                        } else {
-                            let preface = &format!(
-                                include_str!("./fixtures/add_entry.txt"),
-                                name.to_lowercase(),
-                            );
-
-                            let data = match rich_edit(Some(preface)) {
-                                Some(data) => {
-                                    let data = data.trim();
-                                    let data: Vec<_> = data
-                                        .lines()
-                                        .map(|line| line.trim())
-                                        .filter(|line| !line.is_empty())
-                                        .filter(|line| !line.starts_with('#'))
-                                        .map(|line| line.to_string())
-                                        .collect();
-
-                                    for line in &data {
-                                        changelog.add_list_item_to_section(
-                                            name,
-                                            line,
-                                            *edit,
-                                            Some(package),
-                                        );
-                                    }
-
-                                    if data.is_empty() {
-                                        None
-                                    } else {
-                                        Some(data)
-                                    }
-                                }
-                                None => None,
-                            };
-
-                            data.unwrap_or_else(|| {
-                                output(format!(
-                                    "No {}, {} or {} provided, run `{}` for more info",
-                                    "<LINK>".blue().bold(),
-                                    "<COMMIT HASH>".blue().bold(),
-                                    "--message".blue().bold(),
-                                    format!(
-                                        "changelog {} --help",
-                                        match &args.command {
-                                            Commands::Add { .. } => "add",
-                                            Commands::Fix { .. } => "fix",
-                                            Commands::Change { .. } => "change",
-                                            Commands::Remove { .. } => "remove",
-                                            Commands::Deprecate { .. } => "deprecate",
-                                            _ => unreachable!(),
-                                        }
-                                    )
-                                    .blue()
-                                    .bold()
-                                ));
+                        let mut changelog =
+                            Changelog::new(package.pwd(), &args.filename)?.with_config(&config);
 
-                                std::process::exit(1);
-                            })
-                        };
+                        let messages = add_entries(&mut changelog, Some(package))?;
 
                         output_messages.insert(package.pwd().to_path_buf(), messages);
 
                         changelog.persist()?;
+
+                        if let Some(reminder) = changelog.release_reminder(Some(package)) {
+                            output_warning(format!("{} ({})", reminder, package.name()));
+                        }
                     }
 
                     if *commit {
@@ -436,19 +1128,17 @@ async fn main() -> Result<()> {
                         output_indented(format!("{}", package.name().white().dimmed()));
                         eprintln!();
                         let messages = output_messages.get(&package.pwd().to_path_buf()).unwrap();
-                        let changelog = Changelog::new(package.pwd(), &args.filename)?;
+                        let changelog =
+                            Changelog::new(package.pwd(), &args.filename)?.with_config(&config);
 
                         if let Some(node) =
                             changelog.get_contents_of_section_scope(None, Some(package))
                         {
-                            let mut text = node.to_string();
-
-                            for message in messages {
-                                text = text.replace(
-                                    &format!("- {}", message),
-                                    &format!("- {}", message.green().bold()),
-                                );
-                            }
+                            let text = highlight_entries(
+                                node.to_string(),
+                                messages,
+                                !args.no_bold_confirmation,
+                            );
 
                             output_indented(text);
                             eprintln!()
@@ -459,69 +1149,9 @@ async fn main() -> Result<()> {
                     }
                 }
                 None => {
-                    let mut changelog = Changelog::new(&pwd, &args.filename)?;
-
-                    let messages = if let Some(message) = message {
-                        changelog.add_list_item_to_section(name, &message.to_string(), *edit, None);
-                        vec![message.to_string()]
-                    } else if let Some(link) = link {
-                        let data: GitHubInfo = link.parse().unwrap();
-                        changelog.add_list_item_to_section(name, &data.to_string(), *edit, None);
-                        vec![data.to_string()]
-                    } else {
-                        let preface = &format!(
-                            include_str!("./fixtures/add_entry.txt"),
-                            name.to_lowercase()
-                        );
-
-                        let data = match rich_edit(Some(preface)) {
-                            Some(data) => {
-                                let data = data.trim();
-                                let data: Vec<_> = data
-                                    .lines()
-                                    .map(|line| line.trim())
-                                    .filter(|line| !line.is_empty())
-                                    .filter(|line| !line.starts_with('#'))
-                                    .map(|line| line.to_string())
-                                    .collect();
-
-                                for line in &data {
-                                    changelog.add_list_item_to_section(name, line, *edit, None);
-                                }
-
-                                if data.is_empty() {
-                                    None
-                                } else {
-                                    Some(data)
-                                }
-                            }
-                            None => None,
-                        };
-
-                        data.unwrap_or_else(|| {
-                            output(format!(
-                                "No {}, {} or {} provided, run `{}` for more info",
-                                "<LINK>".blue().bold(),
-                                "<COMMIT HASH>".blue().bold(),
-                                "--message".blue().bold(),
-                                format!(
-                                    "changelog {} --help",
-                                    match &args.command {
-                                        Commands::Add { .. } => "add",
-                                        Commands::Fix { .. } => "fix",
-                                        Commands::Change { .. } => "change",
-                                        Commands::Remove { .. } => "remove",
-                                        Commands::Deprecate { .. } => "deprecate",
-                                        _ => unreachable!(),
-                                    }
-                                )
-                                .blue()
-                                .bold()
-                            ));
+                    let mut changelog = Changelog::new(&pwd, &args.filename)?.with_config(&config);
 
-                            std::process::exit(1);
-                        })
-                    };
+                    let messages = add_entries(&mut changelog, None)?;
 
                     output(format!(
                         "Added a new entry to the {} section:",
@@ -529,14 +1159,11 @@ async fn main() -> Result<()> {
                     ));
 
                     if let Some(node) = changelog.get_contents_of_section(&None) {
-                        let mut text = node.to_string();
-
-                        for message in messages {
-                            text = text.replace(
-                                &format!("- {}", message),
-                                &format!("- {}", message.green().bold()),
-                            );
-                        }
+                        let text = highlight_entries(
+                            node.to_string(),
+                            &messages,
+                            !args.no_bold_confirmation,
+                        );
 
                         output_indented(text);
                         eprintln!()
@@ -544,6 +1171,10 @@ async fn main() -> Result<()> {
 
                     changelog.persist()?;
 
+                    if let Some(reminder) = changelog.release_reminder(None) {
+                        output_warning(reminder);
+                    }
+
                     if *commit {
                         // Commit the CHANGELOG.md file
                         Git::new(Some(&pwd))?
@@ -555,100 +1186,676 @@ async fn main() -> Result<()> {
 
             Ok(())
         }
-        Commands::Notes { version } => {
-            match scopes {
-                Some(scopes) => {
-                    for package in scopes {
-                        let message = Changelog::new(package.pwd(), &args.filename)?
-                            .notes(version.as_ref())
-                            .unwrap_or_else(|err| err.to_string().red().to_string());
+        Commands::Notes {
+            version,
+            strip_links,
+            group_by,
+            include_empty_sections,
+            count,
+            summary_only,
+            json,
+            copy,
+            previous,
+            after,
+            before,
+            porcelain,
+            since_tag,
+            format,
+            output_file,
+            fallback_unreleased,
+        } => {
+            let version = &match since_tag {
+                true => Some("unreleased".to_string()),
+                false => version.clone(),
+            };
+            let options = NotesOptions {
+                strip_links: *strip_links,
+                group_by: *group_by,
+                include_empty_sections: *include_empty_sections,
+            };
 
-                        output_title(
-                            match version {
-                                Some(version) => format!(
-                                    "Notes for {}, {}",
-                                    package.name().white().dimmed(),
-                                    version.to_lowercase().blue()
-                                ),
-                                None => format!(
+            // The version to get the notes of, optionally the one before the given version
+            let notes_version =
+                |dir: &Path, filename: &str, package: Option<&PackageJSON>| -> Result<_> {
+                    let version = match previous {
+                        true => Some(
+                            Changelog::new(dir, filename)?
+                                .previous_version(version.as_ref(), package)?,
+                        ),
+                        false => version.clone(),
+                    };
+
+                    // Fall back to the unreleased notes when the version (or its tag, e.g.:
+                    // "v1.2.3") isn't released yet, but not for older versions
+                    match version {
+                        Some(version)
+                            if *fallback_unreleased
+                                && !version.eq_ignore_ascii_case("unreleased") =>
+                        {
+                            let changelog = Changelog::new(dir, filename)?.with_config(&config);
+
+                            match changelog.resolve_version(&version) {
+                                Some(version) => Ok(Some(version)),
+                                None if changelog.is_upcoming_version(&version) => {
+                                    output_warning(format!(
+                                        "Version {} isn't released yet, showing the {} notes",
+                                        version.blue().bold(),
+                                        "unreleased".blue().bold()
+                                    ));
+                                    Ok(Some("unreleased".to_string()))
+                                }
+                                None => Ok(Some(version)),
+                            }
+                        }
+                        version => Ok(version),
+                    }
+                };
+
+            if *porcelain {
+                let entries = |dir: &Path, package: Option<&PackageJSON>| -> Result<_> {
+                    let version = notes_version(dir, &args.filename, package)?;
+                    let changelog = Changelog::for_notes(dir, &args.filename, version.as_ref())?
+                        .with_config(&config);
+
+                    changelog.entries(version.as_ref(), None).ok_or_else(|| {
+                        eyre!(
+                            "Couldn't find notes for version: {}",
+                            version.as_deref().unwrap_or("<unknown>")
+                        )
+                    })
+                };
+                let text = |text: String| match strip_links {
+                    true => markdown::tokens::strip_links(&text),
+                    false => text,
+                };
+
+                let rows = match &scopes {
+                    Some(scopes) => scopes
+                        .iter()
+                        .map(|package| {
+                            Ok(entries(package.pwd(), Some(package))?
+                                .into_iter()
+                                .map(|(section, entry)| {
+                                    vec![package.name().to_string(), section, text(entry)]
+                                })
+                                .collect::<Vec<_>>())
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                        .concat(),
+                    None => entries(&pwd, None)?
+                        .into_iter()
+                        .map(|(section, entry)| vec![section, text(entry)])
+                        .collect(),
+                };
+
+                output_porcelain(rows);
+                return Ok(());
+            }
+
+            if *count || *summary_only {
+                let changelogs = match &scopes {
+                    Some(scopes) => scopes
+                        .iter()
+                        .map(|package| {
+                            let version =
+                                notes_version(package.pwd(), &args.filename, Some(package))?;
+                            let changelog = Changelog::for_notes(
+                                package.pwd(),
+                                &args.filename,
+                                version.as_ref(),
+                            )?;
+                            Ok((changelog, Some(package), version))
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                    None => {
+                        let version = notes_version(&pwd, &args.filename, None)?;
+                        vec![(
+                            Changelog::for_notes(&pwd, &args.filename, version.as_ref())?,
+                            None,
+                            version,
+                        )]
+                    }
+                };
+
+                for (changelog, package, version) in changelogs {
+                    let count = changelog.count(version.as_ref(), None);
+
+                    if *json && *summary_only {
+                        println!(
+                            "{}",
+                            serde_json::json!({
+                                "package": package.map(|package| package.name()),
+                                "version": version.as_deref().unwrap_or("latest"),
+                                "summary": count.map(|count| count.to_json()["sections"].clone()),
+                            })
+                        );
+                        continue;
+                    }
+
+                    if *json {
+                        println!(
+                            "{}",
+                            serde_json::json!({
+                                "package": package.map(|package| package.name()),
+                                "version": version.as_deref().unwrap_or("latest"),
+                                "count": count.map(|count| count.to_json()),
+                            })
+                        );
+                        continue;
+                    }
+
+                    let title = match package {
+                        Some(package) => format!(
+                            "Entries of {}, {}",
+                            package.name().white().dimmed(),
+                            version.as_deref().unwrap_or("latest").to_lowercase().blue()
+                        ),
+                        None => format!(
+                            "Entries of {}",
+                            version.as_deref().unwrap_or("latest").to_lowercase().blue()
+                        ),
+                    };
+
+                    output_title(
+                        title,
+                        match count {
+                            Some(count) if *summary_only => count.summary(),
+                            Some(count) => count.to_string(),
+                            None => format!(
+                                "Couldn't find notes for version: {}",
+                                version.as_deref().unwrap_or("<unknown>").blue().bold()
+                            ),
+                        },
+                    );
+                }
+
+                return Ok(());
+            }
+
+            if *format == NotesFormat::Json {
+                let notes_json = |dir: &Path,
+                                  filename: &str,
+                                  package: Option<&PackageJSON>,
+                                  label: Option<&str>|
+                 -> Result<String> {
+                    let version = notes_version(dir, filename, package)?;
+                    // The whole file, for the link references at the bottom
+                    let changelog = Changelog::new(dir, filename)?.with_config(&config);
+                    let mut json = changelog
+                        .notes_json(version.as_ref(), &options)
+                        .ok_or_else(|| {
+                            eyre!(
+                                "Couldn't find notes for version: {}",
+                                version.as_deref().unwrap_or("<unknown>")
+                            )
+                        })?;
+
+                    if let Some(label) = label {
+                        json["package"] = label.into();
+                    }
+
+                    Ok(json.to_string())
+                };
+
+                let lines = match &scopes {
+                    Some(scopes) => scopes
+                        .iter()
+                        .map(|package| {
+                            notes_json(
+                                package.pwd(),
+                                &args.filename,
+                                Some(package),
+                                Some(package.name()),
+                            )
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                    None if is_glob(&args.filename) => changelog_files(&pwd, &args.filename)?
+                        .iter()
+                        .map(|path| {
+                            let dir = path.parent().unwrap_or(&pwd);
+                            let filename = path.file_name().unwrap_or_default().to_string_lossy();
+                            let label = match PackageJSON::from_directory(dir) {
+                                Ok(package) => package.name().to_string(),
+                                Err(_) => {
+                                    dir.strip_prefix(&pwd).unwrap_or(dir).display().to_string()
+                                }
+                            };
+
+                            notes_json(dir, &filename, None, Some(&label))
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                    None => {
+                        let json = notes_json(&pwd, &args.filename, None, None)?;
+                        return print_or_write(&json, output_file.as_ref(), &pwd);
+                    }
+                };
+
+                // An array with the notes of every package
+                return print_or_write(
+                    &format!("[{}]", lines.join(",")),
+                    output_file.as_ref(),
+                    &pwd,
+                );
+            }
+
+            // A missing version is shown in place of its notes, but fails when writing a file
+            let notes_or_error = |notes: error::Result<String>| -> Result<String> {
+                match (notes, output_file) {
+                    (Ok(notes), _) => Ok(notes),
+                    (Err(e), None) => Ok(e.to_string().red().to_string()),
+                    (Err(e), Some(_)) => Err(e.into()),
+                }
+            };
+
+            let mut blocks = vec![];
+
+            // The combined notes of the versions that were released in a date range
+            if after.is_some() || before.is_some() {
+                let range = format!(
+                    "{} - {}",
+                    after.map_or("…".to_string(), |date| date.to_string()),
+                    before.map_or("…".to_string(), |date| date.to_string())
+                );
+
+                match scopes {
+                    Some(scopes) => {
+                        for package in scopes {
+                            let changelog =
+                                Changelog::new(package.pwd(), &args.filename)?.with_config(&config);
+
+                            blocks.push((
+                                format!(
                                     "Notes for {}, {}",
                                     package.name().white().dimmed(),
-                                    "latest".blue()
+                                    range.blue()
                                 ),
+                                changelog.notes_between(*after, *before, Some(&package), &options),
+                            ));
+                        }
+                    }
+                    None => {
+                        let changelog = Changelog::new(&pwd, &args.filename)?.with_config(&config);
+
+                        blocks.push((
+                            format!("Notes for {}", range.blue()),
+                            changelog.notes_between(*after, *before, None, &options),
+                        ));
+                    }
+                }
+            } else if is_glob(&args.filename) {
+                // The notes of every matching changelog, labeled by the package it belongs to
+                for path in changelog_files(&pwd, &args.filename)? {
+                    let dir = path.parent().unwrap_or(&pwd);
+                    let filename = path.file_name().unwrap_or_default().to_string_lossy();
+                    let label = match PackageJSON::from_directory(dir) {
+                        Ok(package) => package.name().to_string(),
+                        Err(_) => dir.strip_prefix(&pwd).unwrap_or(dir).display().to_string(),
+                    };
+
+                    let version = notes_version(dir, &filename, None)?;
+                    let changelog = Changelog::for_notes(dir, &filename, version.as_ref())?
+                        .with_config(&config);
+                    let message = changelog.notes(version.as_ref(), &options);
+                    let message = notes_or_error(message)?;
+                    let since = match since_tag {
+                        true => format!(" ({})", changelog.commits_since_tag(None)?.dimmed()),
+                        false => String::new(),
+                    };
+
+                    blocks.push((
+                        format!(
+                            "Notes for {}, {}{}",
+                            label.white().dimmed(),
+                            version.as_deref().unwrap_or("latest").to_lowercase().blue(),
+                            since
+                        ),
+                        message,
+                    ));
+                }
+            } else {
+                match scopes {
+                    Some(scopes) => {
+                        for package in scopes {
+                            let version =
+                                notes_version(package.pwd(), &args.filename, Some(&package))?;
+                            let changelog = Changelog::for_notes(
+                                package.pwd(),
+                                &args.filename,
+                                version.as_ref(),
+                            )?
+                            .with_config(&config);
+                            let message = changelog.notes(version.as_ref(), &options);
+                            let message = notes_or_error(message)?;
+                            let since = match since_tag {
+                                true => format!(
+                                    " ({})",
+                                    changelog.commits_since_tag(Some(&package))?.dimmed()
+                                ),
+                                false => String::new(),
+                            };
+
+                            blocks.push((
+                                match version {
+                                    Some(version) => format!(
+                                        "Notes for {}, {}{}",
+                                        package.name().white().dimmed(),
+                                        version.to_lowercase().blue(),
+                                        since
+                                    ),
+                                    None => format!(
+                                        "Notes for {}, {}",
+                                        package.name().white().dimmed(),
+                                        "latest".blue()
+                                    ),
+                                },
+                                message,
+                            ))
+                        }
+                    }
+                    None => {
+                        let version = notes_version(&pwd, &args.filename, None)?;
+                        let changelog =
+                            Changelog::for_notes(&pwd, &args.filename, version.as_ref())?
+                                .with_config(&config);
+                        let message = changelog.notes(version.as_ref(), &options);
+                        let message = notes_or_error(message)?;
+                        let since = match since_tag {
+                            true => format!(" ({})", changelog.commits_since_tag(None)?.dimmed()),
+                            false => String::new(),
+                        };
+
+                        blocks.push((
+                            match version {
+                                Some(version) => {
+                                    format!("Notes for {}{}", version.to_lowercase().blue(), since)
+                                }
+                                None => format!("Notes for {}", "latest".blue()),
                             },
                             message,
-                        )
+                        ))
                     }
                 }
-                None => {
-                    let message = Changelog::new(&pwd, &args.filename)?
-                        .notes(version.as_ref())
-                        .unwrap_or_else(|err| err.to_string().red().to_string());
+            }
 
-                    output_title(
-                        match version {
-                            Some(version) => format!("Notes for {}", version.to_lowercase().blue()),
-                            None => format!("Notes for {}", "latest".blue()),
-                        },
-                        message,
-                    )
+            let notes = blocks
+                .iter()
+                .map(|(_, message)| strip_colors(message))
+                .collect::<Vec<_>>()
+                .join("\n\n");
+
+            match output_file {
+                Some(_) => print_or_write(&notes, output_file.as_ref(), &pwd)?,
+                None => output_paged(blocks, !args.no_pager),
+            }
+
+            if *copy {
+                if copy_to_clipboard(&notes) {
+                    output_status("Copied the notes to the clipboard".to_string());
+                } else {
+                    output_warning(
+                        "Couldn't copy the notes, no clipboard is available on this system"
+                            .to_string(),
+                    );
                 }
             }
 
             Ok(())
         }
-        Commands::Release { version, with_npm } => {
+        Commands::Release {
+            version,
+            verify_semver,
+            with_npm,
+            from_commits,
+            from,
+            to,
+            sort_by: _,
+            no_date,
+            amend,
+            force,
+            scaffold,
+            dedupe_references,
+            notes_out,
+            tag_message_template,
+            commit_template,
+            propagate,
+            allow_empty,
+            create_empty,
+            tag_only,
+            porcelain,
+        } => {
+            if *verify_semver {
+                SemVer::verify(version)?;
+            }
+
+            if *tag_only {
+                return tag_released_versions(&pwd, &args.filename, &config, version, &scopes);
+            }
+
+            let to = to.as_deref().unwrap_or("HEAD");
+            let skip_empty = config.skip_empty_release && !allow_empty;
+            let commit_template = commit_template.as_ref().or(config.commit_template.as_ref());
+            let mut notes: Vec<(Option<String>, String)> = vec![];
+            let mut released: Vec<String> = vec![];
+            // The human output only goes to stderr, so that stdout only contains the porcelain lines
+            let output = |message: String| match porcelain {
+                true => output_status(message),
+                false => output(message),
+            };
+            let options = ReleaseOptions {
+                no_date: *no_date,
+                scaffold: *scaffold,
+                dedupe_references: *dedupe_references,
+                verbose: args.verbose,
+                create_empty: *create_empty,
+            };
+
+            if *amend {
+                let changelogs = match &scopes {
+                    Some(scopes) => scopes
+                        .iter()
+                        .map(|package| {
+                            Ok((
+                                Changelog::new(package.pwd(), &args.filename)?.with_config(&config),
+                                Some(package),
+                            ))
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                    None => vec![(
+                        Changelog::new(&pwd, &args.filename)?.with_config(&config),
+                        None,
+                    )],
+                };
+
+                let mut output_messages = vec![];
+
+                for (mut changelog, package) in changelogs {
+                    let version = changelog
+                        .latest_release()
+                        .ok_or_else(|| eyre!("Couldn't find a released version to amend"))?
+                        .to_string();
+                    let tag = config.tag(&version, package);
+
+                    if !force && changelog.is_published(&version, &tag)? {
+                        return Err(eyre!(
+                            "Version {} was already tagged or pushed, use {} to amend it anyway",
+                            version.blue().bold(),
+                            "--force".blue().bold()
+                        ));
+                    }
+
+                    if *from_commits {
+                        changelog.add_commits(
+                            from.as_deref(),
+                            to,
+                            package,
+                            config.reference_placement,
+                        )?;
+                    }
+
+                    let version = changelog.amend(&options)?;
+                    notes.push((
+                        package.map(|package| format!("{} {}", package.name(), version)),
+                        changelog.notes_scope(Some(&version), package, &NotesOptions::default())?,
+                    ));
+                    output_messages.push(match package {
+                        Some(package) => format!(
+                            "Amended {} for {}",
+                            version.green().bold(),
+                            package.name().white().dimmed()
+                        ),
+                        None => format!("Amended {}", version.green().bold()),
+                    });
+                }
+
+                output(output_messages.join("\n"));
+
+                if let Some(path) = notes_out {
+                    write_notes(&pwd.join(path), &notes)?;
+                }
+
+                return Ok(());
+            }
+
             match &scopes {
                 Some(scopes) => {
                     let repo = Git::new(Some(&pwd))?;
                     let mut changelog_commit_messages: Vec<String> = vec![];
                     let mut output_messages: Vec<String> = vec![];
+                    let mut progress = Progress::new("Releasing", scopes.len(), args.quiet);
+
+                    let packages = match propagate {
+                        true => root_package.packages()?,
+                        false => vec![],
+                    };
 
                     for package in scopes {
-                        let mut changelog = Changelog::new(package.pwd(), &args.filename)?;
+                        let mut changelog =
+                            Changelog::new(package.pwd(), &args.filename)?.with_config(&config);
 
                         let pwd_str = package.pwd().to_str().unwrap();
                         let mut package = package.clone();
                         let package_version = package.version_mut();
                         let version = package_version.change_to(version)?;
 
-                        // TODO: Only release when things changed?
-                        // if !changelog.has_changes(&scope) {
-                        //     continue;
-                        // }
+                        if *from_commits {
+                            changelog.add_commits(
+                                from.as_deref(),
+                                to,
+                                Some(&package),
+                                config.reference_placement,
+                            )?;
+                        }
+
+                        // Only release the packages that changed
+                        if skip_empty && changelog.unreleased_entries(None).is_empty() {
+                            output_messages.push(format!(
+                                "- Skipped {}, the unreleased section is empty",
+                                package.name().white().dimmed()
+                            ));
+                            progress.inc();
+                            continue;
+                        }
 
                         output_messages.push(format!(
                             "- Releasing {} for {}",
                             version.to_string().green().bold(),
                             package.name().white().dimmed()
                         ));
-                        changelog.release(&version, Some(&package))?;
+                        changelog.release(
+                            &Version::SemVer(version.clone()),
+                            Some(&package),
+                            &options,
+                        )?;
+                        released.push(release_summary(
+                            &changelog,
+                            &version.to_string(),
+                            Some(&package),
+                        ));
+                        notes.push((
+                            Some(format!("{} {}", package.name(), version)),
+                            changelog.notes_scope(
+                                Some(&version.to_string()),
+                                Some(&package),
+                                &NotesOptions::default(),
+                            )?,
+                        ));
+
+                        // Record the bump in the changelogs of the dependent packages
+                        for dependent in packages.iter().filter(|other| {
+                            other.name() != package.name()
+                                && config.depends_on(other, package.name())
+                        }) {
+                            let mut dependent_changelog =
+                                Changelog::new(dependent.pwd(), &args.filename)?
+                                    .with_config(&config);
+                            dependent_changelog.add_list_item_to_section(
+                                "Changed",
+                                &format!("Bump `{}` to {}", package.name(), version),
+                                false,
+                                Some(dependent),
+                            );
+                            dependent_changelog.persist()?;
+                            repo.add(dependent_changelog.file_path_str())?;
+
+                            output_messages.push(format!(
+                                "- Recorded the bump in {}",
+                                dependent.name().white().dimmed()
+                            ));
+                            if !*with_npm {
+                                changelog_commit_messages.push(format!(
+                                    "- Recorded the bump of `{}` in `{}`",
+                                    package.name(),
+                                    dependent.name(),
+                                ));
+                            }
+                        }
 
                         // Add the CHANGELOG.md file, so that we can commit it later.
                         repo.add(changelog.file_path_str())?;
+                        for path in changelog.version_file_paths() {
+                            repo.add(path.to_str().unwrap())?;
+                        }
 
                         if *with_npm {
                             Npm::new(Some(pwd_str))?.version_options(
                                 &version,
                                 Options {
                                     no_git_tag_version: true,
+                                    command: config.version_command.clone(),
                                 },
                             )?;
 
-                            // Add the `package-lock.json` file
-                            let pkg_lock = pwd.join("package-lock.json");
-                            if pkg_lock.exists() {
-                                repo.add(pkg_lock.to_str().unwrap())?;
+                            // Add the lockfiles that the version command updated
+                            for lockfile in LOCKFILES {
+                                let lockfile = pwd.join(lockfile);
+                                if lockfile.exists() {
+                                    repo.add(lockfile.to_str().unwrap())?;
+                                }
                             }
 
                             // Add the `package.json` file
                             repo.add(pwd.join("package.json").to_str().unwrap())?;
 
                             // Commit
-                            repo.commit(&format!("{} - {}", &version, &package.name()))?;
+                            repo.commit(&commit_message(
+                                commit_template,
+                                &version.to_string(),
+                                Some(&package),
+                            ))?;
 
                             // Generate a tag
-                            repo.tag(&format!("{}@v{}", &package.name(), &version))?;
+                            let tag = config.tag(&version.to_string(), Some(&package));
+                            match tag_message_template {
+                                Some(template) => {
+                                    let message = tag_message(
+                                        template,
+                                        &version.to_string(),
+                                        notes.last().map_or("", |(_, notes)| notes),
+                                    );
+                                    repo.annotated_tag(&tag, &message)?
+                                }
+                                None => repo.tag(&tag)?,
+                            };
                         } else {
                             changelog_commit_messages.push(format!(
                                 "- Released `{}` for `{}`",
@@ -656,8 +1863,12 @@ async fn main() -> Result<()> {
                                 package.name(),
                             ));
                         }
+
+                        progress.inc();
                     }
 
+                    progress.finish();
+
                     // Commit the CHANGELOG.md file
                     if !changelog_commit_messages.is_empty() {
                         let _ = &repo.commit(&format!(
@@ -669,70 +1880,1243 @@ async fn main() -> Result<()> {
                     output(output_messages.join("\n"));
                 }
                 None => {
-                    let mut changelog = Changelog::new(&pwd, &args.filename)?;
+                    let mut changelog = Changelog::new(&pwd, &args.filename)?.with_config(&config);
+
+                    let version = Version::resolve(version, &config, &pwd)?;
+
+                    if *from_commits {
+                        changelog.add_commits(
+                            from.as_deref(),
+                            to,
+                            None,
+                            config.reference_placement,
+                        )?;
+                    }
+
+                    if skip_empty && changelog.unreleased_entries(None).is_empty() {
+                        output(format!(
+                            "Nothing to release, the unreleased section is empty (use {} to release anyway)",
+                            "--allow-empty".white().dimmed()
+                        ));
+                        return Ok(());
+                    }
 
-                    let version: SemVer = version.parse()?;
                     output(format!("Releasing {}", &version.to_string().green().bold()));
-                    changelog.release(&version, None)?;
+                    changelog.release(&version, None, &options)?;
+                    released.push(release_summary(&changelog, &version.to_string(), None));
+                    notes.push((
+                        None,
+                        changelog.notes(Some(&version.to_string()), &NotesOptions::default())?,
+                    ));
 
                     if *with_npm {
+                        let semver = match &version {
+                            Version::SemVer(semver) => semver,
+                            Version::CalVer(_) => {
+                                return Err(eyre!(
+                                    "npm only supports semver versions, {} is a calendar version",
+                                    version
+                                ))
+                            }
+                        };
+
                         // Commit the CHANGELOG.md file
                         let repo = Git::new(Some(&pwd))?;
                         repo.add(changelog.file_path_str())?;
+                        for path in changelog.version_file_paths() {
+                            repo.add(path.to_str().unwrap())?;
+                        }
 
                         // Execute npm version <version>
                         Npm::new(Some(&args.pwd))?.version_options(
-                            &version,
+                            semver,
                             Options {
                                 no_git_tag_version: true,
+                                command: config.version_command.clone(),
                             },
                         )?;
 
-                        // Add the `package-lock.json` file
-                        let pkg_lock = pwd.join("package-lock.json");
-                        if pkg_lock.exists() {
-                            repo.add(pkg_lock.to_str().unwrap())?;
+                        // Add the lockfiles that the version command updated
+                        for lockfile in LOCKFILES {
+                            let lockfile = pwd.join(lockfile);
+                            if lockfile.exists() {
+                                repo.add(lockfile.to_str().unwrap())?;
+                            }
                         }
 
                         // Add the `package.json` file
                         repo.add(pwd.join("package.json").to_str().unwrap())?;
 
                         // Commit the version
-                        repo.commit(&version.to_string())?;
+                        repo.commit(&commit_message(commit_template, &version.to_string(), None))?;
 
                         // Let's create a tag!
-                        repo.tag(&format!("v{}", &version))?;
+                        let tag = config.tag(&version.to_string(), None);
+                        match tag_message_template {
+                            Some(template) => {
+                                let message = tag_message(
+                                    template,
+                                    &version.to_string(),
+                                    notes.last().map_or("", |(_, notes)| notes),
+                                );
+                                repo.annotated_tag(&tag, &message)?
+                            }
+                            None => repo.tag(&tag)?,
+                        };
                     }
                 }
             }
 
+            if let Some(path) = notes_out.as_ref().filter(|_| !notes.is_empty()) {
+                write_notes(&pwd.join(path), &notes)?;
+            }
+
+            if *porcelain {
+                for line in released {
+                    println!("{}", line);
+                }
+            }
+
+            Ok(())
+        }
+        Commands::NextVersion { bump } => {
+            match &scopes {
+                Some(scopes) => {
+                    let rows = scopes
+                        .iter()
+                        .map(|package| {
+                            let bump = match bump {
+                                Some(bump) => bump.to_string(),
+                                None => Changelog::new(package.pwd(), &args.filename)?
+                                    .with_config(&config)
+                                    .suggested_bump(Some(package))
+                                    .to_string(),
+                            };
+                            let version = package.clone().version_mut().change_to(&bump)?;
+
+                            Ok(vec![package.name().to_string(), version.to_string()])
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+
+                    output_porcelain(rows);
+                }
+                None => {
+                    let bump = match bump {
+                        Some(bump) => bump.to_string(),
+                        None => Changelog::new(&pwd, &args.filename)?
+                            .with_config(&config)
+                            .suggested_bump(None)
+                            .to_string(),
+                    };
+
+                    println!("{}", Version::resolve(&bump, &config, &pwd)?);
+                }
+            }
+
+            Ok(())
+        }
+        Commands::Unrelease { version, force } => {
+            let changelogs = match &scopes {
+                Some(scopes) => scopes
+                    .iter()
+                    .map(|package| {
+                        Ok((
+                            Changelog::new(package.pwd(), &args.filename)?.with_config(&config),
+                            Some(package),
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                None => vec![(
+                    Changelog::new(&pwd, &args.filename)?.with_config(&config),
+                    None,
+                )],
+            };
+
+            let mut output_messages = vec![];
+
+            for (mut changelog, package) in changelogs {
+                let tag = config.tag(version, package);
+
+                if !force && changelog.is_published(version, &tag)? {
+                    return Err(eyre!(
+                        "Version {} was already tagged or pushed, use {} to unrelease it anyway",
+                        version.blue().bold(),
+                        "--force".blue().bold()
+                    ));
+                }
+
+                changelog.unrelease(version, package)?;
+                output_messages.push(match package {
+                    Some(package) => format!(
+                        "Unreleased {} for {}",
+                        version.green().bold(),
+                        package.name().white().dimmed()
+                    ),
+                    None => format!("Unreleased {}", version.green().bold()),
+                });
+            }
+
+            output(output_messages.join("\n"));
+
+            Ok(())
+        }
+        Commands::Changed { base } => {
+            let changelogs = match &scopes {
+                Some(scopes) => scopes
+                    .iter()
+                    .map(|package| {
+                        Ok((
+                            Changelog::new(package.pwd(), &args.filename)?.with_config(&config),
+                            Some(package.name()),
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                None => vec![(
+                    Changelog::new(&pwd, &args.filename)?.with_config(&config),
+                    None,
+                )],
+            };
+
+            let mut messages = vec![];
+            let mut missing = vec![];
+
+            for (changelog, name) in changelogs {
+                let base_entries = changelog.at_revision(base)?.unreleased_entries(None);
+                let new_entries = changelog
+                    .unreleased_entries(None)
+                    .into_iter()
+                    .filter(|entry| !base_entries.contains(entry))
+                    .count();
+
+                match (new_entries, name) {
+                    (0, Some(name)) => missing.push(name.to_string()),
+                    (0, None) => missing.push(changelog.relative_path()?),
+                    (amount, name) => messages.push(format!(
+                        "Found {} new {} in {}",
+                        amount.to_string().green().bold(),
+                        if amount == 1 { "entry" } else { "entries" },
+                        name.map(|name| name.to_string())
+                            .unwrap_or(changelog.relative_path()?)
+                            .white()
+                            .dimmed()
+                    )),
+                }
+            }
+
+            if !missing.is_empty() {
+                output_error(
+                    args.error_format,
+                    "missing_entry",
+                    format!(
+                        "No new entries in the unreleased section of {} compared to {}",
+                        conjunction(&missing).white().dimmed(),
+                        base.blue().bold()
+                    ),
+                );
+
+                std::process::exit(1);
+            }
+
+            output(messages.join("\n"));
+
+            Ok(())
+        }
+        Commands::Diff { range, markdown } => {
+            let changelog = Changelog::new(&pwd, &args.filename)?.with_config(&config);
+            let (from, to) = range.split_once("..").unwrap_or((range, ""));
+
+            if from.is_empty() {
+                return Err(eyre!("Missing the git revision to start from: {}", range));
+            }
+
+            let older = changelog.at_revision(from)?;
+            let newer = match to {
+                "" => changelog,
+                to => changelog.at_revision(to)?,
+            };
+
+            let changes = older.diff(&newer);
+
+            if changes.is_empty() {
+                output(format!(
+                    "No changes to {} in {}",
+                    args.filename.white().dimmed(),
+                    range.blue().bold()
+                ));
+                return Ok(());
+            }
+
+            if *markdown {
+                let list = |added: bool| {
+                    changes
+                        .iter()
+                        .filter_map(|change| match (change, added) {
+                            (Change::Added(text), true) | (Change::Removed(text), false) => {
+                                Some(format!("- {}", text))
+                            }
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>()
+                };
+
+                let sections: Vec<String> = [("Added", list(true)), ("Removed", list(false))]
+                    .into_iter()
+                    .filter(|(_, items)| !items.is_empty())
+                    .map(|(name, items)| format!("### {}\n\n{}", name, items.join("\n")))
+                    .collect();
+
+                println!("{}", sections.join("\n\n"));
+                return Ok(());
+            }
+
+            output_title(
+                format!(
+                    "Changes to {} in {}",
+                    args.filename.white().dimmed(),
+                    range.blue()
+                ),
+                changes
+                    .iter()
+                    .map(|change| match change {
+                        Change::Added(text) => format!("{} {}", "+".green().bold(), text),
+                        Change::Removed(text) => format!("{} {}", "-".red().bold(), text),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+
+            Ok(())
+        }
+        Commands::Split { keep_latest, dir } => {
+            let pwds = match scopes {
+                Some(scopes) => scopes
+                    .iter()
+                    .map(|scope| scope.pwd().to_path_buf())
+                    .collect(),
+                None => vec![pwd],
+            };
+
+            for pwd in pwds {
+                let mut changelog = Changelog::new(&pwd, &args.filename)?.with_config(&config);
+                let paths = changelog.split(*keep_latest, dir)?;
+
+                output(match paths.len() {
+                    0 => format!(
+                        "Nothing to split, {} has {} or fewer versions",
+                        changelog.relative_path()?.white().dimmed(),
+                        keep_latest
+                    ),
+                    amount => format!(
+                        "Moved {} {} to: {}",
+                        amount.to_string().green().bold(),
+                        if amount == 1 { "version" } else { "versions" },
+                        pwd.join(dir).display().to_string().white().dimmed()
+                    ),
+                });
+            }
+
+            Ok(())
+        }
+        Commands::MergeSplit => {
+            let pwds = match scopes {
+                Some(scopes) => scopes
+                    .iter()
+                    .map(|scope| scope.pwd().to_path_buf())
+                    .collect(),
+                None => vec![pwd],
+            };
+
+            for pwd in pwds {
+                let mut changelog = Changelog::new(&pwd, &args.filename)?.with_config(&config);
+
+                output(match changelog.merge_split()? {
+                    0 => format!(
+                        "Nothing to merge, {} has no split versions",
+                        changelog.relative_path()?.white().dimmed()
+                    ),
+                    amount => format!(
+                        "Moved {} {} back into: {}",
+                        amount.to_string().green().bold(),
+                        if amount == 1 { "version" } else { "versions" },
+                        changelog.relative_path()?.white().dimmed()
+                    ),
+                });
+            }
+
             Ok(())
         }
-        Commands::List { amount, all } => {
+        Commands::Merge { file, dry_run } => {
+            let other = Changelog::from_file(Path::new(file))?;
+
+            let mut changelog = Changelog::new(&pwd, &args.filename)?.with_config(&config);
+            let changes = changelog.merge(&other, None)?;
+
+            if *dry_run {
+                println!("{}", changelog);
+            } else {
+                changelog.persist()?;
+            }
+
+            if changes.is_empty() {
+                output("Nothing to merge, the changelogs are already in sync".to_string());
+            } else {
+                output(
+                    changes
+                        .iter()
+                        .map(|change| format!("- {}", change))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                );
+            }
+
+            Ok(())
+        }
+        Commands::Resolve { base, ours, theirs } => {
+            let base = Changelog::from_file(Path::new(base))?;
+            let theirs = Changelog::from_file(Path::new(theirs))?;
+            let mut changelog = Changelog::from_file(Path::new(ours))?.with_config(&config);
+
+            match changelog.merge(&theirs, Some(&base)) {
+                Ok(changes) => {
+                    changelog.persist()?;
+                    output(format!(
+                        "Resolved {} with {} {}",
+                        args.filename.white().dimmed(),
+                        changes.len().to_string().green().bold(),
+                        if changes.len() == 1 {
+                            "change"
+                        } else {
+                            "changes"
+                        }
+                    ));
+
+                    Ok(())
+                }
+                Err(e) => {
+                    // Leave our version of the file, git marks it as conflicted
+                    output_error(args.error_format, "merge_conflict", e.to_string());
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::List {
+            amount,
+            all,
+            include_unreleased,
+            reverse,
+            oldest,
+            porcelain,
+            max_width,
+            contains,
+        } => {
             let amount = match &all {
                 true => Amount::All,
                 false => *amount,
             };
+            let options = ListOptions {
+                include_unreleased: *include_unreleased,
+                reverse: *reverse,
+                oldest: *oldest,
+                // The output is indented by 2 characters
+                max_width: max_width
+                    .or_else(|| terminal_width().map(|width| width.saturating_sub(2))),
+                contains: contains.clone(),
+            };
+            // The oldest versions are at the bottom, and the matching versions can be anywhere, so
+            // the whole changelog has to be read
+            let read_amount = match *oldest || contains.is_some() {
+                true => Amount::All,
+                false => amount,
+            };
+
+            if *porcelain {
+                let rows = match scopes {
+                    Some(scopes) => scopes
+                        .iter()
+                        .map(|package| {
+                            let changelog =
+                                Changelog::for_list(package.pwd(), &args.filename, read_amount)?;
+                            Ok(changelog
+                                .list_versions(amount, &options)
+                                .into_iter()
+                                .map(|(version, date)| {
+                                    vec![
+                                        package.name().to_string(),
+                                        version,
+                                        date.unwrap_or_default(),
+                                    ]
+                                })
+                                .collect::<Vec<_>>())
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                        .concat(),
+                    None => Changelog::for_list(&pwd, &args.filename, read_amount)?
+                        .list_versions(amount, &options)
+                        .into_iter()
+                        .map(|(version, date)| vec![version, date.unwrap_or_default()])
+                        .collect(),
+                };
+
+                output_porcelain(rows);
+                return Ok(());
+            }
+
+            let mut blocks = vec![];
 
             match scopes {
                 Some(scopes) => {
                     for package in scopes {
-                        let message = Changelog::new(package.pwd(), &args.filename)?
-                            .list(amount)
-                            .unwrap_or_else(|err| err.to_string().red().to_string());
+                        let message =
+                            Changelog::for_list(package.pwd(), &args.filename, read_amount)?
+                                .list(amount, &options)
+                                .unwrap_or_else(|err| err.to_string().red().to_string());
 
-                        output_title(
+                        blocks.push((
                             format!("Releases for {}", package.name().white().dimmed()),
                             message,
-                        )
+                        ))
                     }
                 }
                 None => {
-                    output(Changelog::new(&pwd, &args.filename)?.list(amount)?);
+                    blocks.push((
+                        "".to_string(),
+                        Changelog::for_list(&pwd, &args.filename, read_amount)?
+                            .list(amount, &options)?,
+                    ));
                 }
             }
 
+            output_paged(blocks, !args.no_pager);
+
             Ok(())
         }
+        Commands::Search {
+            query,
+            regex,
+            ignore_case,
+            context,
+        } => {
+            let pattern = match regex {
+                true => query.to_string(),
+                false => regex::escape(query),
+            };
+            let pattern = regex::RegexBuilder::new(&pattern)
+                .case_insensitive(*ignore_case)
+                .build()
+                .map_err(|e| eyre!("Invalid regular expression {}: {}", query.blue().bold(), e))?;
+
+            let changelogs: Vec<(String, &Path)> = match &scopes {
+                Some(scopes) => scopes
+                    .iter()
+                    .map(|package| (package.name().to_string(), package.pwd()))
+                    .collect(),
+                None => vec![(args.filename.to_string(), pwd.as_path())],
+            };
+
+            let mut blocks = vec![];
+            for (name, dir) in changelogs {
+                let changelog = Changelog::new(dir, &args.filename)?.with_config(&config);
+                let versions = changelog.search(&pattern, *context);
+
+                if versions.is_empty() {
+                    blocks.push((
+                        String::new(),
+                        format!(
+                            "No entries match {} in {}",
+                            query.blue().bold(),
+                            name.white().dimmed()
+                        ),
+                    ));
+                    continue;
+                }
+
+                let highlight = |entry: &str| {
+                    pattern
+                        .replace_all(entry, |captures: &regex::Captures| {
+                            captures[0].yellow().bold().to_string()
+                        })
+                        .to_string()
+                };
+
+                blocks.push((
+                    format!(
+                        "Entries matching {} in {}",
+                        query.blue().bold(),
+                        name.white().dimmed()
+                    ),
+                    versions
+                        .iter()
+                        .map(|(version, entries)| {
+                            let entries = entries
+                                .iter()
+                                .map(|(section, entry)| match section.is_empty() {
+                                    true => format!("- {}", highlight(entry)),
+                                    false => format!(
+                                        "- {} {}",
+                                        format!("[{}]", section).dimmed(),
+                                        highlight(entry)
+                                    ),
+                                })
+                                .collect::<Vec<_>>()
+                                .join("\n");
+
+                            format!("{}\n\n{}", version.green().bold(), entries)
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n\n"),
+                ));
+            }
+
+            output_paged(blocks, !args.no_pager);
+
+            Ok(())
+        }
+        Commands::Export {
+            format,
+            output_file,
+        } => {
+            let exports = match scopes {
+                Some(scopes) => scopes
+                    .iter()
+                    .map(|package| {
+                        Ok(Changelog::new(package.pwd(), &args.filename)?
+                            .with_config(&config)
+                            .export(*format))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                None => {
+                    let export = Changelog::new(&pwd, &args.filename)?
+                        .with_config(&config)
+                        .export(*format);
+                    return print_or_write(&export, output_file.as_ref(), &pwd);
+                }
+            };
+
+            // An array with the export of every package
+            print_or_write(
+                &format!("[{}]", exports.join(",")),
+                output_file.as_ref(),
+                &pwd,
+            )
+        }
+        Commands::Validate {
+            fail_on_warning,
+            fix,
+        } => {
+            let pwds = match &scopes {
+                Some(scopes) => scopes
+                    .iter()
+                    .map(|scope| scope.pwd().to_path_buf())
+                    .collect(),
+                None => vec![pwd],
+            };
+
+            let mut failed = false;
+
+            for pwd in pwds {
+                let file_path = pwd.join(&args.filename);
+                let contents = fs::read_to_string(&file_path).unwrap_or_default();
+                let (fixed, corrections) = fix_heading_levels(&contents, &config.section_order);
+
+                if *fix && !corrections.is_empty() {
+                    fs::write(&file_path, fixed)?;
+                }
+
+                let changelog = Changelog::new(&pwd, &args.filename)?.with_config(&config);
+                let path = changelog.relative_path()?;
+
+                if *fix && !corrections.is_empty() {
+                    output(format!(
+                        "Fixed the heading levels in {}:\n\n{}",
+                        path.white().dimmed(),
+                        corrections
+                            .iter()
+                            .map(|(line, old, new)| format!(
+                                "- Changed \"{}\" on line {} to \"{}\"",
+                                old, line, new
+                            ))
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    ));
+                }
+
+                let mut problems = changelog.validate();
+                if !*fix {
+                    problems.extend(corrections.into_iter().map(|(line, old, new)| {
+                        Problem::Warning(format!(
+                            "Heading \"{}\" on line {} should be \"{}\"",
+                            old, line, new
+                        ))
+                    }));
+                }
+
+                if problems.is_empty() {
+                    output(format!("No problems found in {}", path.white().dimmed()));
+                    continue;
+                }
+
+                failed |= problems
+                    .iter()
+                    .any(|problem| problem.is_error() || *fail_on_warning);
+
+                output(format!(
+                    "Problems in {}:\n\n{}",
+                    path.white().dimmed(),
+                    problems
+                        .iter()
+                        .map(|problem| format!("- {}", problem))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                ));
+            }
+
+            if failed {
+                output_error(
+                    args.error_format,
+                    "invalid_changelog",
+                    "The changelog isn't valid".to_string(),
+                );
+
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        Commands::Normalize {
+            from,
+            whitespace,
+            check,
+        } => {
+            let pwds = match scopes {
+                Some(scopes) => scopes
+                    .iter()
+                    .map(|scope| scope.pwd().to_path_buf())
+                    .collect(),
+                None => vec![pwd],
+            };
+
+            if *whitespace {
+                let mut unnormalized = vec![];
+
+                for pwd in pwds {
+                    let path = pwd.join(&args.filename);
+                    let contents = fs::read_to_string(&path)?;
+                    let normalized = markdown::tokens::normalize_whitespace(&contents);
+
+                    if normalized == contents {
+                        output(format!(
+                            "The whitespace of the changelog is normalized already: {}",
+                            path.display().to_string().white().dimmed()
+                        ));
+                    } else if *check {
+                        unnormalized.push(path.display().to_string());
+                    } else {
+                        fs::write(&path, normalized)?;
+                        output(format!(
+                            "Normalized the whitespace of the changelog at: {}",
+                            path.display().to_string().white().dimmed()
+                        ));
+                    }
+                }
+
+                if !unnormalized.is_empty() {
+                    output_error(
+                        args.error_format,
+                        "unnormalized_whitespace",
+                        format!(
+                            "The whitespace of {} isn't normalized, run `changelog normalize --whitespace`",
+                            unnormalized.join(", ")
+                        ),
+                    );
+
+                    std::process::exit(1);
+                }
+
+                return Ok(());
+            }
+
+            for pwd in pwds {
+                let mut changelog = Changelog::import(&pwd, &args.filename, *from, &config)?;
+                if config.strip_emoji {
+                    changelog.strip_emoji();
+                }
+                changelog.persist()?;
+
+                output(format!(
+                    "Normalized the changelog at: {}",
+                    pwd.join(&args.filename).display()
+                ));
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Verify that this version of changelog satisfies the requirement in the optional
+/// `.changelog-version` file, so that everyone in a team uses a compatible version.
+fn check_version_pin(pwd: &Path, strict: bool) -> Result<()> {
+    let pin_path = pwd.join(VERSION_PIN_FILENAME);
+
+    if !pin_path.exists() {
+        return Ok(());
+    }
+
+    let requirement = fs::read_to_string(&pin_path)?;
+    let requirement = requirement.trim();
+    let version: SemVer = env!("CARGO_PKG_VERSION").parse()?;
+
+    if version.satisfies(requirement)? {
+        return Ok(());
+    }
+
+    let message = format!(
+        "This is changelog {}, but {} requires {}",
+        version.to_string().blue().bold(),
+        VERSION_PIN_FILENAME.white().dimmed(),
+        requirement.blue().bold()
+    );
+
+    if strict {
+        return Err(eyre!(message));
+    }
+
+    output_warning(message);
+
+    Ok(())
+}
+
+/// Warn about duplicate and misordered version headings in the changelog file, or fail when
+/// `strict` is set.
+fn check_version_headings(path: &Path, strict: bool) -> Result<()> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(()),
+    };
+
+    let problems = version_heading_problems(&contents);
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    let message = format!(
+        "Found problems in the version headings of {}:\n{}",
+        path.display().to_string().white().dimmed(),
+        problems
+            .iter()
+            .map(|problem| match problem {
+                Problem::Error(message) | Problem::Warning(message) => format!("  - {}", message),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+
+    if strict {
+        return Err(eyre!(message));
+    }
+
+    output_warning(message);
+
+    Ok(())
+}
+
+/// Whether the changelog filename is a glob pattern, e.g.: "packages/*/CHANGELOG.md"
+fn is_glob(filename: &str) -> bool {
+    filename.contains(['*', '?', '['])
+}
+
+/// The changelog files that match a glob pattern, relative to `pwd`
+fn changelog_files(pwd: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let files = glob::glob(&pwd.join(pattern).to_string_lossy())
+        .map_err(|e| eyre!("Invalid filename pattern {}: {}", pattern, e))?
+        .filter_map(|path| path.ok())
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+
+    if files.is_empty() {
+        return Err(eyre!("Couldn't find changelog files matching: {}", pattern));
+    }
+
+    Ok(files)
+}
+
+/// The changelog files that a command might write: the changelogs matching a glob pattern, or the
+/// changelogs of the root and of every package of a monorepo
+fn changelog_paths(pwd: &Path, filename: &str, root_package: &PackageJSON) -> Vec<PathBuf> {
+    if is_glob(filename) {
+        return changelog_files(pwd, filename).unwrap_or_default();
+    }
+
+    let packages = match root_package.is_monorepo() {
+        true => root_package.packages().unwrap_or_default(),
+        false => vec![],
+    };
+
+    std::iter::once(pwd.join(filename))
+        .chain(packages.iter().map(|package| package.pwd().join(filename)))
+        .collect()
+}
+
+/// Write the notes of the released versions to a file. The notes of multiple packages are combined
+/// under a heading per package.
+fn write_notes(path: &Path, notes: &[(Option<String>, String)]) -> Result<()> {
+    let contents = notes
+        .iter()
+        .map(|(title, notes)| match title {
+            Some(title) => format!("## {}\n\n{}", title, notes.trim_end()),
+            None => notes.trim_end().to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    fs::write(path, contents + "\n")?;
+    output_status(format!("Wrote the release notes to: {}", path.display()));
+
+    Ok(())
+}
+
+/// Print the output of a command to stdout, or write it to the `--output-file` instead, so that the
+/// file only contains the output itself
+fn print_or_write(contents: &str, output_file: Option<&String>, pwd: &Path) -> Result<()> {
+    match output_file {
+        Some(path) => {
+            let path = pwd.join(path);
+            fs::write(&path, format!("{}\n", contents))?;
+            output_status(format!("Wrote the output to: {}", path.display()));
+        }
+        None => println!("{}", contents),
+    }
+
+    Ok(())
+}
+
+/// The line of a released version for `release --porcelain`, e.g.: "released 1.2.3 on 2024-01-15
+/// with 7 entries"
+fn release_summary(changelog: &Changelog, version: &str, package: Option<&PackageJSON>) -> String {
+    // Released versions are named after the version only, also in the changelog of a package
+    let entries = changelog
+        .count(Some(&version.to_string()), None)
+        .map_or(0, |count| count.total);
+
+    // The date of the released heading, there is none with `--no-date`
+    let date = changelog
+        .version_date(version)
+        .map_or(String::new(), |date| format!(" on {}", date));
+
+    format!(
+        "released {}{}{} with {} {}",
+        package.map_or(String::new(), |package| format!("{}@", package.name())),
+        version,
+        date,
+        entries,
+        if entries == 1 { "entry" } else { "entries" }
+    )
+}
+
+/// The message of an annotated release tag, the `{version}` and `{notes}` placeholders of the
+/// template are filled in and a literal "\n" is a newline, e.g.: when passed from a shell
+fn tag_message(template: &str, version: &str, notes: &str) -> String {
+    template
+        .replace("\\n", "\n")
+        .replace("{version}", version)
+        .replace("{notes}", notes.trim())
+}
+
+/// Create the git tags of versions that are already in the changelog, for `release --tag-only`.
+/// Nothing is tagged unless the working tree is clean and every version exists.
+fn tag_released_versions(
+    pwd: &Path,
+    filename: &str,
+    config: &Config,
+    version: &str,
+    scopes: &Option<Vec<PackageJSON>>,
+) -> Result<()> {
+    let repo = Git::new(Some(&pwd.to_path_buf()))?;
+    if !repo.is_clean()? {
+        return Err(eyre!(
+            "The working tree has uncommitted changes, commit the changelog before tagging"
+        ));
+    }
+
+    let packages: Vec<Option<&PackageJSON>> = match scopes {
+        Some(scopes) => scopes.iter().map(Some).collect(),
+        None => vec![None],
+    };
+
+    let mut tags = vec![];
+    for package in packages {
+        let changelog = Changelog::new(package.map_or(pwd, |package| package.pwd()), filename)?
+            .with_config(config);
+
+        let version = match version {
+            "infer" => changelog.latest_release().map(str::to_string).ok_or_else(|| {
+                eyre!(
+                    "Couldn't find a released version in {}",
+                    changelog.relative_path().unwrap_or_default()
+                )
+            })?,
+            version => version
+                .trim_start_matches('v')
+                .parse::<Version>()
+                .map_err(|_| {
+                    eyre!(
+                        "Use the version that is in the changelog with --tag-only, e.g.: 1.2.3, instead of: {}",
+                        version
+                    )
+                })?
+                .to_string(),
+        };
+
+        if !changelog.has_version(&version) {
+            return Err(eyre!(
+                "Couldn't find version {} in {}",
+                version,
+                changelog.relative_path()?
+            ));
+        }
+
+        let tag = config.tag(&version, package);
+        if repo.tag_exists(&tag) {
+            return Err(eyre!("The tag {} already exists", tag));
+        }
+
+        tags.push((version, tag));
     }
+
+    for (version, tag) in tags {
+        repo.tag(&tag)?;
+        output(format!(
+            "Tagged {} as {}",
+            version.green().bold(),
+            tag.white().dimmed()
+        ));
+    }
+
+    Ok(())
+}
+
+/// The message of a release commit, rendered from the template when one is configured. Without
+/// one, this is the version, followed by the package name in a monorepo.
+fn commit_message(
+    template: Option<&String>,
+    version: &str,
+    package: Option<&PackageJSON>,
+) -> String {
+    match (template, package) {
+        (Some(template), _) => template
+            .replace("\\n", "\n")
+            .replace("{version}", version)
+            .replace("{package}", package.map_or("", |package| package.name())),
+        (None, Some(package)) => format!("{} - {}", version, package.name()),
+        (None, None) => version.to_string(),
+    }
+}
+
+/// Highlight an added entry in a confirmation, unless highlighting is disabled
+fn highlight_entry(entry: &str, highlight: bool) -> String {
+    match highlight {
+        true => entry.green().bold().to_string(),
+        false => entry.to_string(),
+    }
+}
+
+/// Highlight the added entries in the rendered contents of a section, unless highlighting is
+/// disabled
+fn highlight_entries(text: String, entries: &[String], highlight: bool) -> String {
+    entries.iter().fold(text, |text, entry| {
+        text.replace(
+            &format!("- {}", entry),
+            &format!("- {}", highlight_entry(entry, highlight)),
+        )
+    })
+}
+
+/// Fetch the title of a link and render it as an entry, optionally crediting the author of a pull
+/// request with the name from the author map. Links with a configured resolver are resolved with
+/// its command instead. A title with the conventional commit type of another section than the one
+/// the entry is added to gets a suggestion.
+fn resolve_link(
+    link: &str,
+    section: &str,
+    thanks: bool,
+    config: &Config,
+    pwd: &Path,
+) -> Result<String> {
+    if let Some(resolver) = config.link_resolver(link) {
+        return Ok(resolver.resolve(link, pwd)?);
+    }
+
+    // The title of a merge request can't be fetched, so it is added as a literal reference
+    if is_merge_request(link) {
+        return Ok(literal_reference(link, config.link_format));
+    }
+
+    let mut info = link.parse::<GitHubInfo>()?;
+
+    if config.suggest_section {
+        suggest_section(info.title(), section);
+    }
+
+    if thanks {
+        if let Some(name) = info.author().map(|login| config.author(login)) {
+            info.thank(&name);
+        }
+    }
+
+    let entry = info.render(config.reference_placement, config.link_format);
+    let entry = match config.strip_emoji {
+        true => markdown::tokens::strip_emoji(&entry),
+        false => entry,
+    };
+
+    Ok(match config.escape_mentions {
+        true => markdown::tokens::escape_mentions(&entry),
+        false => entry,
+    })
+}
+
+/// Warn when the conventional commit type of a title belongs to another section than the one the
+/// entry is added to, e.g.: a "fix: ..." pull request with `changelog add`
+fn suggest_section(title: &str, section: &str) {
+    let commit = match ConventionalCommit::parse(title) {
+        Some(commit) => commit,
+        None => return,
+    };
+    let command = match commit.section() {
+        Some(expected) if expected.eq_ignore_ascii_case(section) => return,
+        Some("Added") => "add",
+        Some("Fixed") => "fix",
+        Some("Changed") => "change",
+        Some("Removed") => "remove",
+        Some("Deprecated") => "deprecate",
+        _ => return,
+    };
+
+    output_warning(format!(
+        "This looks like a \"{}\" change, did you mean {}?",
+        commit.kind,
+        format!("changelog {}", command).blue().bold()
+    ));
+}
+
+/// An entry for every commit of a pull request, that references the pull request
+fn pull_request_entries(url: &str, config: &Config) -> Result<Vec<String>> {
+    let pr = url.parse::<PullRequest>()?;
+    let entries = pr.commit_entries(config.reference_placement, config.link_format);
+
+    if entries.is_empty() {
+        return Err(eyre!("Couldn't find the commits of: {}", url));
+    }
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| match config.strip_emoji {
+            true => markdown::tokens::strip_emoji(&entry),
+            false => entry,
+        })
+        .map(|entry| match config.escape_mentions {
+            true => markdown::tokens::escape_mentions(&entry),
+            false => entry,
+        })
+        .collect())
+}
+
+/// Read the message of an entry from a file, or from stdin when the path is `-`. Empty lines are
+/// removed, because they would end the entry.
+fn read_message_file(path: &str) -> Result<String> {
+    let contents = match path {
+        "-" => {
+            let mut contents = String::new();
+            std::io::stdin().read_to_string(&mut contents)?;
+            contents
+        }
+        path => fs::read_to_string(path)
+            .map_err(|e| eyre!("Couldn't read message file {}: {}", path.blue().bold(), e))?,
+    };
+
+    let message = contents
+        .lines()
+        .map(|line| line.trim_end())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match message.trim() {
+        "" => Err(eyre!("The message file {} is empty", path.blue().bold())),
+        message => Ok(message.to_string()),
+    }
+}
+
+/// Compose the message of an entry in the `$EDITOR`, like `git commit` does
+fn compose_message(section_name: &str) -> Result<String> {
+    let scaffold = format!(
+        include_str!("./fixtures/compose_entry.txt"),
+        section_name.to_lowercase()
+    );
+
+    let contents = rich_edit(Some(&scaffold)).ok_or_else(|| {
+        eyre!(
+            "Couldn't open your editor, make sure that {} is set",
+            "$EDITOR".blue().bold()
+        )
+    })?;
+
+    let message = contents
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| line.trim_end())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match message.trim() {
+        "" => Err(eyre!("Aborting, the entry is empty")),
+        message => Ok(message.to_string()),
+    }
+}
+
+/// Log to stderr, so that the logs don't end up in the output of `--json` and friends
+fn init_logging(level: Option<LevelFilter>) {
+    let level = level
+        .or_else(|| {
+            std::env::var("RUST_LOG")
+                .ok()
+                .and_then(|level| level.parse().ok())
+        })
+        .unwrap_or(LevelFilter::OFF);
+
+    if level == LevelFilter::OFF {
+        return;
+    }
+
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+/// Exit when none of the link, commit hash or message are provided for the "add" commands
+fn missing_input(command: &Commands, error_format: ErrorFormat) -> ! {
+    let command = match command {
+        Commands::Add { .. } => "add",
+        Commands::Fix { .. } => "fix",
+        Commands::Change { .. } => "change",
+        Commands::Remove { .. } => "remove",
+        Commands::Deprecate { .. } => "deprecate",
+        _ => unreachable!(),
+    };
+
+    output_error(
+        error_format,
+        "missing_input",
+        format!(
+            "No {}, {} or {} provided, run `{}` for more info",
+            "<LINK>".blue().bold(),
+            "<COMMIT HASH>".blue().bold(),
+            "--message".blue().bold(),
+            format!("changelog {} --help", command).blue().bold()
+        ),
+    );
+
+    std::process::exit(1);
 }
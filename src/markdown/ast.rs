@@ -1,6 +1,8 @@
+use crate::error::ChangelogError;
+use crate::markdown::tokens::{strip_emoji, strip_links};
 use crate::MarkdownToken;
-use color_eyre::eyre::Error;
 use std::fmt::Display;
+use std::iter::Peekable;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -41,6 +43,36 @@ impl Node {
         }
     }
 
+    /// Replace the links in all list items, paragraphs and tables with their text
+    pub fn strip_links(&mut self) {
+        match &mut self.data {
+            Some(MarkdownToken::ListItem(text, _)) | Some(MarkdownToken::Paragraph(text)) => {
+                *text = strip_links(text);
+            }
+            Some(MarkdownToken::Table(rows)) => {
+                for row in rows {
+                    *row = strip_links(row);
+                }
+            }
+            _ => {}
+        }
+
+        for child in &mut self.children {
+            child.strip_links();
+        }
+    }
+
+    /// Remove the emoji from all list items
+    pub fn strip_emoji(&mut self) {
+        if let Some(MarkdownToken::ListItem(text, _)) = &mut self.data {
+            *text = strip_emoji(text);
+        }
+
+        for child in &mut self.children {
+            child.strip_emoji();
+        }
+    }
+
     pub fn find_node<'a, F>(&'a self, predicate: F) -> Option<&'a Node>
     where
         Self: Sized,
@@ -133,41 +165,30 @@ impl Display for Node {
 }
 
 impl FromStr for Node {
-    type Err = Error;
+    type Err = ChangelogError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let tokens = MarkdownToken::lex(s);
-        let mut iterator = tokens.iter().peekable();
+        // Reference definitions can be anywhere (e.g.: below the entry that uses them), they are
+        // grouped at the end so they don't end the section they are in.
+        let (references, tokens): (Vec<_>, Vec<_>) = MarkdownToken::lex(s)
+            .into_iter()
+            .partition(|token| matches!(token, MarkdownToken::Reference(_, _)));
+        let mut iterator = tokens.into_iter().chain(references).peekable();
 
         Ok(Node::new(None, parse(&mut iterator, None)))
     }
 }
 
 /// Parse a list of tokens into a tree of nodes
-fn parse(
-    tokens: &mut std::iter::Peekable<std::slice::Iter<'_, MarkdownToken>>,
-    parent: Option<&MarkdownToken>,
-) -> Vec<Node> {
+fn parse<I>(tokens: &mut Peekable<I>, parent: Option<&MarkdownToken>) -> Vec<Node>
+where
+    I: Iterator<Item = MarkdownToken>,
+{
     // TODO: Improve converting our tokens to an AST
     let mut root: Vec<Node> = vec![];
 
     while let Some(token) = tokens.next() {
-        root.push(match token {
-            MarkdownToken::H1(_) | MarkdownToken::H2(_) | MarkdownToken::H3(_) => {
-                Node::new(Some(token.clone()), parse(tokens, Some(token)))
-            }
-            MarkdownToken::ListItem(_, _) => {
-                let mut ul = Node::from_token(MarkdownToken::UnorderedList);
-                ul.add_child(Node::from_token(token.clone()));
-
-                while let Some(MarkdownToken::ListItem(_, _)) = &tokens.peek() {
-                    ul.add_child(Node::from_token(tokens.next().unwrap().clone()));
-                }
-
-                ul
-            }
-            _ => Node::from_token(token.clone()),
-        });
+        root.push(parse_node(token, tokens));
 
         if let Some(parent) = parent {
             match (parent, tokens.peek()) {
@@ -177,6 +198,10 @@ fn parse(
                 | (MarkdownToken::H3(_), Some(MarkdownToken::H3(_)))
                 | (MarkdownToken::H3(_), Some(MarkdownToken::H2(_)))
                 | (MarkdownToken::H3(_), Some(MarkdownToken::H1(_)))
+                | (MarkdownToken::H4(_), Some(MarkdownToken::H4(_)))
+                | (MarkdownToken::H4(_), Some(MarkdownToken::H3(_)))
+                | (MarkdownToken::H4(_), Some(MarkdownToken::H2(_)))
+                | (MarkdownToken::H4(_), Some(MarkdownToken::H1(_)))
                 | (_, Some(MarkdownToken::Reference(_, _))) => {
                     return root;
                 }
@@ -187,3 +212,31 @@ fn parse(
 
     root
 }
+
+/// Parse a single token into a node, consuming the tokens that belong to it (e.g.: the contents
+/// of a heading, or the other items of a list)
+pub fn parse_node<I>(token: MarkdownToken, tokens: &mut Peekable<I>) -> Node
+where
+    I: Iterator<Item = MarkdownToken>,
+{
+    match token {
+        MarkdownToken::H1(_)
+        | MarkdownToken::H2(_)
+        | MarkdownToken::H3(_)
+        | MarkdownToken::H4(_) => {
+            let children = parse(tokens, Some(&token));
+            Node::new(Some(token), children)
+        }
+        MarkdownToken::ListItem(_, _) => {
+            let mut ul = Node::from_token(MarkdownToken::UnorderedList);
+            ul.add_child(Node::from_token(token));
+
+            while let Some(MarkdownToken::ListItem(_, _)) = tokens.peek() {
+                ul.add_child(Node::from_token(tokens.next().unwrap()));
+            }
+
+            ul
+        }
+        _ => Node::from_token(token),
+    }
+}
@@ -1,2 +1,4 @@
 pub mod ast;
+pub mod normalize;
+pub mod stream;
 pub mod tokens;
@@ -0,0 +1,426 @@
+use crate::config::Config;
+use crate::package::Version;
+use std::str::FromStr;
+
+/// The tool that generated a changelog, used to normalize it into the structure of this tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    /// Detect the tool based on the contents
+    Auto,
+
+    /// https://git-cliff.org, e.g.: "### Features" and "### Bug Fixes" sections
+    GitCliff,
+
+    /// The release notes that GitHub generates, e.g.: "## What's Changed" with
+    /// "* Title by @user in https://github.com/..." entries
+    GitHub,
+}
+
+impl FromStr for Tool {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Tool::Auto),
+            "git-cliff" => Ok(Tool::GitCliff),
+            "github" => Ok(Tool::GitHub),
+            _ => Err(format!("Invalid tool: {}", s)),
+        }
+    }
+}
+
+impl Tool {
+    fn detect(contents: &str) -> Self {
+        if contents.contains("## What's Changed") || contents.contains("**Full Changelog**") {
+            Tool::GitHub
+        } else {
+            Tool::GitCliff
+        }
+    }
+}
+
+/// Section names of other tools, and the section of this tool they belong to
+const SECTION_ALIASES: [(&str, &str); 14] = [
+    ("Features", "Added"),
+    ("New Features", "Added"),
+    ("Bug Fixes", "Fixed"),
+    ("Bugfixes", "Fixed"),
+    ("Fixes", "Fixed"),
+    ("Refactor", "Changed"),
+    ("Performance", "Changed"),
+    ("Revert", "Changed"),
+    ("What's Changed", "Changed"),
+    ("Changes", "Changed"),
+    ("Deprecations", "Deprecated"),
+    ("Removals", "Removed"),
+    ("Breaking Changes", "Changed"),
+    ("Security Fixes", "Security"),
+];
+
+/// Sections that don't describe changes, and are dropped
+const DROPPED_SECTIONS: [&str; 2] = ["New Contributors", "Contributors"];
+
+/// Rewrite a changelog of another tool into the structure of this tool: versions are
+/// "## [1.2.3] - <date>" headings, sections are "### Added", "### Fixed", ... headings with "- "
+/// entries, and the links of the versions are references at the bottom. Versions without a link
+/// get one based on the `url` of the repo, when it's known.
+pub fn normalize(contents: &str, tool: Tool, config: &Config, url: Option<&str>) -> String {
+    let tool = match tool {
+        Tool::Auto => Tool::detect(contents),
+        tool => tool,
+    };
+
+    let mut lines: Vec<String> = vec![];
+    let mut references: Vec<(String, String)> = vec![];
+    let mut versions: Vec<String> = vec![];
+    let mut current_version: Option<String> = None;
+    let mut dropping = false;
+    let mut in_code = false;
+
+    for line in contents.lines() {
+        let trimmed = line.trim_end();
+
+        // Fenced code is kept as-is
+        let is_fence =
+            trimmed.trim_start().starts_with("```") || trimmed.trim_start().starts_with("~~~");
+        if in_code || is_fence {
+            in_code = in_code != is_fence;
+            if !dropping {
+                lines.push(trimmed.to_string());
+            }
+            continue;
+        }
+
+        if let Some(heading) = heading(trimmed) {
+            let (level, text) = heading;
+            dropping = false;
+
+            if DROPPED_SECTIONS
+                .iter()
+                .any(|section| section.eq_ignore_ascii_case(text))
+            {
+                dropping = true;
+                continue;
+            }
+
+            if let Some((version, date, link)) = version_heading(text) {
+                if let Some(link) = link {
+                    references.push((version.clone(), link));
+                }
+
+                lines.push(match date {
+                    Some(date) => format!("## [{}] - {}", version, date),
+                    None => format!("## [{}]", version),
+                });
+                versions.push(version.clone());
+                current_version = Some(version);
+                continue;
+            }
+
+            // Only section headings are aliased, other headings keep their level
+            match section_alias(text, config) {
+                Some(section) if level > 1 => {
+                    lines.push(format!("### {}", config.section_case.apply(section)))
+                }
+                _ if level == 3 => lines.push(format!("### {}", section_text(text))),
+                _ => lines.push(trimmed.to_string()),
+            }
+            continue;
+        }
+
+        // GitHub puts the compare link of a release at the bottom of its notes
+        if let Some(link) = trimmed.strip_prefix("**Full Changelog**: ") {
+            if let Some(version) = &current_version {
+                references.push((version.clone(), link.trim().to_string()));
+            }
+            continue;
+        }
+
+        if dropping {
+            continue;
+        }
+
+        let spaces = trimmed.chars().take_while(|c| c.is_whitespace()).count();
+        match trimmed.trim_start().strip_prefix("* ") {
+            Some(entry) => lines.push(format!(
+                "{}- {}",
+                " ".repeat(spaces),
+                match tool {
+                    Tool::GitHub => github_entry(entry),
+                    _ => entry.to_string(),
+                }
+            )),
+            None => lines.push(trimmed.to_string()),
+        }
+    }
+
+    // Separate every block with a blank line, like the rest of the changelog
+    let mut result: Vec<String> = vec![];
+    let mut in_code = false;
+    for line in lines {
+        let is_fence = line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~");
+        if in_code || is_fence {
+            in_code = in_code != is_fence;
+            result.push(line);
+            continue;
+        }
+
+        let is_block = line.starts_with('#');
+        let previous_is_block = result.last().map(|previous| previous.starts_with('#'));
+
+        if (is_block || previous_is_block == Some(true))
+            && result.last().map(|previous| !previous.is_empty()) == Some(true)
+        {
+            result.push(String::new());
+        }
+
+        if line.is_empty() && result.last().map(|previous| previous.is_empty()) == Some(true) {
+            continue;
+        }
+
+        result.push(line);
+    }
+
+    if !result.iter().any(|line| line.starts_with("# ")) {
+        result.splice(0..0, ["# Changelog".to_string(), String::new()]);
+    }
+
+    if let Some(url) = url {
+        for (version, link) in seeded_references(&versions, url, config) {
+            if !references
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case(&version))
+            {
+                references.push((version, link));
+            }
+        }
+        references.sort_by_key(|(name, _)| {
+            versions
+                .iter()
+                .position(|version| version.eq_ignore_ascii_case(name))
+        });
+    }
+
+    let contents = result.join("\n");
+    let missing_references: Vec<_> = references
+        .into_iter()
+        .filter(|(version, _)| !contents.contains(&format!("\n[{}]: ", version.to_lowercase())))
+        .filter(|(version, _)| !contents.contains(&format!("\n[{}]: ", version)))
+        .map(|(version, link)| format!("[{}]: {}", version, link))
+        .collect();
+
+    match missing_references.is_empty() {
+        true => contents.trim_end().to_string(),
+        false => format!(
+            "{}\n\n{}",
+            contents.trim_end(),
+            missing_references.join("\n")
+        ),
+    }
+}
+
+/// The level and text of a markdown heading
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+
+    match level {
+        1..=6 => line[level..]
+            .strip_prefix(' ')
+            .map(|text| (level, text.trim())),
+        _ => None,
+    }
+}
+
+/// The references of the versions, based on the url of the repo and the tags of the versions,
+/// e.g.: "<url>/compare/v1.0.0...v1.1.0". Versions of a package (e.g.: "pkg@v1.1.0") are compared
+/// with the previous version of the same package.
+fn seeded_references(versions: &[String], url: &str, config: &Config) -> Vec<(String, String)> {
+    let package = |version: &str| match version.split_once(" - ") {
+        Some((_, package)) if is_unreleased(version) => package.to_string(),
+        _ => version
+            .rsplit_once('@')
+            .map_or(String::new(), |(package, _)| package.to_string()),
+    };
+    let tag = |version: &str| match version.contains('@') {
+        true => version.to_string(),
+        false => config.tag(version, None),
+    };
+
+    versions
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, version)| {
+            let previous = versions[idx + 1..]
+                .iter()
+                .find(|other| !is_unreleased(other) && package(other) == package(version));
+
+            let link = match (is_unreleased(version), previous) {
+                (true, Some(previous)) => format!("{}/compare/{}...HEAD", url, tag(previous)),
+                (true, None) => return None,
+                (false, Some(previous)) => {
+                    format!("{}/compare/{}...{}", url, tag(previous), tag(version))
+                }
+                (false, None) => format!("{}/releases/tag/{}", url, tag(version)),
+            };
+
+            let name = match is_unreleased(version) {
+                true => version.to_lowercase(),
+                false => version.to_string(),
+            };
+            Some((name, link))
+        })
+        .collect()
+}
+
+/// Whether the name is the (optionally scoped) unreleased version, e.g.: "Unreleased - pkg"
+fn is_unreleased(name: &str) -> bool {
+    name.to_lowercase().starts_with("unreleased")
+}
+
+/// The version, date and link of version headings like "[1.2.3] - 2022-01-01",
+/// "[1.2.3](https://...) - 2022-01-01", "v1.2.3 (2022-01-01)" or "[unreleased]". Versions of a
+/// package (e.g.: "[pkg@v1.2.3]" or "[Unreleased - pkg]") keep their name.
+fn version_heading(text: &str) -> Option<(String, Option<String>, Option<String>)> {
+    let (name, rest) = match text.strip_prefix('[') {
+        Some(text) => {
+            let (name, rest) = text.split_once(']')?;
+            (name, rest)
+        }
+        None => text.split_once(' ').unwrap_or((text, "")),
+    };
+
+    let (link, rest) = match rest.strip_prefix('(') {
+        Some(rest) if rest.starts_with("http") => {
+            let (link, rest) = rest.split_once(')')?;
+            (Some(link.to_string()), rest)
+        }
+        _ => (None, rest),
+    };
+
+    let name = name.trim();
+    let version = match name.split_once(" - ") {
+        Some((unreleased, package)) if unreleased.eq_ignore_ascii_case("unreleased") => {
+            format!("Unreleased - {}", package.trim())
+        }
+        _ if name.eq_ignore_ascii_case("unreleased") => "Unreleased".to_string(),
+        _ => match name.rsplit_once('@') {
+            Some((package, version)) if !package.is_empty() => {
+                version.trim_start_matches('v').parse::<Version>().ok()?;
+                name.to_string()
+            }
+            _ => {
+                let version = name.trim_start_matches('v');
+                version.parse::<Version>().ok()?;
+                version.to_string()
+            }
+        },
+    };
+
+    let date = rest
+        .trim()
+        .trim_start_matches('-')
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim();
+    let date = (!date.is_empty()).then(|| date.to_string());
+
+    Some((version, date, link))
+}
+
+/// The text of a section heading, without the emoji or sorting comment that git-cliff can prefix
+/// sections with
+fn section_text(name: &str) -> &str {
+    name.trim_start_matches(|c: char| !c.is_alphanumeric())
+        .trim()
+}
+
+/// The section of this tool for a section name of another tool. The configured sections are kept
+/// as-is, e.g.: a custom "Features" section isn't renamed to "Added".
+fn section_alias<'a>(name: &'a str, config: &'a Config) -> Option<&'a str> {
+    let name = section_text(name);
+
+    config
+        .section_order
+        .iter()
+        .find(|section| section.eq_ignore_ascii_case(name))
+        .map(|section| section.as_str())
+        .or_else(|| {
+            SECTION_ALIASES
+                .iter()
+                .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+                .map(|(_, section)| *section)
+        })
+}
+
+/// Rewrite a GitHub release notes entry like "Title by @user in https://github.com/o/r/pull/12"
+/// into "Title ([#12](https://github.com/o/r/pull/12))"
+fn github_entry(entry: &str) -> String {
+    let parsed = entry.rsplit_once(" in https://").and_then(|(rest, url)| {
+        let url = format!("https://{}", url.trim());
+        let number = url.rsplit('/').next()?.parse::<usize>().ok()?;
+        let title = match rest.rsplit_once(" by @") {
+            Some((title, _)) => title,
+            None => rest,
+        };
+
+        Some(format!("{} ([#{}]({}))", title.trim(), number, url))
+    });
+
+    parsed.unwrap_or_else(|| entry.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_normalize_git_cliff_changelogs() {
+        let contents = "# Changelog\n\n## [unreleased]\n\n### Bug Fixes\n\n- Fix the parser\n\n## [1.0.0](https://github.com/o/r/releases/tag/v1.0.0) - 2021-07-18\n\n### ⛰️  Features\n\n- Add tables\n";
+
+        assert_eq!(
+            normalize(contents, Tool::Auto, &Config::default(), None),
+            "# Changelog\n\n## [Unreleased]\n\n### Fixed\n\n- Fix the parser\n\n## [1.0.0] - 2021-07-18\n\n### Added\n\n- Add tables\n\n[1.0.0]: https://github.com/o/r/releases/tag/v1.0.0"
+        );
+    }
+
+    #[test]
+    fn it_should_normalize_github_release_notes() {
+        let contents = "## v1.1.0\n## What's Changed\n* Add tables by @octocat in https://github.com/o/r/pull/12\n\n## New Contributors\n* @octocat made their first contribution in https://github.com/o/r/pull/12\n\n**Full Changelog**: https://github.com/o/r/compare/v1.0.0...v1.1.0\n";
+
+        assert_eq!(
+            normalize(contents, Tool::Auto, &Config::default(), None),
+            "# Changelog\n\n## [1.1.0]\n\n### Changed\n\n- Add tables ([#12](https://github.com/o/r/pull/12))\n\n[1.1.0]: https://github.com/o/r/compare/v1.0.0...v1.1.0"
+        );
+    }
+
+    #[test]
+    fn it_should_keep_scoped_versions_sub_headings_and_code() {
+        let contents = concat!(
+            "# Changelog\n\n## [Unreleased - pkg]\n\n### Features\n\n- A\n\n",
+            "## [pkg@v1.2.3] - 2022-01-01\n\n### Bug Fixes\n\n#### API\n\n- B\n\n",
+            "```sh\n## not a heading\n* not an entry\n```\n\n## [1.0.0]\n\n## Notes\n\n- C\n"
+        );
+        let config = Config {
+            section_order: vec!["Features".to_string(), "Fixed".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            normalize(
+                contents,
+                Tool::GitCliff,
+                &config,
+                Some("https://github.com/o/r")
+            ),
+            concat!(
+                "# Changelog\n\n## [Unreleased - pkg]\n\n### Features\n\n- A\n\n",
+                "## [pkg@v1.2.3] - 2022-01-01\n\n### Fixed\n\n#### API\n\n- B\n\n",
+                "```sh\n## not a heading\n* not an entry\n```\n\n## [1.0.0]\n\n## Notes\n\n- C\n\n",
+                "[unreleased - pkg]: https://github.com/o/r/compare/pkg@v1.2.3...HEAD\n",
+                "[pkg@v1.2.3]: https://github.com/o/r/releases/tag/pkg@v1.2.3\n",
+                "[1.0.0]: https://github.com/o/r/releases/tag/v1.0.0"
+            )
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use crate::markdown::ast::{parse_node, Node};
+use crate::markdown::tokens::continues;
+use crate::MarkdownToken;
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+
+/// Lex the tokens of a markdown document while reading it, one group of lines at a time. This
+/// produces the exact same tokens as `MarkdownToken::lex` on the full contents.
+pub struct TokenStream<R: BufRead> {
+    reader: R,
+    buffer: String,
+    pending: Option<String>,
+    tokens: VecDeque<MarkdownToken>,
+    error: Option<io::Error>,
+    done: bool,
+}
+
+impl<R: BufRead> TokenStream<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            pending: None,
+            tokens: VecDeque::new(),
+            error: None,
+            done: false,
+        }
+    }
+
+    /// Read lines until we have a complete group (separated by an empty line), and lex the
+    /// previous group once we know that this one doesn't continue it.
+    fn read_group(&mut self) -> io::Result<()> {
+        loop {
+            if let Some(idx) = self.buffer.find("\n\n") {
+                let group: String = self.buffer.drain(..idx + 2).collect();
+                self.push_group(group[..idx].to_string());
+                return Ok(());
+            }
+
+            if self.reader.read_line(&mut self.buffer)? == 0 {
+                self.done = true;
+                let group = std::mem::take(&mut self.buffer);
+                self.push_group(group);
+                if let Some(pending) = self.pending.take() {
+                    self.tokens.extend(MarkdownToken::lex_group(&pending));
+                }
+                return Ok(());
+            }
+        }
+    }
+
+    /// Combine the group with the pending group when it continues it (e.g.: the blank lines of a
+    /// fenced code block), otherwise lex the pending group.
+    fn push_group(&mut self, group: String) {
+        match self.pending.take() {
+            Some(mut pending) if continues(&pending, &group) => {
+                pending.push_str("\n\n");
+                pending.push_str(&group);
+                self.pending = Some(pending);
+            }
+            Some(pending) => {
+                self.tokens.extend(MarkdownToken::lex_group(&pending));
+                self.pending = Some(group);
+            }
+            None => self.pending = Some(group),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for TokenStream<R> {
+    type Item = MarkdownToken;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.tokens.is_empty() && !self.done {
+            if let Err(e) = self.read_group() {
+                self.error = Some(e);
+                self.done = true;
+            }
+        }
+
+        self.tokens.pop_front()
+    }
+}
+
+/// Lazily parse a markdown document into nodes. The main (H1) heading is flattened, so that every
+/// section below it is yielded as soon as it is complete. Searching these nodes in order gives the
+/// same results as searching the fully parsed document.
+pub struct NodeStream<R: BufRead> {
+    tokens: TokenStream<R>,
+    peeked: Option<MarkdownToken>,
+    /// The definitions of reference-style links, yielded after the section they are in
+    deferred: VecDeque<MarkdownToken>,
+}
+
+impl<R: BufRead> NodeStream<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            tokens: TokenStream::new(reader),
+            peeked: None,
+            deferred: VecDeque::new(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for NodeStream<R> {
+    type Item = io::Result<Node>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(reference) = self.deferred.pop_front() {
+            return Some(Ok(Node::from_token(reference)));
+        }
+
+        // Like `Node::from_str`, a reference definition doesn't end the section it is in, only
+        // the references of the versions at the end of the document do.
+        let deferred = &mut self.deferred;
+        let mut tokens = self
+            .peeked
+            .take()
+            .into_iter()
+            .chain(&mut self.tokens)
+            .filter(|token| match token {
+                MarkdownToken::Reference(_, _) if !token.is_version_reference() => {
+                    deferred.push_back(token.clone());
+                    false
+                }
+                _ => true,
+            })
+            .peekable();
+
+        let node = loop {
+            match tokens.next() {
+                Some(MarkdownToken::H1(_)) => continue,
+                Some(token) => break Some(parse_node(token, &mut tokens)),
+                None => break None,
+            }
+        };
+
+        // The parser might have peeked at the start of the next node already
+        self.peeked = tokens.next();
+        drop(tokens);
+
+        // The document might end with reference definitions
+        let node = node.or_else(|| self.deferred.pop_front().map(Node::from_token));
+
+        match self.tokens.error.take() {
+            Some(e) => Some(Err(e)),
+            None => node.map(Ok),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn it_should_lex_the_same_tokens_as_the_full_lexer() {
+        let contents = include_str!("../../CHANGELOG.md");
+
+        assert_eq!(
+            TokenStream::new(contents.as_bytes()).collect::<Vec<_>>(),
+            MarkdownToken::lex(contents)
+        );
+    }
+
+    #[test]
+    fn it_should_lex_code_blocks_with_blank_lines_like_the_full_lexer() {
+        let contents = "## [Unreleased]\n\n- Add `foo`:\n\n  ```rust\n  foo(1);\n\n  bar(2);\n  ```\n- Add `bar`\n";
+
+        assert_eq!(
+            TokenStream::new(contents.as_bytes()).collect::<Vec<_>>(),
+            MarkdownToken::lex(contents)
+        );
+    }
+
+    #[test]
+    fn it_should_find_the_same_nodes_as_the_full_parser() {
+        let contents = include_str!("../../CHANGELOG.md");
+        let root = Node::from_str(contents).unwrap();
+        let nodes = NodeStream::new(contents.as_bytes())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        let is_h2 = |node: &Node| matches!(&node.data, Some(MarkdownToken::H2(_)));
+        assert_eq!(
+            nodes
+                .iter()
+                .flat_map(|node| node.filter_nodes(is_h2))
+                .collect::<Vec<_>>(),
+            root.filter_nodes(is_h2)
+        );
+
+        let is_reference = |node: &Node| matches!(&node.data, Some(MarkdownToken::Reference(..)));
+        assert_eq!(
+            nodes
+                .iter()
+                .flat_map(|node| node.filter_nodes(is_reference))
+                .collect::<Vec<_>>(),
+            root.filter_nodes(is_reference)
+        );
+    }
+
+    #[test]
+    fn it_should_not_end_a_section_at_a_reference_definition() {
+        let contents = "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- Add `foo` ([docs][1])\n\n[1]: https://example.com/docs\n\n- Add `bar`\n\n### Fixed\n\n- Fix `baz`\n\n## [1.0.0] - 2024-01-01\n\n- Everything!\n\n[unreleased]: https://github.com/org/repo/compare/v1.0.0...HEAD\n[1.0.0]: https://github.com/org/repo/releases/tag/v1.0.0\n";
+        let root = Node::from_str(contents).unwrap();
+        let nodes = NodeStream::new(contents.as_bytes())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        let is_h2 = |node: &Node| matches!(&node.data, Some(MarkdownToken::H2(_)));
+        assert_eq!(
+            nodes
+                .iter()
+                .flat_map(|node| node.filter_nodes(is_h2))
+                .collect::<Vec<_>>(),
+            root.filter_nodes(is_h2)
+        );
+        assert_eq!(
+            nodes.iter().find(|node| is_h2(node)).unwrap().to_string(),
+            "## [Unreleased]\n\n### Added\n\n- Add `foo` ([docs][1])\n- Add `bar`\n\n### Fixed\n\n- Fix `baz`\n"
+        );
+
+        let is_reference = |node: &Node| matches!(&node.data, Some(MarkdownToken::Reference(..)));
+        assert_eq!(
+            nodes
+                .iter()
+                .flat_map(|node| node.filter_nodes(is_reference))
+                .collect::<Vec<_>>(),
+            root.filter_nodes(is_reference)
+        );
+    }
+}
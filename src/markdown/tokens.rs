@@ -1,59 +1,230 @@
+use crate::package::Version;
 use std::fmt::Display;
+use std::ops::Range;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum MarkdownToken {
     H1(String),
     H2(String),
     H3(String),
+    H4(String),
     Paragraph(String),
     UnorderedList,
     ListItem(String, usize),
     Reference(String, String),
+    /// The rows of a GFM table, e.g.: a compatibility matrix. The rows are kept verbatim.
+    Table(Vec<String>),
     BlankLine,
 }
 
 impl MarkdownToken {
+    /// Whether the token is the reference of a version (e.g.: "[1.0.0]: https://..."), instead of
+    /// the definition of a reference-style link in an entry (e.g.: "[1]: https://...")
+    pub fn is_version_reference(&self) -> bool {
+        match self {
+            MarkdownToken::Reference(name, _) => {
+                let number = name
+                    .rsplit_once('@')
+                    .map_or(name.as_str(), |(_, number)| number);
+
+                name.to_lowercase().starts_with("unreleased")
+                    || number.trim_start_matches('v').parse::<Version>().is_ok()
+            }
+            _ => false,
+        }
+    }
+
     /// Convert each line to a proper MarkdownToken
     pub fn lex(contents: &str) -> Vec<MarkdownToken> {
-        contents
-            .split("\n\n")
-            .filter(|line| !line.is_empty())
-            .flat_map(|group| match &group.trim()[..1] {
-                "#" | "-" | "[" => group
-                    .lines()
-                    .map(|line| {
-                        let spaces = line.chars().take_while(|c| c.is_whitespace()).count();
-                        let l = line.trim_start();
-                        match l {
-                            line if line.starts_with("# ") => {
-                                MarkdownToken::H1(line[2..].to_string())
-                            }
-                            line if line.starts_with("## ") => {
-                                MarkdownToken::H2(line[3..].to_string())
-                            }
-                            line if line.starts_with("### ") => {
-                                MarkdownToken::H3(line[4..].to_string())
-                            }
-                            line if line.starts_with("- ") => {
-                                MarkdownToken::ListItem(line[2..].to_string(), spaces)
-                            }
-                            line if line.starts_with('[') => {
-                                let mut parts = line.split(": ");
-                                let name = parts.next().unwrap();
-                                let link = parts.next().unwrap();
-                                MarkdownToken::Reference(
-                                    name[1..(name.len() - 1)].to_string(),
-                                    link.to_string(),
-                                )
-                            }
-                            _ => MarkdownToken::Paragraph(l.to_string()),
-                        }
-                    })
-                    .collect(),
-                _ => vec![MarkdownToken::Paragraph(group.to_string())],
-            })
+        let mut groups: Vec<String> = vec![];
+
+        for group in contents.split("\n\n") {
+            match groups.last_mut() {
+                Some(previous) if continues(previous, group) => {
+                    previous.push_str("\n\n");
+                    previous.push_str(group);
+                }
+                _ => groups.push(group.to_string()),
+            }
+        }
+
+        groups
+            .iter()
+            .flat_map(|group| MarkdownToken::lex_group(group))
             .collect()
     }
+
+    /// Convert a single group of lines (separated by an empty line) to MarkdownTokens
+    pub fn lex_group(group: &str) -> Vec<MarkdownToken> {
+        if group.is_empty() {
+            return vec![];
+        }
+
+        match group.trim().chars().next() {
+            Some('#' | '-' | '|') => tokens(group),
+            // Text that starts with a link (e.g.: in the preamble) is not a reference
+            Some('[') if group.lines().all(is_reference) => tokens(group),
+            _ => vec![MarkdownToken::Paragraph(group.to_string())],
+        }
+    }
+}
+
+/// Whether a group belongs to the previous group, instead of starting a new one. This is the case
+/// for the blank lines of a fenced code block, and for an indented block (e.g.: code) that
+/// continues the last list item.
+pub fn continues(previous: &str, group: &str) -> bool {
+    if in_code(previous) {
+        return true;
+    }
+
+    group.starts_with(' ')
+        && !group.trim_start().starts_with("- ")
+        && previous.trim_start().starts_with("- ")
+}
+
+/// Whether the text opened a fenced code block (``` or ~~~) that isn't closed yet
+fn in_code(text: &str) -> bool {
+    text.lines()
+        .map(str::trim_start)
+        .filter(|line| line.starts_with("```") || line.starts_with("~~~"))
+        .count()
+        % 2
+        == 1
+}
+
+/// Normalize the whitespace of a markdown document without changing its contents: trailing
+/// whitespace is trimmed, multiple blank lines are collapsed into one, and headings are surrounded
+/// by exactly one blank line. Fenced code blocks are kept as-is, and so are hard breaks (two
+/// trailing spaces) inside a paragraph or list item.
+pub fn normalize_whitespace(text: &str) -> String {
+    let is_heading =
+        |line: &str| line.starts_with('#') && line.trim_start_matches('#').starts_with(' ');
+    // Whether the line continues the paragraph or list item of the line before it
+    let is_continuation = |line: &str| {
+        let line = line.trim_start();
+        let number = line.trim_start_matches(|c: char| c.is_ascii_digit());
+        let is_list_item = ["- ", "* ", "+ "].iter().any(|m| line.starts_with(m))
+            || (number.len() < line.len() && number.starts_with(". "));
+        !line.is_empty()
+            && !is_heading(line)
+            && !is_list_item
+            && !line.starts_with("```")
+            && !line.starts_with("~~~")
+    };
+    let mut lines: Vec<&str> = vec![];
+    let mut in_code = false;
+    let mut text_lines = text.lines().peekable();
+
+    while let Some(line) = text_lines.next() {
+        let is_fence = line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~");
+        if in_code {
+            lines.push(line);
+            in_code = !is_fence;
+            continue;
+        }
+        in_code = is_fence;
+
+        let trimmed = line.trim_end();
+        let trailing = &line[trimmed.len()..];
+        let hard_break = !trimmed.is_empty()
+            && !is_heading(trimmed)
+            && trailing.len() >= 2
+            && trailing.chars().all(|c| c == ' ')
+            && text_lines.peek().is_some_and(|next| is_continuation(next));
+        let line = match hard_break {
+            true => &line[..trimmed.len() + 2],
+            false => trimmed,
+        };
+        let previous = lines.last().copied();
+        let after_heading = previous.is_some_and(is_heading);
+
+        if line.is_empty() {
+            if previous.is_some_and(|previous| !previous.is_empty()) {
+                lines.push(line);
+            }
+            continue;
+        }
+
+        if (is_heading(line) || after_heading) && previous.is_some_and(|p| !p.is_empty()) {
+            lines.push("");
+        }
+        lines.push(line);
+    }
+
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    match lines.is_empty() {
+        true => String::new(),
+        false => format!("{}\n", lines.join("\n")),
+    }
+}
+
+/// Remove up to `amount` spaces of indentation, deeper indentation (e.g.: of code) is kept
+fn dedent(line: &str, amount: usize) -> &str {
+    let spaces = line.len() - line.trim_start_matches(' ').len();
+    &line[spaces.min(amount)..]
+}
+
+/// Whether the line is a row of a table, e.g.: "| 1.x | >=16 |"
+fn is_table_row(line: &str) -> bool {
+    line.trim_start().starts_with('|')
+}
+
+/// Whether the line is a reference definition, e.g.: "[1.0.0]: https://..."
+fn is_reference(line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix('[')
+        .and_then(|line| line.split_once("]: "))
+        .is_some_and(|(name, _)| !name.contains(']'))
+}
+
+/// The tokens of a group of headings, list items or references
+fn tokens(group: &str) -> Vec<MarkdownToken> {
+    group
+        .lines()
+        .fold(vec![], |mut tokens: Vec<MarkdownToken>, line| {
+            let spaces = line.chars().take_while(|c| c.is_whitespace()).count();
+            let l = line.trim_start();
+
+            // Indented lines, blank lines and code blocks continue the text of the previous list
+            // item. The contents of code blocks are kept verbatim.
+            if let Some(MarkdownToken::ListItem(text, indent)) = tokens.last_mut() {
+                if in_code(text) || l.is_empty() || (spaces > *indent && !l.starts_with("- ")) {
+                    text.push('\n');
+                    text.push_str(dedent(line, *indent + 2));
+                    return tokens;
+                }
+
+                text.truncate(text.trim_end_matches('\n').len());
+            }
+
+            if is_table_row(line) {
+                match tokens.last_mut() {
+                    Some(MarkdownToken::Table(rows)) => rows.push(line.to_string()),
+                    _ => tokens.push(MarkdownToken::Table(vec![line.to_string()])),
+                }
+
+                return tokens;
+            }
+
+            tokens.push(match l {
+                line if line.starts_with("# ") => MarkdownToken::H1(line[2..].to_string()),
+                line if line.starts_with("## ") => MarkdownToken::H2(line[3..].to_string()),
+                line if line.starts_with("### ") => MarkdownToken::H3(line[4..].to_string()),
+                line if line.starts_with("#### ") => MarkdownToken::H4(line[5..].to_string()),
+                line if line.starts_with("- ") => {
+                    MarkdownToken::ListItem(line[2..].to_string(), spaces)
+                }
+                line if is_reference(line) => {
+                    let (name, link) = line[1..].split_once("]: ").unwrap();
+                    MarkdownToken::Reference(name.to_string(), link.to_string())
+                }
+                _ => MarkdownToken::Paragraph(l.to_string()),
+            });
+            tokens
+        })
 }
 
 impl Display for MarkdownToken {
@@ -62,13 +233,429 @@ impl Display for MarkdownToken {
             MarkdownToken::H1(line) => writeln!(f, "# {}", line),
             MarkdownToken::H2(line) => writeln!(f, "## {}", line),
             MarkdownToken::H3(line) => writeln!(f, "### {}", line),
+            MarkdownToken::H4(line) => writeln!(f, "#### {}", line),
             MarkdownToken::Paragraph(line) => writeln!(f, "{}", line),
             MarkdownToken::UnorderedList => Ok(()),
             MarkdownToken::ListItem(line, indent) => {
-                write!(f, "{}- {}", " ".repeat(*indent), line)
+                let indent = " ".repeat(*indent);
+                let mut lines = line.split('\n');
+
+                write!(f, "{}- {}", indent, lines.next().unwrap_or_default())?;
+                for line in lines {
+                    match line.is_empty() {
+                        true => writeln!(f)?,
+                        false => write!(f, "\n{}  {}", indent, line)?,
+                    }
+                }
+
+                Ok(())
             }
             MarkdownToken::Reference(name, link) => write!(f, "[{}]: {}", name, link),
+            MarkdownToken::Table(rows) => writeln!(f, "{}", rows.join("\n")),
             MarkdownToken::BlankLine => write!(f, ""),
         }
     }
 }
+
+/// Where a link points to: an inline url, or the label of a reference definition
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Target<'a> {
+    Url(&'a str),
+    Reference(&'a str),
+}
+
+/// Replace markdown links (`[text](url)` and `[text][label]`) with their text
+pub fn strip_links(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+
+    while let Some((range, link_text, _)) = next_link(rest) {
+        result.push_str(&rest[..range.start]);
+        result.push_str(link_text);
+        rest = &rest[range.end..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Wrap the mentions of users and teams (e.g.: "@octocat" or "@org/maintainers") in backticks, so
+/// that posting the text on GitHub doesn't notify them. Mentions in code, links and email addresses
+/// are kept as-is.
+pub fn escape_mentions(text: &str) -> String {
+    let mut result = String::new();
+    let mut in_code = false;
+    let mut previous: Option<char> = None;
+    let mut idx = 0;
+
+    while let Some(c) = text[idx..].chars().next() {
+        let length = match c {
+            '@' if !in_code && previous.is_none_or(|p| p.is_whitespace() || p == '(') => {
+                mention_length(&text[idx + 1..])
+            }
+            _ => 0,
+        };
+
+        if length > 0 {
+            result.push('`');
+            result.push_str(&text[idx..=idx + length]);
+            result.push('`');
+            previous = Some('`');
+            idx += length + 1;
+            continue;
+        }
+
+        if c == '`' {
+            in_code = !in_code;
+        }
+
+        result.push(c);
+        previous = Some(c);
+        idx += c.len_utf8();
+    }
+
+    result
+}
+
+/// The length of the login (and team) of a mention without the "@", e.g.: "org/team" of
+/// "@org/team and more"
+fn mention_length(text: &str) -> usize {
+    let name = |text: &str| {
+        text.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(text.len())
+    };
+    let login = name(text);
+
+    match text[login..].strip_prefix('/') {
+        Some(team) if login > 0 && name(team) > 0 => login + 1 + name(team),
+        _ => login,
+    }
+}
+
+/// Remove the emoji (e.g.: gitmoji like "✨" or ":sparkles:") from a text, together with the
+/// space that separated them from the rest of the text
+pub fn strip_emoji(text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            line.split(' ')
+                .filter(|word| !is_shortcode(word))
+                .filter_map(|word| {
+                    let stripped = strip_emoji_chars(word);
+                    (word.is_empty() || !stripped.is_empty()).then_some(stripped)
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Remove the emoji characters of a word, including the rest of their sequence: variation
+/// selectors, skin tones, keycaps and emoji that are joined with a zero width joiner
+fn strip_emoji_chars(word: &str) -> String {
+    let mut result = String::new();
+    let mut chars = word.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if !is_emoji(c) {
+            result.push(c);
+            continue;
+        }
+
+        while chars
+            .next_if(|&c| is_emoji(c) || is_emoji_modifier(c))
+            .is_some()
+        {}
+    }
+
+    result
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1F000}'..='\u{1FAFF}'
+            | '\u{2600}'..='\u{27BF}'
+            | '\u{231A}'..='\u{231B}'
+            | '\u{2328}'
+            | '\u{23CF}'
+            | '\u{23E9}'..='\u{23FA}'
+            | '\u{2B05}'..='\u{2B07}'
+            | '\u{2B1B}'..='\u{2B1C}'
+            | '\u{2B50}'
+            | '\u{2B55}'
+    )
+}
+
+/// The characters that modify the preceding emoji, they are only removed as part of an emoji
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(
+        c,
+        '\u{FE0E}' | '\u{FE0F}' | '\u{200D}' | '\u{20E3}' | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+/// Whether a word is an emoji code, e.g.: ":sparkles:"
+fn is_shortcode(word: &str) -> bool {
+    word.strip_prefix(':')
+        .and_then(|word| word.strip_suffix(':'))
+        .is_some_and(|code| {
+            code.starts_with(|c: char| c.is_ascii_lowercase())
+                && code
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "_+-".contains(c))
+        })
+}
+
+/// The inline markdown links (`[text](url)`) of a text, as (text, url) pairs
+pub fn links(text: &str) -> Vec<(&str, &str)> {
+    all_links(text)
+        .into_iter()
+        .filter_map(|(link_text, target)| match target {
+            Target::Url(url) => Some((link_text, url)),
+            Target::Reference(_) => None,
+        })
+        .collect()
+}
+
+/// The reference-style markdown links (`[text][label]`) of a text, as (text, label) pairs. The
+/// label of a collapsed link (`[text][]`) is its text.
+pub fn reference_links(text: &str) -> Vec<(&str, &str)> {
+    all_links(text)
+        .into_iter()
+        .filter_map(|(link_text, target)| match target {
+            Target::Reference(label) => Some((link_text, label)),
+            Target::Url(_) => None,
+        })
+        .collect()
+}
+
+fn all_links(text: &str) -> Vec<(&str, Target<'_>)> {
+    let mut result = vec![];
+    let mut rest = text;
+
+    while let Some((range, link_text, target)) = next_link(rest) {
+        result.push((link_text, target));
+        rest = &rest[range.end..];
+    }
+
+    result
+}
+
+/// The first markdown link of a text, as its byte range, text and target
+fn next_link(text: &str) -> Option<(Range<usize>, &str, Target<'_>)> {
+    let mut offset = 0;
+
+    while let Some(start) = text[offset..].find('[') {
+        let start = offset + start;
+        let candidate = &text[start + 1..];
+
+        let link = candidate.find(']').and_then(|close| {
+            let rest = &candidate[close + 1..];
+            if let Some(url) = rest.strip_prefix('(') {
+                let end = url.find(')')?;
+                return Some((close, end, Target::Url(&url[..end])));
+            }
+
+            let label = rest.strip_prefix('[')?;
+            let end = label.find(']')?;
+            match end {
+                0 => Some((close, end, Target::Reference(&candidate[..close]))),
+                _ => Some((close, end, Target::Reference(&label[..end]))),
+            }
+        });
+
+        if let Some((close, length, target)) = link {
+            let end = start + close + length + 4;
+            return Some((start..end, &candidate[..close], target));
+        }
+
+        offset = start + 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::ast::Node;
+    use std::str::FromStr;
+
+    #[test]
+    fn it_should_lex_multi_line_list_items() {
+        let tokens = MarkdownToken::lex("- First line\n  continued here\n  - Nested\n- Second");
+
+        assert_eq!(
+            tokens,
+            vec![
+                MarkdownToken::ListItem("First line\ncontinued here".to_string(), 0),
+                MarkdownToken::ListItem("Nested".to_string(), 2),
+                MarkdownToken::ListItem("Second".to_string(), 0),
+            ]
+        );
+        assert_eq!(tokens[0].to_string(), "- First line\n  continued here");
+    }
+
+    #[test]
+    fn it_should_keep_code_blocks_in_list_items_verbatim() {
+        let contents = "- Add the `foo` API:\n  ```rust\n  fn main() {\n      foo(1);\n\n      - not a list\n  }\n  ```\n- Add `bar`:\n\n  ```sh\n  bar --baz\n  ```";
+        let tokens = MarkdownToken::lex(contents);
+
+        assert_eq!(
+            tokens,
+            vec![
+                MarkdownToken::ListItem(
+                    "Add the `foo` API:\n```rust\nfn main() {\n    foo(1);\n\n    - not a list\n}\n```"
+                        .to_string(),
+                    0
+                ),
+                MarkdownToken::ListItem("Add `bar`:\n\n```sh\nbar --baz\n```".to_string(), 0),
+            ]
+        );
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            contents
+        );
+    }
+
+    #[test]
+    fn it_should_keep_tables_verbatim() {
+        let contents = "## [Unreleased]\n| Version | Node |\n| ------- | :--: |\n| 1.x     | >=16 |\n\n### Added\n\n- Add tables\n| [Docs](https://example.com) | Yes |\n|---|---|\n\n| A |\n| - |\n";
+        let node = Node::from_str(contents).unwrap();
+
+        assert_eq!(
+            node.find_node(|node| matches!(&node.data, Some(MarkdownToken::Table(_))))
+                .and_then(|node| node.data.clone()),
+            Some(MarkdownToken::Table(vec![
+                "| Version | Node |".to_string(),
+                "| ------- | :--: |".to_string(),
+                "| 1.x     | >=16 |".to_string(),
+            ]))
+        );
+        assert_eq!(
+            node.to_string(),
+            "## [Unreleased]\n\n| Version | Node |\n| ------- | :--: |\n| 1.x     | >=16 |\n\n### Added\n\n- Add tables\n\n| [Docs](https://example.com) | Yes |\n|---|---|\n\n| A |\n| - |\n"
+        );
+
+        // Rewriting it again doesn't change anything
+        assert_eq!(
+            Node::from_str(&node.to_string()).unwrap().to_string(),
+            node.to_string()
+        );
+    }
+
+    #[test]
+    fn it_should_lex_text_starting_with_a_link_as_a_paragraph() {
+        let tokens = MarkdownToken::lex(
+            "[Keep a Changelog](https://keepachangelog.com) rules apply: newest first.\n\n[1.0.0]: https://github.com/o/r/releases/tag/v1.0.0",
+        );
+
+        assert_eq!(
+            tokens,
+            vec![
+                MarkdownToken::Paragraph(
+                    "[Keep a Changelog](https://keepachangelog.com) rules apply: newest first."
+                        .to_string()
+                ),
+                MarkdownToken::Reference(
+                    "1.0.0".to_string(),
+                    "https://github.com/o/r/releases/tag/v1.0.0".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_find_links() {
+        assert_eq!(
+            links("Add [tables](https://example.com) ([#12](https://github.com/org/repo/pull/12))"),
+            vec![
+                ("tables", "https://example.com"),
+                ("#12", "https://github.com/org/repo/pull/12")
+            ]
+        );
+        assert!(links("Support [brackets]").is_empty());
+    }
+
+    #[test]
+    fn it_should_find_reference_links() {
+        assert_eq!(
+            reference_links("Fix [the parser][1] and [tables][], see [docs](https://example.com)"),
+            vec![("the parser", "1"), ("tables", "tables")]
+        );
+        assert_eq!(
+            links("Fix [the parser][1], see [docs](https://example.com)"),
+            vec![("docs", "https://example.com")]
+        );
+    }
+
+    #[test]
+    fn it_should_normalize_whitespace() {
+        assert_eq!(
+            normalize_whitespace(concat!(
+                "\n# Changelog  \n## [Unreleased]\n\n\n\n- A \n  more\t\n\n",
+                "```\ncode  \n\n\n```\n### Added\n- B\n#123 C\n\n\n"
+            )),
+            concat!(
+                "# Changelog\n\n## [Unreleased]\n\n- A\n  more\n\n",
+                "```\ncode  \n\n\n```\n\n### Added\n\n- B\n#123 C\n"
+            )
+        );
+        assert_eq!(
+            normalize_whitespace("- A   \n  more  \n- B  \n\nC \t\nD    \nE  \n"),
+            "- A  \n  more\n- B\n\nC\nD  \nE\n"
+        );
+    }
+
+    #[test]
+    fn it_should_escape_mentions() {
+        assert_eq!(
+            escape_mentions("Add tables, thanks @octocat and @org/maintainers."),
+            "Add tables, thanks `@octocat` and `@org/maintainers`."
+        );
+        assert_eq!(
+            escape_mentions("Mail a@example.com, see [@octocat](https://github.com/octocat)"),
+            "Mail a@example.com, see [@octocat](https://github.com/octocat)"
+        );
+        assert_eq!(
+            escape_mentions("Keep `@octocat` (@me) and @ alone"),
+            "Keep `@octocat` (`@me`) and @ alone"
+        );
+    }
+
+    #[test]
+    fn it_should_strip_emoji() {
+        assert_eq!(strip_emoji("✨ Add tables"), "Add tables");
+        assert_eq!(strip_emoji(":bug: Fix the parser 🐛"), "Fix the parser");
+        assert_eq!(strip_emoji("♻️ Refactor 👩🏽‍💻 code"), "Refactor code");
+        assert_eq!(
+            strip_emoji("Support ⌘K in Ωmega, naïve 日本語 and 10:30:00"),
+            "Support ⌘K in Ωmega, naïve 日本語 and 10:30:00"
+        );
+        assert_eq!(
+            strip_emoji("🔥\n```\n    code\n```"),
+            "\n```\n    code\n```"
+        );
+    }
+
+    #[test]
+    fn it_should_strip_links() {
+        assert_eq!(
+            strip_links("Add tables ([#12](https://github.com/org/repo/pull/12))"),
+            "Add tables (#12)"
+        );
+        assert_eq!(
+            strip_links("Support [brackets] and [links](https://example.com)"),
+            "Support [brackets] and links"
+        );
+        assert_eq!(
+            strip_links("Fix [the parser][1] and [tables][]"),
+            "Fix the parser and tables"
+        );
+    }
+}
@@ -1,6 +1,9 @@
+use crate::error::{ChangelogError, Result};
+use crate::shell::Shell;
 use crate::SemVer;
-use color_eyre::eyre::{eyre, Result};
+use std::path::Path;
 use std::process::Command;
+use std::time::Instant;
 
 #[derive(Debug)]
 pub struct Npm {
@@ -9,8 +12,15 @@ pub struct Npm {
 
 pub struct Options {
     pub no_git_tag_version: bool,
+
+    /// A command that runs instead of `npm version`, with a `{version}` placeholder, e.g.:
+    /// "pnpm version {version} --no-git-tag-version"
+    pub command: Option<String>,
 }
 
+/// The lockfiles that a version command might update
+pub const LOCKFILES: [&str; 3] = ["package-lock.json", "yarn.lock", "pnpm-lock.yaml"];
+
 impl Npm {
     pub fn new(pwd: Option<&str>) -> Result<Self> {
         match pwd {
@@ -24,6 +34,13 @@ impl Npm {
     }
 
     pub fn version_options(&self, version: &SemVer, options: Options) -> Result<&Self> {
+        if let Some(command) = &options.command {
+            Shell::new(Path::new(&self.pwd))
+                .run(&command.replace("{version}", &version.to_string()))?;
+
+            return Ok(self);
+        }
+
         self.exec(vec![
             "version",
             &version.to_string(),
@@ -41,11 +58,21 @@ impl Npm {
 
         cmd.current_dir(&self.pwd);
 
-        for arg in args {
+        for arg in &args {
             cmd.arg(arg);
         }
 
-        match cmd.output() {
+        let start = Instant::now();
+        let output = cmd.output();
+
+        tracing::debug!(
+            args = %args.join(" "),
+            status = ?output.as_ref().map(|output| output.status.code()),
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "npm"
+        );
+
+        match output {
             Ok(output) => {
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 let stdout = stdout.trim();
@@ -53,7 +80,10 @@ impl Npm {
 
                 Ok(stdout)
             }
-            Err(e) => Err(eyre!(e)),
+            Err(e) => Err(ChangelogError::Command(format!(
+                "Failed running npm: {}",
+                e
+            ))),
         }
     }
 }
@@ -1,9 +1,52 @@
 use colored::*;
+use console::{measure_text_width, pad_str, truncate_str, Alignment, Term};
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::markdown::tokens::strip_emoji;
+
+/// Whether the output is limited to plain ASCII, for terminals and CI logs without Unicode support
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// The Unicode characters of the output, with the ASCII characters that replace them
+const ASCII_REPLACEMENTS: &[(&str, &str)] = &[
+    ("…", "..."),
+    ("—", "-"),
+    ("–", "-"),
+    ("•", "*"),
+    ("→", "->"),
+    ("‘", "'"),
+    ("’", "'"),
+    ("“", "\""),
+    ("”", "\""),
+];
+
+/// Limit all the following output to plain ASCII: the emoji are removed and the Unicode
+/// punctuation is replaced, e.g.: "…" becomes "..."
+pub fn set_ascii(ascii: bool) {
+    ASCII.store(ascii, Ordering::Relaxed);
+}
+
+/// The text as it should be printed, i.e. as plain ASCII when `set_ascii` is enabled
+fn printable(str: &str) -> String {
+    if !ASCII.load(Ordering::Relaxed) {
+        return str.to_string();
+    }
+
+    let replaced = ASCII_REPLACEMENTS
+        .iter()
+        .fold(str.to_string(), |str, (from, to)| str.replace(from, to));
+
+    strip_emoji(&replaced)
+}
 
 /// Small wrapper to have a nice output that is indented and contains a CHANGELOG header. Also
 /// playing with some eprintln so that piping it to another process or redirecting it to a file
 /// doesn't contain all the extra stuff.
 pub fn output(str: String) {
+    let str = printable(&str);
     eprintln!();
 
     if str.contains('\n') {
@@ -18,7 +61,32 @@ pub fn output(str: String) {
     eprintln!()
 }
 
+/// Output a status message, always to stderr so that it doesn't end up in piped output
+pub fn output_status(message: String) {
+    let message = printable(&message);
+    eprintln!();
+    eprintln!(
+        "  {} {}",
+        " CHANGELOG ".black().on_bright_blue().bold(),
+        message
+    );
+    eprintln!();
+}
+
+/// Output a warning, always to stderr so that it never ends up in piped output
+pub fn output_warning(message: String) {
+    let message = printable(&message);
+    eprintln!();
+    eprintln!(
+        "  {} {}",
+        " WARNING ".black().on_bright_yellow().bold(),
+        message
+    );
+    eprintln!();
+}
+
 pub fn output_title(title: String, message: String) {
+    let (title, message) = (printable(&title), printable(&message));
     eprintln!();
 
     if message.contains('\n') {
@@ -37,7 +105,168 @@ pub fn output_title(title: String, message: String) {
     eprintln!()
 }
 
+/// Output one or more titled blocks through the user's `$PAGER` (`less` by default) when stdout is
+/// a terminal, like git does. Falls back to `output_title` when paging is disabled or the pager
+/// can't be started.
+pub fn output_paged(blocks: Vec<(String, String)>, pager: bool) {
+    if pager && std::io::stdout().is_terminal() {
+        let contents: String = blocks
+            .iter()
+            .map(|(title, message)| render_title(title, message))
+            .collect();
+
+        if page(&contents).is_ok() {
+            return;
+        }
+    }
+
+    for (title, message) in blocks {
+        output_title(title, message);
+    }
+}
+
+/// The same output as `output_title`, as a single string
+fn render_title(title: &str, message: &str) -> String {
+    let (title, message) = (printable(title), printable(message));
+    let header = " CHANGELOG ".black().on_bright_blue().bold();
+
+    if message.contains('\n') {
+        let lines: String = message
+            .trim()
+            .lines()
+            .map(|line| format!("  {}\n", line))
+            .collect();
+
+        format!("\n  {} {}\n\n{}\n", header, title, lines)
+    } else {
+        format!("\n  {} {}\n\n", header, message)
+    }
+}
+
+fn page(contents: &str) -> std::io::Result<()> {
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| "less".to_string());
+    let mut parts = pager.split_whitespace();
+    let program = parts.next().unwrap_or("less");
+
+    let mut child = Command::new(program)
+        .args(parts)
+        // Quit when the output fits on one screen, keep the colors and don't clear the screen
+        .env(
+            "LESS",
+            std::env::var("LESS").unwrap_or_else(|_| "FRX".to_string()),
+        )
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(stdin) = child.stdin.as_mut() {
+        // The user can quit the pager before everything is written
+        let _ = stdin.write_all(contents.as_bytes());
+    }
+
+    child.wait().map(|_| ())
+}
+
+/// Copy text to the system clipboard with the clipboard tool of the platform. Returns false when
+/// no clipboard is available, e.g.: on a headless system.
+pub fn copy_to_clipboard(text: &str) -> bool {
+    let tools: [(&str, &[&str]); 5] = [
+        ("pbcopy", &[]),
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+        ("clip.exe", &[]),
+    ];
+
+    tools.iter().any(|(program, args)| {
+        let child = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        match child {
+            Ok(mut child) => {
+                let written = child
+                    .stdin
+                    .take()
+                    .map(|mut stdin| stdin.write_all(text.as_bytes()).is_ok())
+                    .unwrap_or(false);
+
+                child.wait().map(|status| status.success()).unwrap_or(false) && written
+            }
+            Err(_) => false,
+        }
+    })
+}
+
+/// Output rows in a stable format for scripts, like git's porcelain output: one line per row with
+/// tab separated fields, without colors. Tabs and newlines in the fields are replaced with spaces.
+pub fn output_porcelain(rows: Vec<Vec<String>>) {
+    for row in rows {
+        let fields: Vec<_> = row
+            .iter()
+            .map(|field| field.replace(['\t', '\n'], " "))
+            .collect();
+
+        println!("{}", fields.join("\t"));
+    }
+}
+
+/// Align the columns of the rows into a table, the fields are separated by a space. Rows that are
+/// wider than `max_width` are truncated. Colors don't count towards the width of a field.
+pub fn table(rows: &[Vec<String>], max_width: Option<usize>) -> String {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+    let widths: Vec<usize> = (0..columns)
+        .map(|idx| {
+            rows.iter()
+                .filter_map(|row| row.get(idx))
+                .map(|field| measure_text_width(field))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    rows.iter()
+        .map(|row| {
+            let fields: Vec<_> = row
+                .iter()
+                .enumerate()
+                .map(|(idx, field)| match idx + 1 < row.len() {
+                    true => pad_str(field, widths[idx], Alignment::Left, None),
+                    false => field.into(),
+                })
+                .collect();
+            let line = fields.join(" ");
+
+            match max_width {
+                Some(max_width) => {
+                    let tail = match ASCII.load(Ordering::Relaxed) {
+                        true => "...",
+                        false => "…",
+                    };
+
+                    truncate_str(&line, max_width, tail).to_string()
+                }
+                None => line,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The width of the terminal, when stdout is a terminal
+pub fn terminal_width() -> Option<usize> {
+    Term::stdout()
+        .size_checked()
+        .map(|(_, columns)| columns as usize)
+}
+
 pub fn output_indented(str: String) {
+    let str = printable(&str);
     let str = str.trim();
     let lines = str.lines();
     let total_lines = lines.clone().count();
@@ -51,3 +280,107 @@ pub fn output_indented(str: String) {
         }
     }
 }
+
+/// Progress of a long running batch operation, e.g.: "Releasing [2/5]". It is printed to stderr so
+/// that it doesn't end up in piped output, and it is hidden when stderr is not a terminal.
+pub struct Progress {
+    label: String,
+    total: usize,
+    completed: usize,
+    enabled: bool,
+}
+
+impl Progress {
+    pub fn new(label: &str, total: usize, quiet: bool) -> Self {
+        let progress = Self {
+            label: label.to_string(),
+            total,
+            completed: 0,
+            enabled: !quiet && std::io::stderr().is_terminal(),
+        };
+        progress.render();
+        progress
+    }
+
+    pub fn inc(&mut self) {
+        self.completed = (self.completed + 1).min(self.total);
+        self.render();
+    }
+
+    /// Remove the progress line again
+    pub fn finish(self) {
+        if self.enabled {
+            eprint!("\r\x1b[2K");
+        }
+    }
+
+    fn render(&self) {
+        if !self.enabled {
+            return;
+        }
+
+        eprint!(
+            "\r\x1b[2K  {} {} {}",
+            " CHANGELOG ".black().on_bright_blue().bold(),
+            self.label,
+            format!("[{}/{}]", self.completed, self.total)
+                .white()
+                .dimmed()
+        );
+        let _ = std::io::stderr().flush();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    Human,
+    Json,
+}
+
+impl FromStr for ErrorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(ErrorFormat::Human),
+            "json" => Ok(ErrorFormat::Json),
+            _ => Err(format!("Invalid error format: {}", s)),
+        }
+    }
+}
+
+/// Output an error that scripts might want to detect. In the json format, the error is printed as
+/// a single line with a stable `code`, and the message without any colors.
+pub fn output_error(format: ErrorFormat, code: &str, message: String) {
+    match format {
+        ErrorFormat::Human => output(message),
+        ErrorFormat::Json => eprintln!(
+            "{}",
+            serde_json::json!({
+                "error": code,
+                "message": strip_colors(&message),
+            })
+        ),
+    }
+}
+
+/// Remove the ANSI escape codes that `colored` adds
+pub fn strip_colors(str: &str) -> String {
+    let mut result = String::new();
+    let mut chars = str.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip everything up to and including the final byte of the escape sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
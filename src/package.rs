@@ -1,7 +1,11 @@
-use color_eyre::eyre::{eyre, Error, Result};
+use crate::config::{Config, VersionSource};
+use crate::error::{ChangelogError, Result};
+use crate::git::Git;
 use colored::*;
 use glob::glob;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
@@ -21,6 +25,11 @@ pub struct SemVer {
     /// A pre-release version MAY be denoted by appending a hyphen and a series of dot separated
     /// identifiers immediately following the patch version.
     pre_release: Option<String>,
+
+    /// Build metadata MAY be denoted by appending a plus sign and a series of dot separated
+    /// identifiers immediately following the patch or pre-release version. It is ignored when
+    /// determining the precedence of versions.
+    build: Option<String>,
 }
 
 impl SemVer {
@@ -30,14 +39,25 @@ impl SemVer {
             minor,
             patch,
             pre_release,
+            build: None,
         }
     }
 
-    pub fn change_to(&mut self, version: &str) -> Result<Self, Error> {
+    /// Verify that a release version is a keyword like "patch", or a full semver version. This
+    /// rejects partial versions like "1.2", and calendar versions like "2024.01".
+    pub fn verify(version: &str) -> Result<()> {
+        match version {
+            "major" | "minor" | "patch" | "final" | "release" | "infer" => Ok(()),
+            _ => version.parse::<SemVer>().map(|_| ()),
+        }
+    }
+
+    pub fn change_to(&mut self, version: &str) -> Result<Self> {
         let version = match version {
             "major" => self.new_major(),
             "minor" => self.new_minor(),
             "patch" => self.new_patch(),
+            "final" | "release" => self.new_final()?,
             "infer" => self.clone(),
             _ => version.parse::<Self>()?,
         };
@@ -46,6 +66,49 @@ impl SemVer {
 
         Ok(self.clone())
     }
+
+    /// Resolve a release version like "patch", "infer" or "1.2.3". Versions that are relative to
+    /// the current version read the current version from the given source.
+    pub fn resolve(version: &str, config: &Config, dir: &Path) -> Result<Self> {
+        match version {
+            "major" | "minor" | "patch" | "final" | "release" | "infer" => {
+                current_version(config, dir)?.change_to(version)
+            }
+            _ => version.parse(),
+        }
+    }
+}
+
+/// The current version of the project in the given directory
+pub fn current_version(config: &Config, dir: &Path) -> Result<SemVer> {
+    match config.version_source {
+        VersionSource::Package => Ok(PackageJSON::from_directory(dir)?.version),
+        VersionSource::File => {
+            let contents = std::fs::read_to_string(dir.join("VERSION")).map_err(|_| {
+                ChangelogError::NotFound(format!(
+                    "Couldn't read the version from: {}",
+                    dir.join("VERSION").display()
+                ))
+            })?;
+
+            contents.trim().trim_start_matches('v').parse()
+        }
+        VersionSource::Git => {
+            let tag = Git::new(Some(&dir.to_path_buf()))?.latest_tag("HEAD", &config.tag_prefix)?;
+            version_from_tag(&tag, &config.tag_prefix)
+        }
+    }
+}
+
+/// The version of a git tag with the given prefix, e.g.: "1.2.3" for "v1.2.3" or
+/// "my-package@v1.2.3"
+fn version_from_tag(tag: &str, prefix: &str) -> Result<SemVer> {
+    let version = match tag.rsplit_once('@') {
+        Some((_, version)) => version,
+        None => tag,
+    };
+
+    version.strip_prefix(prefix).unwrap_or(version).parse()
 }
 
 impl SemVer {
@@ -60,61 +123,248 @@ impl SemVer {
     fn new_patch(&self) -> Self {
         Self::new(self.major, self.minor, self.patch + 1, None)
     }
+
+    /// Finalize a pre-release version, e.g.: "1.2.0-rc.2" becomes "1.2.0"
+    fn new_final(&self) -> Result<Self> {
+        match self.pre_release {
+            Some(_) => Ok(Self::new(self.major, self.minor, self.patch, None)),
+            None => Err(ChangelogError::InvalidVersion(format!(
+                "{} is not a pre-release version, there is nothing to finalize",
+                self.to_string().blue().bold()
+            ))),
+        }
+    }
+
+    /// Whether this version satisfies a requirement like "1.2.3", ">=1.2.0", "^1.2" or "~1.2.3".
+    /// A version without an operator requires that exact version.
+    pub fn satisfies(&self, requirement: &str) -> Result<bool> {
+        let requirement = requirement.trim();
+        let (operator, version) = match requirement.find(|c: char| c.is_ascii_digit()) {
+            Some(idx) => requirement.split_at(idx),
+            None => {
+                return Err(ChangelogError::InvalidVersion(format!(
+                    "Invalid version requirement: {}",
+                    requirement
+                )))
+            }
+        };
+
+        // Allow partial versions like "1.2" in requirements
+        let parts = version
+            .split('-')
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .count();
+        let version: SemVer = match parts {
+            1 => format!("{}.0.0", version).parse()?,
+            2 => format!("{}.0", version).parse()?,
+            _ => version.parse()?,
+        };
+
+        Ok(match operator.trim() {
+            "" | "=" => *self == version,
+            ">" => *self > version,
+            ">=" => *self >= version,
+            "<" => *self < version,
+            "<=" => *self <= version,
+            // Compatible with the left-most non-zero part
+            "^" => {
+                let upper = match (version.major, version.minor) {
+                    (0, 0) if parts == 3 => version.new_patch(),
+                    (0, _) if parts > 1 => version.new_minor(),
+                    _ => version.new_major(),
+                };
+                *self >= version && *self < upper
+            }
+            // Only patch changes, or minor changes when no minor version is given
+            "~" => {
+                let upper = match parts {
+                    1 => version.new_major(),
+                    _ => version.new_minor(),
+                };
+                *self >= version && *self < upper
+            }
+            operator => {
+                return Err(ChangelogError::InvalidVersion(format!(
+                    "Invalid version requirement operator: {}",
+                    operator
+                )))
+            }
+        })
+    }
 }
 
+impl Ord for SemVer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre_release, &other.pre_release) {
+                (None, None) => Ordering::Equal,
+                // A pre-release version has a lower precedence than a normal version
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => {
+                    let mut a = a.split('.');
+                    let mut b = b.split('.');
+
+                    loop {
+                        match (a.next(), b.next()) {
+                            (None, None) => return Ordering::Equal,
+                            (None, Some(_)) => return Ordering::Less,
+                            (Some(_), None) => return Ordering::Greater,
+                            (Some(a), Some(b)) => {
+                                let ordering = match (a.parse::<u64>(), b.parse::<u64>()) {
+                                    (Ok(a), Ok(b)) => a.cmp(&b),
+                                    // Numeric identifiers have a lower precedence
+                                    (Ok(_), Err(_)) => Ordering::Less,
+                                    (Err(_), Ok(_)) => Ordering::Greater,
+                                    (Err(_), Err(_)) => a.cmp(b),
+                                };
+
+                                if ordering != Ordering::Equal {
+                                    return ordering;
+                                }
+                            }
+                        }
+                    }
+                }
+            })
+    }
+}
+
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Versions that only differ in their build metadata have the same precedence, so they are equal
+impl PartialEq for SemVer {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SemVer {}
+
 impl Display for SemVer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+
         if let Some(pre_release) = &self.pre_release {
-            write!(
-                f,
-                "{}.{}.{}-{}",
-                self.major, self.minor, self.patch, pre_release
-            )
-        } else {
-            write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+            write!(f, "-{}", pre_release)?;
+        }
+
+        match &self.build {
+            Some(build) => write!(f, "+{}", build),
+            None => Ok(()),
         }
     }
 }
 
 impl FromStr for SemVer {
-    type Err = Error;
+    type Err = ChangelogError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "major" => Ok(PackageJSON::from_current_directory()?.version.new_major()),
             "minor" => Ok(PackageJSON::from_current_directory()?.version.new_minor()),
             "patch" => Ok(PackageJSON::from_current_directory()?.version.new_patch()),
+            "final" | "release" => PackageJSON::from_current_directory()?.version.new_final(),
             "infer" => Ok(PackageJSON::from_current_directory()?.version),
-            _ => {
-                let (s, pre_release) = match s.split_once('-') {
-                    Some((s, pre_release)) => (s, Some(pre_release.to_owned())),
-                    None => (s, None),
+            version => {
+                let (s, build) = match version.split_once('+') {
+                    Some((s, build)) => (s, Some(parse_identifiers(version, build, "build")?)),
+                    None => (version, None),
                 };
-                let mut parts = s.split('.');
-
-                let (major, minor, patch) = match (parts.next(), parts.next(), parts.next()) {
-                    (Some(major), Some(minor), Some(patch)) => (
-                        major.parse::<u64>()?,
-                        minor.parse::<u64>()?,
-                        patch.parse::<u64>()?,
+                let (s, pre_release) = match s.split_once('-') {
+                    Some((s, pre_release)) => (
+                        s,
+                        Some(parse_identifiers(version, pre_release, "pre-release")?),
                     ),
-                    (None, _, _) => {
-                        return Err(eyre!("{} version is missing", "major".blue().bold()))
-                    }
-                    (_, None, _) => {
-                        return Err(eyre!("{} version is missing", "minor".blue().bold()))
-                    }
-                    (_, _, None) => {
-                        return Err(eyre!("{} version is missing", "patch".blue().bold()))
-                    }
+                    None => (s, None),
                 };
 
-                Ok(Self::new(major, minor, patch, pre_release))
+                let parts: Vec<_> = s.split('.').collect();
+                let numbers = parts
+                    .iter()
+                    .zip(["major", "minor", "patch"])
+                    .map(|(number, part)| parse_number(version, number, part))
+                    .collect::<Result<Vec<_>>>()?;
+
+                match numbers[..] {
+                    [major, minor, patch] if parts.len() == 3 => Ok(Self {
+                        build,
+                        ..Self::new(major, minor, patch, pre_release)
+                    }),
+                    [_, _, _] => Err(invalid_version(
+                        version,
+                        format!("it has {} parts instead of 3", parts.len()),
+                    )),
+                    _ => Err(invalid_version(
+                        version,
+                        format!(
+                            "the {} version is missing",
+                            ["major", "minor", "patch"][numbers.len()].blue().bold()
+                        ),
+                    )),
+                }
             }
         }
     }
 }
 
+fn invalid_version(version: &str, problem: String) -> ChangelogError {
+    ChangelogError::InvalidVersion(format!(
+        "Invalid version {}, {}. Expected MAJOR.MINOR.PATCH, e.g.: 1.2.3 or 1.2.3-rc.1",
+        version.blue().bold(),
+        problem
+    ))
+}
+
+fn parse_number(version: &str, number: &str, part: &str) -> Result<u64> {
+    if number.len() > 1 && number.starts_with('0') {
+        return Err(invalid_version(
+            version,
+            format!(
+                "the {} version {} has a leading zero",
+                part.blue().bold(),
+                number
+            ),
+        ));
+    }
+
+    number.parse::<u64>().map_err(|e| {
+        invalid_version(
+            version,
+            format!("the {} version {:?} {}", part.blue().bold(), number, e),
+        )
+    })
+}
+
+/// Parse the dot separated identifiers of a pre-release version or build metadata, e.g.: "rc.1"
+fn parse_identifiers(version: &str, identifiers: &str, kind: &str) -> Result<String> {
+    let valid = identifiers.split('.').all(|identifier| {
+        !identifier.is_empty()
+            && identifier
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+
+    match valid {
+        true => Ok(identifiers.to_string()),
+        false => Err(invalid_version(
+            version,
+            format!(
+                "the {} {:?} should be dot separated letters, digits and hyphens",
+                kind.blue().bold(),
+                identifiers
+            ),
+        )),
+    }
+}
+
 impl<'de> Deserialize<'de> for SemVer {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
@@ -125,6 +375,144 @@ impl<'de> Deserialize<'de> for SemVer {
     }
 }
 
+/// Calendar Versioning: https://calver.org, e.g.: "2024.01" or "2024.01.15". The version is
+/// displayed as written, "2024.1.5" stays "2024.1.5", and compared by its date.
+#[derive(Debug, Clone)]
+pub struct CalVer {
+    year: u64,
+    month: u64,
+    day: Option<u64>,
+    text: String,
+}
+
+impl CalVer {
+    fn date(&self) -> (u64, u64, Option<u64>) {
+        (self.year, self.month, self.day)
+    }
+}
+
+impl PartialEq for CalVer {
+    fn eq(&self, other: &Self) -> bool {
+        self.date() == other.date()
+    }
+}
+
+impl Eq for CalVer {}
+
+impl PartialOrd for CalVer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CalVer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.date().cmp(&other.date())
+    }
+}
+
+impl Display for CalVer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl FromStr for CalVer {
+    type Err = ChangelogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ChangelogError::InvalidVersion(format!("Invalid calendar version: {}", s));
+        let parts: Vec<_> = s.split('.').collect();
+
+        let (year, month, day) = match parts[..] {
+            [year, month] => (year, month, None),
+            [year, month, day] => (year, month, Some(day)),
+            _ => return Err(invalid()),
+        };
+
+        // Only year-like majors are calendar versions, "1000.1.1" is a semver version
+        if year.len() != 4 {
+            return Err(invalid());
+        }
+
+        let year = year
+            .parse::<u64>()
+            .ok()
+            .filter(|year| (1900..=2999).contains(year))
+            .ok_or_else(invalid)?;
+        let month = month
+            .parse::<u64>()
+            .ok()
+            .filter(|month| (1..=12).contains(month))
+            .ok_or_else(invalid)?;
+        let day = match day {
+            Some(day) => Some(
+                day.parse::<u64>()
+                    .ok()
+                    .filter(|day| (1..=31).contains(day))
+                    .ok_or_else(invalid)?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            year,
+            month,
+            day,
+            text: s.to_string(),
+        })
+    }
+}
+
+/// The version of a release in a changelog, calendar versions like "2024.01" are detected before
+/// semver versions. Versions of different schemes are not comparable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Version {
+    CalVer(CalVer),
+    SemVer(SemVer),
+}
+
+impl Version {
+    /// Resolve a release version, explicit calendar versions are used as-is and everything else
+    /// is resolved as a semver version
+    pub fn resolve(version: &str, config: &Config, dir: &Path) -> Result<Self> {
+        match version.parse::<CalVer>() {
+            Ok(calver) => Ok(Version::CalVer(calver)),
+            Err(_) => SemVer::resolve(version, config, dir).map(Version::SemVer),
+        }
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Version::CalVer(calver) => write!(f, "{}", calver),
+            Version::SemVer(semver) => write!(f, "{}", semver),
+        }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Version::CalVer(a), Version::CalVer(b)) => Some(a.cmp(b)),
+            (Version::SemVer(a), Version::SemVer(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = ChangelogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<CalVer>() {
+            Ok(calver) => Ok(Version::CalVer(calver)),
+            Err(_) => s.parse::<SemVer>().map(Version::SemVer),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PackageJSON {
     // Meta data
@@ -137,6 +525,12 @@ pub struct PackageJSON {
     name: String,
     version: SemVer,
     workspaces: Option<Vec<String>>,
+    #[serde(default)]
+    dependencies: HashMap<String, String>,
+    #[serde(default, rename = "devDependencies")]
+    dev_dependencies: HashMap<String, String>,
+    #[serde(default, rename = "peerDependencies")]
+    peer_dependencies: HashMap<String, String>,
 }
 
 impl PackageJSON {
@@ -148,7 +542,7 @@ impl PackageJSON {
                 pkg.pwd = dir.to_path_buf();
                 pkg
             })
-            .map_err(|e| eyre!(e))
+            .map_err(|e| ChangelogError::ParseError(e.to_string()))
     }
 
     pub fn from_root(dir: &Path) -> Result<Self> {
@@ -159,7 +553,7 @@ impl PackageJSON {
                 pkg.pwd = dir.to_path_buf();
                 pkg
             })
-            .map_err(|e| eyre!(e))
+            .map_err(|e| ChangelogError::ParseError(e.to_string()))
             .map(|mut root| {
                 root.is_root = true;
                 root
@@ -190,6 +584,14 @@ impl PackageJSON {
         self.is_root
     }
 
+    /// Whether this package depends on the given package, as a dependency, dev dependency or peer
+    /// dependency
+    pub fn depends_on(&self, name: &str) -> bool {
+        self.dependencies.contains_key(name)
+            || self.dev_dependencies.contains_key(name)
+            || self.peer_dependencies.contains_key(name)
+    }
+
     pub fn version_mut(&mut self) -> &mut SemVer {
         &mut self.version
     }
@@ -206,8 +608,8 @@ impl PackageJSON {
         if let Some(workspaces) = &self.workspaces {
             for workspace_glob in workspaces {
                 packages.extend(
-                    glob(base.join(workspace_glob).to_str().unwrap())
-                        .expect("Failed to read glob pattern")
+                    glob(&base.join(workspace_glob).to_string_lossy())
+                        .map_err(|e| ChangelogError::ParseError(e.to_string()))?
                         .flatten()
                         .filter(|path| path.is_dir())
                         .filter_map(|path| PackageJSON::from_directory(&path).ok()),
@@ -218,3 +620,188 @@ impl PackageJSON {
         Ok(packages)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_read_the_version_from_a_version_file() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::write(dir.join("VERSION"), "v1.2.3\n").unwrap();
+
+        let config = Config {
+            version_source: VersionSource::File,
+            ..Default::default()
+        };
+        let version = SemVer::resolve("minor", &config, &dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(version.unwrap(), SemVer::new(1, 3, 0, None));
+    }
+
+    #[test]
+    fn it_should_read_the_version_of_a_git_tag() {
+        assert_eq!(
+            version_from_tag("v1.2.3", "v").unwrap(),
+            SemVer::new(1, 2, 3, None)
+        );
+        assert_eq!(
+            version_from_tag("my-package@v1.2.3-rc.1", "v").unwrap(),
+            SemVer::new(1, 2, 3, Some("rc.1".to_string()))
+        );
+        assert_eq!(
+            version_from_tag("release-1.2.3", "release-").unwrap(),
+            SemVer::new(1, 2, 3, None)
+        );
+        assert!(version_from_tag("latest", "v").is_err());
+    }
+
+    #[test]
+    fn it_should_order_calendar_versions_chronologically() {
+        let versions: Vec<Version> = ["2023.12", "2024.01", "2024.01.15", "2024.02"]
+            .iter()
+            .map(|v| v.parse().unwrap())
+            .collect();
+
+        for pair in versions.windows(2) {
+            assert!(pair[0] < pair[1], "{:?} < {:?}", pair[0], pair[1]);
+        }
+
+        assert_eq!(
+            "2024.1.5".parse::<CalVer>().unwrap().to_string(),
+            "2024.1.5".to_string()
+        );
+        assert_eq!(
+            "2024.1".parse::<CalVer>().unwrap(),
+            "2024.01".parse::<CalVer>().unwrap()
+        );
+        assert!(matches!(
+            "1000.1.1".parse::<Version>().unwrap(),
+            Version::SemVer(_)
+        ));
+        assert!(matches!(
+            "2024.0.1".parse::<Version>().unwrap(),
+            Version::SemVer(_)
+        ));
+        assert!(matches!(
+            "1.2.3".parse::<Version>().unwrap(),
+            Version::SemVer(_)
+        ));
+        assert!("2024.13".parse::<CalVer>().is_err());
+        assert_eq!(
+            "2024.01"
+                .parse::<Version>()
+                .unwrap()
+                .partial_cmp(&"1.2.3".parse().unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn it_should_order_versions_by_precedence() {
+        let versions: Vec<SemVer> = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.1.0",
+            "2.0.0",
+        ]
+        .iter()
+        .map(|version| version.parse().unwrap())
+        .collect();
+
+        for pair in versions.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn it_should_reject_invalid_versions() {
+        assert!(matches!(
+            "1.2".parse::<SemVer>(),
+            Err(ChangelogError::InvalidVersion(_))
+        ));
+        assert!(matches!(
+            "1.x.3".parse::<SemVer>(),
+            Err(ChangelogError::InvalidVersion(_))
+        ));
+
+        for version in [
+            "1.2.3.4",
+            "01.2.3",
+            "1.2.3-",
+            "1.2.3-rc..1",
+            "1.2.3+",
+            "1..3",
+        ] {
+            assert!(
+                matches!(
+                    version.parse::<SemVer>(),
+                    Err(ChangelogError::InvalidVersion(_))
+                ),
+                "{} should be invalid",
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_parse_build_metadata() {
+        let version: SemVer = "1.2.3-rc.1+build.5".parse().unwrap();
+
+        assert_eq!(version.to_string(), "1.2.3-rc.1+build.5");
+        assert!(version.satisfies("1.2.3-rc.1").unwrap());
+        assert_eq!(version.new_final().unwrap().to_string(), "1.2.3");
+
+        // Build metadata is ignored for precedence, and for equality
+        let other: SemVer = "1.2.3-rc.1+build.6".parse().unwrap();
+        assert_eq!(version, other);
+        assert_eq!(version.cmp(&other), Ordering::Equal);
+        assert_ne!(version, "1.2.3-rc.2+build.5".parse().unwrap());
+    }
+
+    #[test]
+    fn it_should_verify_release_versions() {
+        assert!(SemVer::verify("patch").is_ok());
+        assert!(SemVer::verify("1.2.3").is_ok());
+        assert!(SemVer::verify("1.2").is_err());
+        assert!(SemVer::verify("2024.01").is_err());
+    }
+
+    #[test]
+    fn it_should_check_version_requirements() {
+        let version: SemVer = "1.2.3".parse().unwrap();
+
+        assert!(version.satisfies("1.2.3").unwrap());
+        assert!(!version.satisfies("=1.2.4").unwrap());
+        assert!(version.satisfies(">=1.2").unwrap());
+        assert!(!version.satisfies("<1.2.3").unwrap());
+        assert!(version.satisfies("^1.0").unwrap());
+        assert!(!version.satisfies("^2").unwrap());
+        assert!(version.satisfies("~1.2.0").unwrap());
+        assert!(!version.satisfies("~1.1").unwrap());
+        assert!(!"0.2.0"
+            .parse::<SemVer>()
+            .unwrap()
+            .satisfies("^0.1")
+            .unwrap());
+        assert!(version.satisfies("latest").is_err());
+    }
+
+    #[test]
+    fn it_should_finalize_a_pre_release_version() {
+        let mut version: SemVer = "1.2.0-rc.2".parse().unwrap();
+        assert_eq!(version.change_to("final").unwrap().to_string(), "1.2.0");
+
+        // The version is not a pre-release anymore
+        assert!(version.change_to("release").is_err());
+    }
+}
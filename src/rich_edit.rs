@@ -12,7 +12,7 @@ pub fn rich_edit(contents: Option<&str>) -> Option<String> {
     dir.push(&file_name);
     let file_path = dir.to_str().unwrap();
 
-    std::fs::write(file_path, contents.unwrap_or("")).unwrap();
+    std::fs::write(file_path, contents.unwrap_or("")).ok()?;
 
     let result = match std::process::Command::new(editor.unwrap())
         .arg(file_path)
@@ -29,7 +29,7 @@ pub fn rich_edit(contents: Option<&str>) -> Option<String> {
     };
 
     // Cleanup
-    std::fs::remove_file(file_path).unwrap();
+    std::fs::remove_file(file_path).ok();
 
     result
 }
@@ -0,0 +1,156 @@
+use crate::error::{ChangelogError, Result};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+/// Runs user defined shell commands, like the release hooks
+#[derive(Debug)]
+pub struct Shell {
+    pwd: PathBuf,
+    env: Vec<(String, String)>,
+}
+
+impl Shell {
+    pub fn new(pwd: &Path) -> Self {
+        Self {
+            pwd: pwd.to_path_buf(),
+            env: vec![],
+        }
+    }
+
+    /// Expose an environment variable to the commands
+    pub fn env(mut self, key: &str, value: &str) -> Self {
+        self.env.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    fn command(&self, command: &str) -> Command {
+        let mut cmd = Command::new("sh");
+
+        cmd.current_dir(&self.pwd).arg("-c").arg(command);
+
+        for (key, value) in &self.env {
+            cmd.env(key, value);
+        }
+
+        cmd
+    }
+
+    /// Run a command with `sh -c`, fails when the command exits with a non-zero status
+    pub fn run(&self, command: &str) -> Result<String> {
+        let mut cmd = self.command(command);
+
+        let start = Instant::now();
+        let output = cmd
+            .output()
+            .map_err(|e| ChangelogError::Command(format!("Failed running `{}`: {}", command, e)))?;
+
+        tracing::debug!(
+            command,
+            status = ?output.status.code(),
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "sh"
+        );
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(ChangelogError::Command(format!(
+                "`{}` failed: {}",
+                command,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+
+    /// Run a command with `sh -c` and stream its output to stderr, so that it doesn't mix with
+    /// the output of the cli on stdout. Fails when the command exits with a non-zero status.
+    pub fn stream(&self, command: &str) -> Result<()> {
+        let mut cmd = self.command(command);
+        cmd.stdout(Stdio::from(io::stderr()));
+
+        let start = Instant::now();
+        let status = cmd
+            .status()
+            .map_err(|e| ChangelogError::Command(format!("Failed running `{}`: {}", command, e)))?;
+
+        tracing::debug!(
+            command,
+            status = ?status.code(),
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "sh"
+        );
+
+        match status.success() {
+            true => Ok(()),
+            false => Err(ChangelogError::Command(format!(
+                "`{}` failed with {}",
+                command, status
+            ))),
+        }
+    }
+}
+
+/// Expand the `${VAR}` references to environment variables in a text, e.g.: in a message that is
+/// composed in CI. A `${VAR:-fallback}` reference falls back to the given text when the variable
+/// isn't set, other unset variables fail.
+pub fn expand_env(text: &str) -> Result<String> {
+    expand_vars(text, |name| std::env::var(name).ok())
+}
+
+fn expand_vars(text: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut result = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+
+        let reference = &rest[start + 2..];
+        let end = reference.find('}').ok_or_else(|| {
+            ChangelogError::ParseError(format!("Missing a closing `}}` in: {}", &rest[start..]))
+        })?;
+
+        let (name, fallback) = match reference[..end].split_once(":-") {
+            Some((name, fallback)) => (name, Some(fallback)),
+            None => (&reference[..end], None),
+        };
+
+        match (lookup(name), fallback) {
+            (Some(value), _) => result.push_str(&value),
+            (None, Some(fallback)) => result.push_str(fallback),
+            (None, None) => {
+                return Err(ChangelogError::NotFound(format!(
+                    "Environment variable {} is not set, use ${{{}:-<fallback>}} for a default",
+                    name, name
+                )))
+            }
+        }
+
+        rest = &reference[end + 1..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_expand_environment_variables() {
+        let lookup = |name: &str| (name == "VERSION").then(|| "1.2.3".to_string());
+
+        assert_eq!(
+            expand_vars("Bump to ${VERSION} in $HOME", lookup).unwrap(),
+            "Bump to 1.2.3 in $HOME"
+        );
+        assert_eq!(
+            expand_vars("Built by ${CI_JOB:-hand}", lookup).unwrap(),
+            "Built by hand"
+        );
+        assert!(expand_vars("Built by ${CI_JOB}", lookup).is_err());
+        assert!(expand_vars("Built by ${CI_JOB", lookup).is_err());
+    }
+}